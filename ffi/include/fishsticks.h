@@ -0,0 +1,116 @@
+/*
+ * C interface of fishsticks, a gamepad input library.
+ *
+ * Link against the shared or static library built from the fishsticks-ffi crate.
+ *
+ * Gamepads are identified by the integer form of their id, as listed by
+ * fishsticks_context_gamepads, and inputs by the values of fishsticks_button and
+ * fishsticks_axis. Functions that can fail return 0 on success and -1 on failure.
+ */
+
+#ifndef FISHSTICKS_H
+#define FISHSTICKS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A gamepad context, created with fishsticks_context_create. */
+typedef struct fishsticks_context fishsticks_context;
+
+/* The buttons of a gamepad, named by their position on a standard layout. */
+enum fishsticks_button {
+    FISHSTICKS_BUTTON_SOUTH = 0,
+    FISHSTICKS_BUTTON_EAST = 1,
+    FISHSTICKS_BUTTON_WEST = 2,
+    FISHSTICKS_BUTTON_NORTH = 3,
+    FISHSTICKS_BUTTON_SELECT = 4,
+    FISHSTICKS_BUTTON_START = 5,
+    FISHSTICKS_BUTTON_GUIDE = 6,
+    FISHSTICKS_BUTTON_LEFT_STICK = 7,
+    FISHSTICKS_BUTTON_RIGHT_STICK = 8,
+    FISHSTICKS_BUTTON_LEFT_SHOULDER = 9,
+    FISHSTICKS_BUTTON_RIGHT_SHOULDER = 10,
+    FISHSTICKS_BUTTON_LEFT_TRIGGER = 11,
+    FISHSTICKS_BUTTON_RIGHT_TRIGGER = 12,
+    FISHSTICKS_BUTTON_DPAD_UP = 13,
+    FISHSTICKS_BUTTON_DPAD_DOWN = 14,
+    FISHSTICKS_BUTTON_DPAD_LEFT = 15,
+    FISHSTICKS_BUTTON_DPAD_RIGHT = 16,
+    FISHSTICKS_BUTTON_MISC = 17,
+    FISHSTICKS_BUTTON_PADDLE1 = 18,
+    FISHSTICKS_BUTTON_PADDLE2 = 19,
+    FISHSTICKS_BUTTON_PADDLE3 = 20,
+    FISHSTICKS_BUTTON_PADDLE4 = 21,
+    FISHSTICKS_BUTTON_TOUCHPAD = 22,
+    FISHSTICKS_BUTTON_C = 23,
+    FISHSTICKS_BUTTON_Z = 24,
+};
+
+/*
+ * The axes of a gamepad. The vertical axes of the sticks are positive downwards, and the
+ * triggers range from 0.0 when released.
+ */
+enum fishsticks_axis {
+    FISHSTICKS_AXIS_LEFT_X = 0,
+    FISHSTICKS_AXIS_LEFT_Y = 1,
+    FISHSTICKS_AXIS_RIGHT_X = 2,
+    FISHSTICKS_AXIS_RIGHT_Y = 3,
+    FISHSTICKS_AXIS_TRIGGER_LEFT = 4,
+    FISHSTICKS_AXIS_TRIGGER_RIGHT = 5,
+};
+
+/* Creates a gamepad context, or returns NULL if it could not be initialized. */
+fishsticks_context *fishsticks_context_create(void);
+
+/* Destroys a gamepad context. Does nothing if context is NULL. */
+void fishsticks_context_destroy(fishsticks_context *context);
+
+/* Updates the state of all gamepads. */
+int32_t fishsticks_context_update(fishsticks_context *context);
+
+/*
+ * Writes the ids of up to capacity connected gamepads to ids, and returns the total number of
+ * connected gamepads, which may exceed capacity.
+ */
+size_t fishsticks_context_gamepads(const fishsticks_context *context, size_t *ids,
+                                   size_t capacity);
+
+/* Checks if a button is activated. */
+bool fishsticks_button_activated(const fishsticks_context *context, size_t gamepad,
+                                 uint32_t button);
+
+/* Checks if a button has just been activated. */
+bool fishsticks_button_just_activated(const fishsticks_context *context, size_t gamepad,
+                                      uint32_t button);
+
+/* Checks if a button has just been deactivated. */
+bool fishsticks_button_just_deactivated(const fishsticks_context *context, size_t gamepad,
+                                        uint32_t button);
+
+/* Gets the value of an axis, with the analog deadzone applied. */
+float fishsticks_axis_value(const fishsticks_context *context, size_t gamepad, uint32_t axis);
+
+/* Gets the value of an axis, with the digital deadzone applied. */
+float fishsticks_axis_digital_value(const fishsticks_context *context, size_t gamepad,
+                                    uint32_t axis);
+
+/*
+ * Rumbles a gamepad for duration_ms milliseconds, with the strong and weak motors at
+ * intensities from 0.0 to 1.0.
+ */
+int32_t fishsticks_rumble(fishsticks_context *context, size_t gamepad, float strong, float weak,
+                          uint32_t duration_ms);
+
+/* Stops any rumble playing on a gamepad. */
+int32_t fishsticks_stop_rumble(fishsticks_context *context, size_t gamepad);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FISHSTICKS_H */
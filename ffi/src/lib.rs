@@ -0,0 +1,6 @@
+//! Builds the C ABI of fishsticks as a shared and a static library.
+//!
+//! The functions are those of [`fishsticks::ffi`], declared for C in `include/fishsticks.h`.
+//! Backends are chosen with the same features as for fishsticks itself.
+
+pub use fishsticks::ffi::*;
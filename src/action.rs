@@ -0,0 +1,432 @@
+//! Logical actions, such as "jump" or "move", bound to buttons and axes.
+//!
+//! An [`ActionMap`] holds the bindings of every action a game defines, usually as an enum.
+//! After the gamepads are updated each frame, [`ActionMap::update`] evaluates the bindings
+//! against one or more gamepads, and the game then queries its actions rather than specific
+//! inputs. Since the bindings are plain data, they can be changed at runtime to let players remap
+//! their controls.
+//!
+//! With the `serde` feature, the bindings of an action map can be saved and loaded in any format
+//! supported by `serde`, along with their action, which must then be serializable as well. The
+//! `toml` feature adds `ActionMap::to_toml` and `ActionMap::from_toml` for config files.
+//!
+//! Games mark the actions that are meant to be held, such as aiming, sprinting and crouching,
+//! with [`ActionMap::set_hold_actions`]. With [`ActionMap::set_sticky_hold`], an accessibility
+//! option that is saved along with the bindings, those actions become toggles: pressing one once
+//! latches it, and pressing it again releases it. [`ActionMap::set_hold_mode`] overrides this for
+//! single actions, and is saved with their bindings.
+
+use crate::state::{AnyInput, GamepadState};
+use crate::{Axis, Button};
+
+/// An input, or combination of inputs, that an action is bound to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Binding {
+    /// Every one of the buttons held at once. A single button is a combination of one.
+    Buttons {
+        /// The buttons.
+        buttons: Vec<Button>,
+        /// The value the binding gives an axis action while held, such as `-1.0` for moving
+        /// left with the d-pad.
+        value: f32,
+    },
+    /// An axis pushed past a threshold in one direction, such as a thumbstick pushed up.
+    AxisDirection {
+        /// The axis.
+        axis: Axis,
+        /// Whether the binding is for the positive direction of the axis.
+        positive: bool,
+        /// How far the axis must be pushed, after the analog deadzone, for the binding to be
+        /// pressed.
+        threshold: f32,
+    },
+    /// An axis as is, for analog actions. It counts as pressed while outside the digital
+    /// deadzone.
+    Axis {
+        /// The axis.
+        axis: Axis,
+        /// Whether the value of the axis is reversed.
+        inverted: bool,
+    },
+}
+
+impl Binding {
+    /// Binds a single button.
+    pub fn button(button: Button) -> Self {
+        Self::buttons(&[button], 1.0)
+    }
+
+    /// Binds a combination of buttons, held at once, which gives an axis action `value`.
+    pub fn buttons(buttons: &[Button], value: f32) -> Self {
+        Binding::Buttons {
+            buttons: buttons.to_vec(),
+            value,
+        }
+    }
+
+    /// Binds an axis as is.
+    pub fn axis(axis: Axis) -> Self {
+        Binding::Axis {
+            axis,
+            inverted: false,
+        }
+    }
+
+    /// Binds an axis pushed past `threshold` in one direction.
+    pub fn axis_direction(axis: Axis, positive: bool, threshold: f32) -> Self {
+        Binding::AxisDirection {
+            axis,
+            positive,
+            threshold,
+        }
+    }
+
+    /// Gets whether the binding is pressed, and the value it gives an axis action.
+    fn evaluate(&self, state: GamepadState<'_, Axis, Button>) -> (bool, f32) {
+        match *self {
+            Binding::Buttons { ref buttons, value } => {
+                let pressed = !buttons.is_empty() && buttons.iter().all(|&b| state.button(b));
+                (pressed, if pressed { value } else { 0.0 })
+            }
+            Binding::AxisDirection {
+                axis,
+                positive,
+                threshold,
+            } => {
+                let value = state.axis(axis);
+                let distance = if positive { value } else { -value };
+                let pressed = distance > 0.0 && distance >= threshold;
+                (pressed, if pressed { value } else { 0.0 })
+            }
+            Binding::Axis { axis, inverted } => {
+                let value = state.axis(axis);
+                let pressed = state.pressed(AnyInput::Axis(axis));
+                (pressed, if inverted { -value } else { value })
+            }
+        }
+    }
+}
+
+/// How an action behaves while its bindings are held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HoldMode {
+    /// The action is pressed while a binding is held.
+    Hold,
+    /// Pressing the action once latches it, and pressing it again releases it.
+    Toggle,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ActionState {
+    bindings: Vec<Binding>,
+    // Whether the game treats the action as held, which sticky hold turns into a toggle.
+    hold: bool,
+    // The mode chosen for the action alone, overriding sticky hold.
+    hold_mode: Option<HoldMode>,
+    pressed: bool,
+    was_pressed: bool,
+    // Whether any binding was pressed as of the latest update, before toggling.
+    bindings_pressed: bool,
+    latched: bool,
+    value: f32,
+}
+
+impl ActionState {
+    fn hold_mode(&self, sticky_hold: bool) -> HoldMode {
+        match self.hold_mode {
+            Some(mode) => mode,
+            None if sticky_hold && self.hold => HoldMode::Toggle,
+            None => HoldMode::Hold,
+        }
+    }
+
+    fn keeps_place(&self) -> bool {
+        self.hold || self.hold_mode.is_some()
+    }
+}
+
+/// The bindings of a set of actions, and their state as of the latest update.
+///
+/// Actions can be any `Copy` type that can be compared, typically a fieldless enum. Actions are
+/// kept in the order they were first bound, which is also the order they are saved in.
+#[derive(Debug, Clone)]
+pub struct ActionMap<A> {
+    actions: Vec<(A, ActionState)>,
+    sticky_hold: bool,
+}
+
+impl<A> ActionMap<A>
+where
+    A: Copy + PartialEq,
+{
+    /// Creates a map with no actions bound.
+    pub fn new() -> Self {
+        Self {
+            actions: Vec::new(),
+            sticky_hold: false,
+        }
+    }
+
+    /// Adds a binding to an action, keeping its existing bindings.
+    pub fn bind(&mut self, action: A, binding: Binding) {
+        self.entry(action).bindings.push(binding);
+    }
+
+    /// Replaces every binding of an action, such as when a player remaps it.
+    pub fn set_bindings(&mut self, action: A, bindings: Vec<Binding>) {
+        self.entry(action).bindings = bindings;
+    }
+
+    /// Removes every binding of an action, returning them.
+    ///
+    /// An action that is a hold action or has a hold mode of its own keeps its place, so that it
+    /// behaves the same when bound again.
+    pub fn unbind(&mut self, action: A) -> Vec<Binding> {
+        match self.actions.iter().position(|(other, _)| *other == action) {
+            Some(index) if self.actions[index].1.keeps_place() => {
+                core::mem::take(&mut self.actions[index].1.bindings)
+            }
+            Some(index) => self.actions.remove(index).1.bindings,
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the bindings of an action.
+    pub fn bindings(&self, action: A) -> &[Binding] {
+        self.get(action).map_or(&[], |state| &state.bindings)
+    }
+
+    /// Sets which actions the game treats as held, such as those for aiming, sprinting and
+    /// crouching.
+    ///
+    /// These are the actions affected by [`set_sticky_hold`](Self::set_sticky_hold).
+    pub fn set_hold_actions(&mut self, actions: impl IntoIterator<Item = A>) {
+        for (_, state) in &mut self.actions {
+            state.hold = false;
+        }
+        for action in actions {
+            self.entry(action).hold = true;
+        }
+    }
+
+    /// Checks if every hold action is turned into a toggle.
+    pub fn sticky_hold(&self) -> bool {
+        self.sticky_hold
+    }
+
+    /// Turns every hold action into a toggle: pressing it once latches it, and pressing it
+    /// again releases it.
+    ///
+    /// The hold actions are set with [`set_hold_actions`](Self::set_hold_actions). Actions
+    /// latched when sticky hold is turned off are released on the next update.
+    pub fn set_sticky_hold(&mut self, sticky_hold: bool) {
+        self.sticky_hold = sticky_hold;
+    }
+
+    /// Gets how an action behaves while its bindings are held.
+    ///
+    /// This is the mode set with [`set_hold_mode`](Self::set_hold_mode), or, failing that,
+    /// [`HoldMode::Toggle`] for hold actions while [sticky hold](Self::set_sticky_hold) is on
+    /// and [`HoldMode::Hold`] otherwise.
+    pub fn hold_mode(&self, action: A) -> HoldMode {
+        self.get(action)
+            .map_or(HoldMode::Hold, |state| state.hold_mode(self.sticky_hold))
+    }
+
+    /// Sets how a single action behaves while its bindings are held, regardless of sticky hold,
+    /// or restores the default if `None`.
+    ///
+    /// The mode is saved along with the action's bindings. An action latched when it stops
+    /// being a toggle is released on the next update.
+    pub fn set_hold_mode(&mut self, action: A, mode: Option<HoldMode>) {
+        self.entry(action).hold_mode = mode;
+    }
+
+    /// Iterates over every action and its bindings.
+    pub fn actions(&self) -> impl Iterator<Item = (A, &[Binding])> {
+        self.actions
+            .iter()
+            .map(|(action, state)| (*action, state.bindings.as_slice()))
+    }
+
+    /// Evaluates every binding against the given gamepads.
+    ///
+    /// An action is pressed if any of its bindings is pressed on any of the gamepads, or, for a
+    /// toggled hold action, from one press to the next. Its axis value is the value furthest from the
+    /// center among all of its bindings. Call this once per frame, after
+    /// [`GamepadContext::update`](crate::GamepadContext::update); the `just_*` queries compare
+    /// against the previous call.
+    pub fn update<'a>(&mut self, states: impl IntoIterator<Item = GamepadState<'a, Axis, Button>>) {
+        let mut pressed = vec![false; self.actions.len()];
+        for (_, action) in &mut self.actions {
+            action.was_pressed = action.pressed;
+            action.value = 0.0;
+        }
+
+        for state in states {
+            for ((_, action), pressed) in self.actions.iter_mut().zip(&mut pressed) {
+                for binding in &action.bindings {
+                    let (binding_pressed, value) = binding.evaluate(state);
+                    *pressed |= binding_pressed;
+                    if value.abs() > action.value.abs() {
+                        action.value = value;
+                    }
+                }
+            }
+        }
+
+        for ((_, action), pressed) in self.actions.iter_mut().zip(pressed) {
+            if action.hold_mode(self.sticky_hold) == HoldMode::Toggle {
+                if pressed && !action.bindings_pressed {
+                    action.latched = !action.latched;
+                }
+                action.pressed = action.latched;
+            } else {
+                action.latched = false;
+                action.pressed = pressed;
+            }
+            action.bindings_pressed = pressed;
+        }
+    }
+
+    /// Checks if an action is pressed.
+    pub fn pressed(&self, action: A) -> bool {
+        self.get(action).is_some_and(|state| state.pressed)
+    }
+
+    /// Checks if an action was pressed during the latest update.
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.get(action)
+            .is_some_and(|state| state.pressed && !state.was_pressed)
+    }
+
+    /// Checks if an action was released during the latest update.
+    pub fn just_released(&self, action: A) -> bool {
+        self.get(action)
+            .is_some_and(|state| !state.pressed && state.was_pressed)
+    }
+
+    /// Gets the axis value of an action, typically in `[-1.0, 1.0]`.
+    ///
+    /// Buttons give the value they were bound with while held, and axes give their value after
+    /// the analog deadzone.
+    pub fn axis(&self, action: A) -> f32 {
+        self.get(action).map_or(0.0, |state| state.value)
+    }
+
+    fn get(&self, action: A) -> Option<&ActionState> {
+        self.actions
+            .iter()
+            .find(|(other, _)| *other == action)
+            .map(|(_, state)| state)
+    }
+
+    fn entry(&mut self, action: A) -> &mut ActionState {
+        match self.actions.iter().position(|(other, _)| *other == action) {
+            Some(index) => &mut self.actions[index].1,
+            None => {
+                self.actions.push((action, ActionState::default()));
+                let index = self.actions.len() - 1;
+                &mut self.actions[index].1
+            }
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl<A> ActionMap<A>
+where
+    A: Copy + PartialEq + serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Saves the bindings as TOML.
+    pub fn to_toml(&self) -> crate::error::Result<String> {
+        toml::to_string(self).map_err(|error| {
+            crate::error::Error::Invalid(format!("failed to save bindings: {}", error))
+        })
+    }
+
+    /// Loads bindings saved with [`to_toml`](Self::to_toml).
+    pub fn from_toml(text: &str) -> crate::error::Result<Self> {
+        toml::from_str(text).map_err(|error| {
+            crate::error::Error::Invalid(format!("failed to load bindings: {}", error))
+        })
+    }
+}
+
+/// The saved form of an [`ActionMap`], which leaves out the state of each action.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedActions<T> {
+    // Before the actions, since TOML cannot have plain values after an array of tables.
+    #[serde(default)]
+    sticky_hold: bool,
+    actions: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedAction<A, B> {
+    action: A,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hold_mode: Option<HoldMode>,
+    bindings: B,
+}
+
+#[cfg(feature = "serde")]
+impl<A> serde::Serialize for ActionMap<A>
+where
+    A: Copy + PartialEq + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let actions = self
+            .actions
+            .iter()
+            .map(|(action, state)| SavedAction {
+                action: *action,
+                hold_mode: state.hold_mode,
+                bindings: state.bindings.as_slice(),
+            })
+            .collect();
+        SavedActions {
+            sticky_hold: self.sticky_hold,
+            actions,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, A> serde::Deserialize<'de> for ActionMap<A>
+where
+    A: Copy + PartialEq + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = SavedActions::<SavedAction<A, Vec<Binding>>>::deserialize(deserializer)?;
+        let mut map = Self::new();
+        map.sticky_hold = saved.sticky_hold;
+        for SavedAction {
+            action,
+            hold_mode,
+            bindings,
+        } in saved.actions
+        {
+            let state = map.entry(action);
+            state.bindings = bindings;
+            state.hold_mode = hold_mode;
+        }
+        Ok(map)
+    }
+}
+
+impl<A> Default for ActionMap<A>
+where
+    A: Copy + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
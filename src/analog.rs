@@ -1,7 +1,11 @@
 //! Generic analog input support.
 
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use crate::input::{Input, InputSet};
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
 
 /// The minimum value of an analog input.
 pub const ANALOG_MIN: f32 = -1.0;
@@ -13,7 +17,7 @@ pub const ANALOG_MAX: f32 = 1.0;
 pub(crate) struct AnalogInputValue(f32);
 
 impl AnalogInputValue {
-    fn get(&self) -> f32 {
+    pub(crate) fn get(&self) -> f32 {
         self.0
     }
 }
@@ -51,41 +55,467 @@ impl From<AnalogInputValue> for Deadzone {
     }
 }
 
+/// Applies a deadzone to a value, remapping values outside it to the full range if `rescale` is
+/// set, or leaving them as they are otherwise.
+pub(crate) fn remap(value: f32, deadzone: f32, rescale: bool) -> f32 {
+    if value.abs() < deadzone {
+        return 0.0;
+    }
+    if !rescale {
+        return value;
+    }
+
+    let remapped_value = (value.abs() - deadzone) / (ANALOG_MAX - deadzone);
+    value.signum() * remapped_value
+}
+
+/// Which side of a deadzone an analog input is on.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum Activation {
+    #[default]
+    Inactive,
+    Negative,
+    Positive,
+}
+
+impl Activation {
+    fn new(value: AnalogInputValue, deadzone: Deadzone) -> Self {
+        if Deadzone::from(value) < deadzone {
+            Self::Inactive
+        } else if value.get().is_sign_negative() {
+            Self::Negative
+        } else {
+            Self::Positive
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        *self != Self::Inactive
+    }
+}
+
+/// The maximum horizon for analog extrapolation.
+pub const MAX_EXTRAPOLATION: Duration = Duration::from_millis(100);
+
+/// A single reading of an analog input.
+///
+/// Times are measured from an arbitrary, backend-specific epoch.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Sample {
+    value: AnalogInputValue,
+    time: Duration,
+}
+
+impl Sample {
+    pub(crate) fn new(value: AnalogInputValue, time: Duration) -> Self {
+        Self { value, time }
+    }
+}
+
+/// The last two samples of an analog input, along with its activation state
+/// relative to both deadzones.
+#[derive(Default, Debug, Clone, Copy)]
+struct AnalogState {
+    sample: Sample,
+    previous: Sample,
+    // When the value last changed, or `None` if it never did.
+    changed_at: Option<Duration>,
+    // The value, after the deadzone and response curve, as of the end of the previous update.
+    previous_value: f32,
+    activation: Activation,
+    digital_activation: Activation,
+}
+
+impl AnalogState {
+    /// Gets the rate of change between the last two samples, in units per second.
+    fn velocity(&self) -> Option<f32> {
+        let elapsed = self.sample.time.checked_sub(self.previous.time)?;
+        if elapsed.is_zero() {
+            None
+        } else {
+            let delta = self.sample.value.get() - self.previous.value.get();
+            Some(delta / elapsed.as_secs_f32())
+        }
+    }
+}
+
+/// How consecutive motion events for the same analog input are handled within an update.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Coalescing {
+    /// Every motion event is applied as it arrives.
+    #[default]
+    Disabled,
+    /// Motion events are merged until the end of the update, keeping only the first, most
+    /// extreme and last values, which are then applied in that order.
+    ///
+    /// Digital inputs are never coalesced.
+    Enabled,
+}
+
+/// Filtering of analog inputs against noise, such as from worn potentiometers that make aim
+/// reticles jitter.
+///
+/// Smoothing runs once per update, after calibration and before the deadzone, so smoothed
+/// inputs change at the time of the update rather than of the backend events. Steadier values
+/// follow the input more slowly.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Smoothing {
+    /// Values are used as read.
+    #[default]
+    Disabled,
+    /// An exponential low-pass filter with the given time constant, after which the value has
+    /// covered about two thirds of a change.
+    LowPass(Duration),
+    /// The average of the values read at the given number of most recent updates.
+    MovingAverage(usize),
+}
+
+/// The state of the smoothing of an analog input.
+#[derive(Default, Debug, Clone)]
+struct Smoother {
+    // The latest value read, which the smoothed value follows.
+    target: f32,
+    // The values at the most recent updates, for a moving average.
+    history: VecDeque<f32>,
+}
+
+/// The distance from its target at which a low-pass filtered value snaps to it, so that it
+/// stops changing.
+const SMOOTHING_EPSILON: f32 = 1e-4;
+
+/// Motion events for an analog input that have been merged during the current update.
+#[derive(Debug, Clone, Copy)]
+struct PendingMotion {
+    first: Sample,
+    extreme: Sample,
+    last: Sample,
+}
+
+impl PendingMotion {
+    fn new(sample: Sample) -> Self {
+        Self {
+            first: sample,
+            extreme: sample,
+            last: sample,
+        }
+    }
+
+    fn merge(&mut self, sample: Sample) {
+        if sample.value.get().abs() > self.extreme.value.get().abs() {
+            self.extreme = sample;
+        }
+        self.last = sample;
+    }
+}
+
+/// Corrects the resting position and range of a worn or off-center analog input.
+///
+/// Values are mapped so that `center` becomes `0.0`, `min` becomes `ANALOG_MIN` and `max`
+/// becomes `ANALOG_MAX`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisCalibration {
+    /// The value the input rests at.
+    pub center: f32,
+    /// The lowest value the input reaches.
+    pub min: f32,
+    /// The highest value the input reaches.
+    pub max: f32,
+}
+
+impl AxisCalibration {
+    /// Applies the calibration to a value.
+    ///
+    /// Inputs that rest at one end of their range, such as triggers, have `center` equal to
+    /// `min`, and are mapped to the range `[0.0, ANALOG_MAX]`.
+    pub fn apply(&self, value: f32) -> f32 {
+        let offset = value - self.center;
+        let range = if offset >= 0.0 {
+            self.max - self.center
+        } else {
+            self.center - self.min
+        };
+
+        if range > f32::EPSILON {
+            (offset / range).clamp(ANALOG_MIN, ANALOG_MAX)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Shapes the value of an analog input after the deadzone, trading precision near the center
+/// for speed near the edge or the other way around.
+///
+/// Curves work on the distance from the center, so the sign of the value is kept, and their
+/// result is clamped to `[0.0, ANALOG_MAX]`.
+#[derive(Clone, Default)]
+pub enum ResponseCurve {
+    /// The value as is.
+    #[default]
+    Linear,
+    /// The value raised to a power. Exponents above `1.0` give finer control near the center,
+    /// such as `2.0` for a squared curve, and exponents below `1.0` give the opposite.
+    Power(f32),
+    /// A custom function of the distance from the center, from `[0.0, ANALOG_MAX]`.
+    Custom(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+}
+
+impl ResponseCurve {
+    /// Creates a custom curve from a function.
+    pub fn custom(curve: impl Fn(f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(curve))
+    }
+
+    /// Applies the curve to a value.
+    pub fn apply(&self, value: f32) -> f32 {
+        let distance = value.abs();
+        let shaped = match self {
+            Self::Linear => return value,
+            Self::Power(exponent) => libm::powf(distance, *exponent),
+            Self::Custom(curve) => curve(distance),
+        };
+        if shaped.is_finite() {
+            value.signum() * shaped.clamp(0.0, ANALOG_MAX)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Debug for ResponseCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Linear => f.write_str("Linear"),
+            Self::Power(exponent) => f.debug_tuple("Power").field(exponent).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
 /// Container for analog inputs.
 #[derive(Debug)]
 pub struct AnalogInput<T> {
-    inputs: HashMap<T, AnalogInputValue>,
+    inputs: Vec<AnalogState>,
+    pending: Vec<Option<(T, PendingMotion)>>,
+    calibration: Vec<Option<AxisCalibration>>,
+    response_curves: Vec<ResponseCurve>,
+    coalescing: Coalescing,
+    smoothing: Smoothing,
+    smoothers: Vec<Smoother>,
+    // When the inputs were last smoothed.
+    smoothed_at: Option<Duration>,
+    extrapolation: Option<Duration>,
+    time: Duration,
 
-    just_activated: HashSet<T>,
-    just_deactivated: HashSet<T>,
+    changed: InputSet<T>,
+    just_activated: InputSet<T>,
+    just_deactivated: InputSet<T>,
     deadzone: Deadzone,
+    deadzone_rescaling: bool,
+    // Deadzones overriding `deadzone` and `digital_deadzone` for single inputs.
+    input_deadzones: Vec<Option<Deadzone>>,
+    input_digital_deadzones: Vec<Option<Deadzone>>,
 
-    just_activated_digital: HashSet<T>,
-    just_deactivated_digital: HashSet<T>,
+    just_activated_digital: InputSet<T>,
+    just_deactivated_digital: InputSet<T>,
     digital_deadzone: Deadzone,
 }
 
 impl<T> AnalogInput<T>
 where
-    T: Hash + Eq,
+    T: Input,
 {
     /// Gets the value of an analog input.
     ///
     /// Returns `0.0` if the input is within the analog deadzone, or if it has not been read yet.
+    /// Outside the deadzone, the rest of the range is rescaled, so the value grows smoothly from
+    /// `0.0` at the edge of the deadzone to `ANALOG_MAX` at full deflection rather than jumping
+    /// to the size of the deadzone. With rescaling turned off through
+    /// [`set_deadzone_rescaling`](crate::GamepadContext::set_deadzone_rescaling), values outside
+    /// the deadzone are returned unchanged instead.
     pub fn value(&self, input: T) -> f32 {
-        match self.inputs.get(&input) {
-            Some(&value) if Deadzone::from(value) >= self.deadzone => {
-                let deadzone = self.deadzone.get();
-                let remapped_value = (value.get().abs() - deadzone) / (ANALOG_MAX - deadzone);
-                value.get().signum() * remapped_value
-            }
+        match self.inputs.get(input.index()) {
+            Some(state) if state.activation.is_active() => self.remap(input, state.sample.value),
             _ => 0.0,
         }
     }
 
+    /// Gets the raw value of an analog input, before the deadzone is applied.
+    ///
+    /// Returns `0.0` if the input has not been read yet.
+    pub fn raw_value(&self, input: T) -> f32 {
+        match self.inputs.get(input.index()) {
+            Some(state) => state.sample.value.get(),
+            None => 0.0,
+        }
+    }
+
+    /// Gets when the raw value of an analog input last changed, or `None` if it never did.
+    ///
+    /// Times are those of the backend events where available, on the clock of
+    /// [`GamepadContext::time`](crate::GamepadContext::time), so they can be compared with it to
+    /// measure input latency.
+    pub fn last_changed(&self, input: T) -> Option<Duration> {
+        self.inputs.get(input.index())?.changed_at
+    }
+
+    /// Gets how far the value of an analog input moved since the previous update, such as for
+    /// flick gestures or camera acceleration.
+    ///
+    /// The change is that of [`value`](Self::value), so it includes the deadzone and response
+    /// curve.
+    pub fn delta(&self, input: T) -> f32 {
+        match self.inputs.get(input.index()) {
+            Some(state) => self.value(input) - state.previous_value,
+            None => 0.0,
+        }
+    }
+
+    /// Gets the analog deadzone.
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone.get()
+    }
+
+    /// Gets the digital deadzone.
+    pub fn digital_deadzone(&self) -> f32 {
+        self.digital_deadzone.get()
+    }
+
+    /// Gets the analog deadzone of a single analog input, which is [`deadzone`](Self::deadzone)
+    /// unless overridden for that input.
+    pub fn input_deadzone(&self, input: T) -> f32 {
+        self.analog_deadzone_of(input).get()
+    }
+
+    /// Gets the digital deadzone of a single analog input, which is
+    /// [`digital_deadzone`](Self::digital_deadzone) unless overridden for that input.
+    pub fn input_digital_deadzone(&self, input: T) -> f32 {
+        self.digital_deadzone_of(input).get()
+    }
+
+    fn analog_deadzone_of(&self, input: T) -> Deadzone {
+        match self.input_deadzones.get(input.index()) {
+            Some(&Some(deadzone)) => deadzone,
+            _ => self.deadzone,
+        }
+    }
+
+    fn digital_deadzone_of(&self, input: T) -> Deadzone {
+        match self.input_digital_deadzones.get(input.index()) {
+            Some(&Some(deadzone)) => deadzone,
+            _ => self.digital_deadzone,
+        }
+    }
+
+    /// Gets the calibration of an analog input, if it has one.
+    pub fn calibration(&self, input: T) -> Option<AxisCalibration> {
+        self.calibration.get(input.index()).copied().flatten()
+    }
+
+    /// Gets the response curve of an analog input.
+    pub fn response_curve(&self, input: T) -> &ResponseCurve {
+        static LINEAR: ResponseCurve = ResponseCurve::Linear;
+        self.response_curves.get(input.index()).unwrap_or(&LINEAR)
+    }
+
+    /// Gets how analog inputs are smoothed.
+    pub fn smoothing(&self) -> Smoothing {
+        self.smoothing
+    }
+
+    /// Checks if values outside the analog deadzone are rescaled to start at `0.0`.
+    pub fn deadzone_rescaling(&self) -> bool {
+        self.deadzone_rescaling
+    }
+
+    /// Gets the extrapolation horizon, or `None` if extrapolation is disabled.
+    pub fn extrapolation(&self) -> Option<Duration> {
+        self.extrapolation
+    }
+
+    /// Gets how consecutive motion events are handled within an update.
+    pub fn coalescing(&self) -> Coalescing {
+        self.coalescing
+    }
+
+    /// Gets the value of an analog input, extrapolated from its recent velocity to the time of
+    /// the last update.
+    ///
+    /// The extrapolation only applies within the horizon set with
+    /// [`set_extrapolation`](crate::GamepadContext::set_extrapolation) after the latest sample.
+    /// Backends only report changes, so once the horizon has passed without a new sample the
+    /// input is assumed to be still and this returns the same as [`value`](Self::value). The
+    /// result is clamped to the range `[ANALOG_MIN, ANALOG_MAX]` before the deadzone is applied.
+    /// Returns the same as [`value`](Self::value) if extrapolation is disabled.
+    pub fn predicted_value(&self, input: T) -> f32 {
+        let state = match self.inputs.get(input.index()) {
+            Some(state) => state,
+            None => return 0.0,
+        };
+
+        match (self.extrapolation, state.velocity()) {
+            (Some(horizon), Some(velocity)) => {
+                let elapsed = self.time.saturating_sub(state.sample.time);
+                if elapsed >= horizon {
+                    return self.value(input);
+                }
+                let value = state.sample.value.get() + velocity * elapsed.as_secs_f32();
+                self.remap(input, AnalogInputValue::from(value))
+            }
+            _ => self.value(input),
+        }
+    }
+
+    /// Gets the value of an analog input at the given time, linearly interpolated between its
+    /// last two samples.
+    ///
+    /// Times before the previous sample or after the latest sample return the value of that
+    /// sample. The time is measured on the same clock as [`GamepadContext::time`]; rendering
+    /// slightly in the past, such as one report interval behind, gives smooth motion when the
+    /// game renders faster than the gamepad reports.
+    ///
+    /// [`GamepadContext::time`]: crate::GamepadContext::time
+    pub fn interpolated_value(&self, input: T, time: Duration) -> f32 {
+        let state = match self.inputs.get(input.index()) {
+            Some(state) => state,
+            None => return 0.0,
+        };
+
+        let (previous, sample) = (state.previous, state.sample);
+        let value = if time <= previous.time {
+            previous.value
+        } else if time >= sample.time {
+            sample.value
+        } else {
+            let t =
+                (time - previous.time).as_secs_f32() / (sample.time - previous.time).as_secs_f32();
+            let value = previous.value.get() + (sample.value.get() - previous.value.get()) * t;
+            AnalogInputValue::from(value)
+        };
+        self.remap(input, value)
+    }
+
+    /// Remaps a value outside the analog deadzone to the full range of an analog input, then
+    /// applies its response curve.
+    fn remap(&self, input: T, value: AnalogInputValue) -> f32 {
+        let deadzone = self.analog_deadzone_of(input).get();
+        let value = remap(value.get(), deadzone, self.deadzone_rescaling);
+        self.response_curve(input).apply(value)
+    }
+
+    /// Iterates over the analog inputs whose raw value changed since the last update.
+    ///
+    /// This only visits inputs that changed, so it costs next to nothing when none did.
+    pub fn changed(&self) -> impl Iterator<Item = T> {
+        self.changed.iter()
+    }
+
     /// Checks if an analog input just left the analog deadzone.
     pub fn just_activated(&self, input: T) -> Option<f32> {
-        if self.just_activated.contains(&input) {
+        if self.just_activated.contains(input) {
             Some(self.value(input))
         } else {
             None
@@ -94,7 +524,7 @@ where
 
     /// Checks if an analog input just entered the analog deadzone.
     pub fn just_deactivated(&self, input: T) -> bool {
-        self.just_deactivated.contains(&input)
+        self.just_deactivated.contains(input)
     }
 
     /// Converts an analog input to a digital value.
@@ -102,11 +532,12 @@ where
     /// Returns either `ANALOG_MIN` or `ANALOG_MAX` when a nonzero input is outside
     /// the digital deadzone, and `0.0` otherwise.
     pub fn digital_value(&self, input: T) -> f32 {
-        match self.inputs.get(&input) {
-            Some(&value) if Deadzone::from(value) >= self.digital_deadzone => {
-                if value.get() < 0.0 {
+        match self.inputs.get(input.index()) {
+            Some(state) if state.digital_activation.is_active() => {
+                let value = state.sample.value.get();
+                if value < 0.0 {
                     ANALOG_MIN
-                } else if value.get() > 0.0 {
+                } else if value > 0.0 {
                     ANALOG_MAX
                 } else {
                     0.0
@@ -118,7 +549,7 @@ where
 
     /// Checks if an analog input just left the digital deadzone.
     pub fn just_activated_digital(&self, input: T) -> Option<f32> {
-        if self.just_activated_digital.contains(&input) {
+        if self.just_activated_digital.contains(input) {
             Some(self.digital_value(input))
         } else {
             None
@@ -127,92 +558,371 @@ where
 
     /// Checks if an analog input just entered the digital deadzone.
     pub fn just_deactivated_digital(&self, input: T) -> bool {
-        self.just_deactivated_digital.contains(&input)
+        self.just_deactivated_digital.contains(input)
     }
 }
 
 impl<T> AnalogInput<T>
 where
-    T: Hash + Copy + Eq,
+    T: Input,
 {
-    pub(crate) fn set(&mut self, input: T, value: AnalogInputValue) {
-        let old_value = self.inputs.insert(input, value);
-        let value = value.get();
-        let deadzone = self.deadzone.get();
-        let digital_deadzone = self.digital_deadzone.get();
-
-        if let Some(old_value) = old_value {
-            let old_value = old_value.get();
-
-            if value.abs() < deadzone {
-                self.just_activated.remove(&input);
-                if old_value.abs() >= deadzone {
-                    self.just_deactivated.insert(input);
+    pub(crate) fn set(&mut self, input: T, mut sample: Sample) {
+        if let Some(calibration) = self.calibration(input) {
+            let value = calibration.apply(sample.value.get());
+            sample.value = AnalogInputValue::from(value);
+        }
+
+        if self.smoothing != Smoothing::Disabled {
+            self.smoothers[input.index()].target = sample.value.get();
+            return;
+        }
+
+        match self.coalescing {
+            Coalescing::Disabled => self.apply(input, sample),
+            Coalescing::Enabled => match &mut self.pending[input.index()] {
+                Some((_, pending)) => pending.merge(sample),
+                pending => *pending = Some((input, PendingMotion::new(sample))),
+            },
+        }
+    }
+
+    /// Applies any motion events that were merged during the current update.
+    pub(crate) fn flush(&mut self) {
+        for index in 0..self.pending.len() {
+            if let Some((input, pending)) = self.pending[index].take() {
+                self.apply(input, pending.first);
+                if pending.extreme != pending.first && pending.extreme != pending.last {
+                    self.apply(input, pending.extreme);
                 }
-            } else {
-                self.just_deactivated.remove(&input);
-                // It is possible for an analog input to completely pass through the deadzone
-                // between updates. In that case, both the old and new values would exceed the
-                // deadzone, but they would have opposite signs.
-                if old_value.abs() < deadzone || value.signum() != old_value.signum() {
-                    self.just_activated.insert(input);
+                if pending.last != pending.first {
+                    self.apply(input, pending.last);
                 }
             }
+        }
+        self.smooth();
+    }
 
-            if value.abs() < digital_deadzone {
-                self.just_activated_digital.remove(&input);
-                if old_value.abs() >= digital_deadzone {
-                    self.just_deactivated_digital.insert(input);
+    /// Moves every smoothed input towards its latest value, as of the time of the update.
+    fn smooth(&mut self) {
+        let elapsed = self
+            .time
+            .saturating_sub(self.smoothed_at.unwrap_or(self.time));
+        self.smoothed_at = Some(self.time);
+
+        for &input in T::ALL {
+            let current = self.inputs[input.index()].sample.value.get();
+            let smoother = &mut self.smoothers[input.index()];
+            let target = smoother.target;
+            let value = match self.smoothing {
+                Smoothing::Disabled => return,
+                Smoothing::LowPass(constant) if constant.is_zero() => target,
+                Smoothing::LowPass(constant) => {
+                    let ratio = elapsed.as_secs_f32() / constant.as_secs_f32();
+                    let value = current + (target - current) * (1.0 - libm::expf(-ratio));
+                    if (target - value).abs() < SMOOTHING_EPSILON {
+                        target
+                    } else {
+                        value
+                    }
                 }
-            } else {
-                self.just_deactivated_digital.remove(&input);
-                if old_value.abs() < digital_deadzone || value.signum() != old_value.signum() {
-                    self.just_activated_digital.insert(input);
+                Smoothing::MovingAverage(updates) => {
+                    smoother.history.push_back(target);
+                    while smoother.history.len() > updates.max(1) {
+                        smoother.history.pop_front();
+                    }
+                    smoother.history.iter().sum::<f32>() / smoother.history.len() as f32
                 }
+            };
+            if value != current {
+                let sample = Sample::new(AnalogInputValue::from(value), self.time);
+                self.apply(input, sample);
             }
+        }
+    }
+
+    fn apply(&mut self, input: T, sample: Sample) {
+        let deadzone = self.analog_deadzone_of(input);
+        let digital_deadzone = self.digital_deadzone_of(input);
+        let state = &mut self.inputs[input.index()];
+        let old_state = *state;
+        let value = sample.value;
+
+        state.previous = state.sample;
+        state.sample = sample;
+        state.activation = Activation::new(value, deadzone);
+        state.digital_activation = Activation::new(value, digital_deadzone);
+        let new_state = *state;
+
+        if new_state.sample.value != old_state.sample.value {
+            state.changed_at = Some(sample.time);
+            self.changed.insert(input);
+        }
+
+        // Most reports only move an input within the same zone, so the change sets are only
+        // touched on an actual transition. Note that an analog input can completely pass through
+        // the deadzone between updates, which is a transition from one sign to the other.
+        if new_state.activation != old_state.activation {
+            Self::transition(
+                input,
+                new_state.activation,
+                &mut self.just_activated,
+                &mut self.just_deactivated,
+            );
+        }
+
+        if new_state.digital_activation != old_state.digital_activation {
+            Self::transition(
+                input,
+                new_state.digital_activation,
+                &mut self.just_activated_digital,
+                &mut self.just_deactivated_digital,
+            );
+        }
+    }
+
+    fn transition(
+        input: T,
+        activation: Activation,
+        just_activated: &mut InputSet<T>,
+        just_deactivated: &mut InputSet<T>,
+    ) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            input = input.index(),
+            ?activation,
+            "analog input changed zone"
+        );
+
+        if activation.is_active() {
+            just_activated.insert(input);
+            just_deactivated.remove(input);
         } else {
-            if value.abs() >= deadzone {
-                self.just_activated.insert(input);
-                self.just_deactivated.remove(&input);
-            }
-            if value.abs() >= digital_deadzone {
-                self.just_activated_digital.insert(input);
-                self.just_deactivated_digital.remove(&input);
-            }
+            just_activated.remove(input);
+            just_deactivated.insert(input);
+        }
+    }
+
+    pub(crate) fn update(&mut self, time: Duration) {
+        for &input in T::ALL {
+            let value = self.value(input);
+            self.inputs[input.index()].previous_value = value;
         }
+        self.time = time;
+        self.clear();
     }
 
-    pub(crate) fn update(&mut self) {
+    pub(crate) fn clear(&mut self) {
+        self.changed.clear();
         self.just_activated.clear();
         self.just_deactivated.clear();
         self.just_activated_digital.clear();
         self.just_deactivated_digital.clear();
     }
 
+    /// Copies the state of `other`, while keeping any changes that have already been recorded.
+    pub(crate) fn accumulate(&mut self, other: &Self) {
+        self.inputs.clone_from(&other.inputs);
+        self.coalescing = other.coalescing;
+        self.smoothing = other.smoothing;
+        self.extrapolation = other.extrapolation;
+        self.time = other.time;
+        self.deadzone = other.deadzone;
+        self.deadzone_rescaling = other.deadzone_rescaling;
+        self.digital_deadzone = other.digital_deadzone;
+        self.input_deadzones.clone_from(&other.input_deadzones);
+        self.input_digital_deadzones
+            .clone_from(&other.input_digital_deadzones);
+        self.response_curves.clone_from(&other.response_curves);
+
+        self.changed.union(&other.changed);
+        self.just_activated.union(&other.just_activated);
+        self.just_deactivated.union(&other.just_deactivated);
+        self.just_activated_digital
+            .union(&other.just_activated_digital);
+        self.just_deactivated_digital
+            .union(&other.just_deactivated_digital);
+    }
+
+    pub(crate) fn set_coalescing(&mut self, coalescing: Coalescing) {
+        self.flush();
+        self.coalescing = coalescing;
+    }
+
+    /// Changes the smoothing, restarting it from the current values. Inputs that were being
+    /// smoothed jump to their latest values when smoothing is disabled.
+    pub(crate) fn set_smoothing(&mut self, smoothing: Smoothing) {
+        if self.smoothing != Smoothing::Disabled && smoothing == Smoothing::Disabled {
+            for &input in T::ALL {
+                let target = self.smoothers[input.index()].target;
+                if target != self.inputs[input.index()].sample.value.get() {
+                    self.apply(
+                        input,
+                        Sample::new(AnalogInputValue::from(target), self.time),
+                    );
+                }
+            }
+        }
+        for (smoother, state) in self.smoothers.iter_mut().zip(&self.inputs) {
+            smoother.target = state.sample.value.get();
+            smoother.history.clear();
+        }
+        self.smoothing = smoothing;
+        self.smoothed_at = None;
+    }
+
+    /// Copies the settings of single inputs, such as calibrations, deadzones and response
+    /// curves, from `other`.
+    pub(crate) fn copy_input_settings(&mut self, other: &Self) {
+        self.calibration.clone_from(&other.calibration);
+        self.response_curves.clone_from(&other.response_curves);
+        self.input_deadzones.clone_from(&other.input_deadzones);
+        self.input_digital_deadzones
+            .clone_from(&other.input_digital_deadzones);
+        self.refresh_activations();
+    }
+
+    /// Calibrates an analog input, affecting the samples read from then on.
+    pub(crate) fn set_calibration(&mut self, input: T, calibration: Option<AxisCalibration>) {
+        self.calibration[input.index()] = calibration;
+    }
+
+    pub(crate) fn set_response_curve(&mut self, input: T, curve: ResponseCurve) {
+        self.response_curves[input.index()] = curve;
+    }
+
+    pub(crate) fn set_extrapolation(&mut self, horizon: Option<Duration>) {
+        self.extrapolation = horizon.map(|horizon| horizon.min(MAX_EXTRAPOLATION));
+    }
+
     pub(crate) fn set_deadzone(&mut self, deadzone: Deadzone) {
         self.deadzone = deadzone;
+        self.refresh_activations();
+    }
+
+    pub(crate) fn set_deadzone_rescaling(&mut self, rescale: bool) {
+        self.deadzone_rescaling = rescale;
     }
 
     pub(crate) fn set_digital_deadzone(&mut self, deadzone: Deadzone) {
         self.digital_deadzone = deadzone;
+        self.refresh_activations();
+    }
+
+    pub(crate) fn set_input_deadzone(&mut self, input: T, deadzone: Option<Deadzone>) {
+        self.input_deadzones[input.index()] = deadzone;
+        self.refresh_activations();
+    }
+
+    pub(crate) fn set_input_digital_deadzone(&mut self, input: T, deadzone: Option<Deadzone>) {
+        self.input_digital_deadzones[input.index()] = deadzone;
+        self.refresh_activations();
+    }
+
+    /// Reclassifies every input after a deadzone changed, recording inputs that changed zone as
+    /// just activated or deactivated.
+    fn refresh_activations(&mut self) {
+        for &input in T::ALL {
+            let deadzone = self.analog_deadzone_of(input);
+            let digital_deadzone = self.digital_deadzone_of(input);
+            let state = &mut self.inputs[input.index()];
+            let old_state = *state;
+            state.activation = Activation::new(state.sample.value, deadzone);
+            state.digital_activation = Activation::new(state.sample.value, digital_deadzone);
+            let new_state = *state;
+
+            if new_state.activation != old_state.activation {
+                Self::transition(
+                    input,
+                    new_state.activation,
+                    &mut self.just_activated,
+                    &mut self.just_deactivated,
+                );
+            }
+            if new_state.digital_activation != old_state.digital_activation {
+                Self::transition(
+                    input,
+                    new_state.digital_activation,
+                    &mut self.just_activated_digital,
+                    &mut self.just_deactivated_digital,
+                );
+            }
+        }
+    }
+}
+
+impl<T: Clone> Clone for AnalogInput<T> {
+    fn clone(&self) -> Self {
+        let mut clone = Self {
+            inputs: Vec::new(),
+            pending: Vec::new(),
+            calibration: Vec::new(),
+            response_curves: Vec::new(),
+            smoothers: Vec::new(),
+            input_deadzones: Vec::new(),
+            input_digital_deadzones: Vec::new(),
+            ..*self
+        };
+        clone.clone_from(self);
+        clone
+    }
+
+    /// Reuses the storage of `self`, since snapshots copy inputs every frame.
+    fn clone_from(&mut self, source: &Self) {
+        self.inputs.clone_from(&source.inputs);
+        self.pending.clone_from(&source.pending);
+        self.calibration.clone_from(&source.calibration);
+        self.response_curves.clone_from(&source.response_curves);
+        self.coalescing = source.coalescing;
+        self.smoothing = source.smoothing;
+        self.smoothers.clone_from(&source.smoothers);
+        self.smoothed_at = source.smoothed_at;
+        self.extrapolation = source.extrapolation;
+        self.time = source.time;
+        self.changed = source.changed;
+        self.just_activated = source.just_activated;
+        self.just_deactivated = source.just_deactivated;
+        self.deadzone = source.deadzone;
+        self.deadzone_rescaling = source.deadzone_rescaling;
+        self.input_deadzones.clone_from(&source.input_deadzones);
+        self.input_digital_deadzones
+            .clone_from(&source.input_digital_deadzones);
+        self.just_activated_digital = source.just_activated_digital;
+        self.just_deactivated_digital = source.just_deactivated_digital;
+        self.digital_deadzone = source.digital_deadzone;
     }
 }
 
-impl<T> Default for AnalogInput<T> {
+impl<T> Default for AnalogInput<T>
+where
+    T: Input,
+{
     fn default() -> Self {
         Self {
-            inputs: Default::default(),
+            inputs: alloc::vec![AnalogState::default(); T::COUNT],
+            pending: alloc::vec![None; T::COUNT],
+            calibration: alloc::vec![None; T::COUNT],
+            response_curves: alloc::vec![ResponseCurve::Linear; T::COUNT],
+            coalescing: Coalescing::default(),
+            smoothing: Smoothing::default(),
+            smoothers: alloc::vec![Smoother::default(); T::COUNT],
+            smoothed_at: None,
+            extrapolation: None,
+            time: Duration::ZERO,
 
+            changed: Default::default(),
             just_activated: Default::default(),
             just_deactivated: Default::default(),
-            deadzone: DEFAULT_DEADZONE,
+            deadzone: Deadzone(DEFAULT_DEADZONE),
+            deadzone_rescaling: true,
+            input_deadzones: alloc::vec![None; T::COUNT],
+            input_digital_deadzones: alloc::vec![None; T::COUNT],
 
             just_activated_digital: Default::default(),
             just_deactivated_digital: Default::default(),
-            digital_deadzone: DEFAULT_DEADZONE_DIGITAL,
+            digital_deadzone: Deadzone(DEFAULT_DEADZONE_DIGITAL),
         }
     }
 }
 
-const DEFAULT_DEADZONE: Deadzone = Deadzone(0.1);
-const DEFAULT_DEADZONE_DIGITAL: Deadzone = Deadzone(0.5);
+pub(crate) const DEFAULT_DEADZONE: f32 = 0.1;
+pub(crate) const DEFAULT_DEADZONE_DIGITAL: f32 = 0.5;
@@ -0,0 +1,152 @@
+//! Contiguous storage of per-gamepad state.
+
+use crate::GamepadId;
+use std::slice;
+
+/// Per-gamepad values, stored contiguously in the order the gamepads connected.
+///
+/// Even with many gamepads connected, a linear scan over a few cache lines beats hashing every
+/// id, and iterating every gamepad each update touches contiguous memory only.
+#[derive(Debug)]
+pub(crate) struct GamepadMap<T> {
+    entries: Vec<(GamepadId, T)>,
+}
+
+impl<T> GamepadMap<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    fn position(&self, id: GamepadId) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|&(entry_id, _)| entry_id == id)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn contains_key(&self, id: GamepadId) -> bool {
+        self.position(id).is_some()
+    }
+
+    pub(crate) fn get(&self, id: GamepadId) -> Option<&T> {
+        self.entries
+            .iter()
+            .find(|&&(entry_id, _)| entry_id == id)
+            .map(|(_, value)| value)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: GamepadId) -> Option<&mut T> {
+        self.entries
+            .iter_mut()
+            .find(|(entry_id, _)| *entry_id == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Inserts a value, returning the one it replaced. A new gamepad is added at the end.
+    pub(crate) fn insert(&mut self, id: GamepadId, value: T) -> Option<T> {
+        match self.get_mut(id) {
+            Some(existing) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((id, value));
+                None
+            }
+        }
+    }
+
+    /// Removes a value, keeping the order of the remaining gamepads.
+    pub(crate) fn remove(&mut self, id: GamepadId) -> Option<T> {
+        let index = self.position(id)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(GamepadId, &mut T) -> bool) {
+        self.entries.retain_mut(|(id, value)| keep(*id, value));
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        Iter(self.entries.iter())
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.entries.iter_mut())
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+}
+
+impl<T> Default for GamepadMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for GamepadMap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+
+    /// Reuses the storage of values for gamepads present in both maps, so copying the state of
+    /// the same gamepads every frame does not allocate.
+    fn clone_from(&mut self, source: &Self) {
+        self.entries.truncate(source.entries.len());
+        for (index, (id, value)) in source.entries.iter().enumerate() {
+            match self.entries.get_mut(index) {
+                Some(entry) if entry.0 == *id => entry.1.clone_from(value),
+                Some(entry) => *entry = (*id, value.clone()),
+                None => self.entries.push((*id, value.clone())),
+            }
+        }
+    }
+}
+
+/// An iterator over the entries of a [`GamepadMap`].
+pub(crate) struct Iter<'a, T>(slice::Iter<'a, (GamepadId, T)>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (GamepadId, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(id, value)| (*id, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// A mutable iterator over the entries of a [`GamepadMap`].
+pub(crate) struct IterMut<'a, T>(slice::IterMut<'a, (GamepadId, T)>);
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (GamepadId, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(id, value)| (*id, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GamepadMap<T> {
+    type Item = (GamepadId, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,50 @@
+//! The axes of a gamepad, independent of the backend.
+
+use crate::input::Input;
+
+/// An analog axis, named by its place on a standard gamepad layout.
+///
+/// Every backend maps its own axes into this layout, with the vertical axes of the thumbsticks
+/// positive downwards and the triggers from `0.0` when released, so that a gamepad reads the
+/// same whichever backend reports it. The axes are named like those of SDL2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    /// The horizontal axis of the left thumbstick, positive to the right.
+    LeftX,
+    /// The vertical axis of the left thumbstick, positive downwards.
+    LeftY,
+    /// The horizontal axis of the right thumbstick, positive to the right.
+    RightX,
+    /// The vertical axis of the right thumbstick, positive downwards.
+    RightY,
+    /// The left trigger, from `0.0` when released.
+    TriggerLeft,
+    /// The right trigger, from `0.0` when released.
+    TriggerRight,
+}
+
+impl Axis {
+    /// Checks if the axis is a trigger, which rests at `0.0` rather than in the middle of its
+    /// range.
+    pub fn is_trigger(self) -> bool {
+        matches!(self, Axis::TriggerLeft | Axis::TriggerRight)
+    }
+}
+
+impl Input for Axis {
+    const COUNT: usize = Axis::TriggerRight as usize + 1;
+
+    const ALL: &'static [Self] = &[
+        Axis::LeftX,
+        Axis::LeftY,
+        Axis::RightX,
+        Axis::RightY,
+        Axis::TriggerLeft,
+        Axis::TriggerRight,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
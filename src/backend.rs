@@ -1,23 +1,839 @@
-cfg_if::cfg_if! {
-    if #[cfg(feature = "sdl2")] {
-        #[path = "backend/sdl2.rs"]
-        mod implementation;
-    } else if #[cfg(feature = "gilrs")] {
-        #[path = "backend/gilrs.rs"]
-        mod implementation;
-    } else {
-        #[path = "backend/dummy.rs"]
-        mod implementation;
+#[cfg(not(any(
+    all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+    feature = "gilrs-backend",
+    feature = "wasm-backend",
+    feature = "headless",
+    feature = "mock-backend"
+)))]
+#[path = "backend/dummy.rs"]
+mod dummy;
+#[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+#[path = "backend/gilrs.rs"]
+mod gilrs_backend;
+#[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+#[path = "backend/headless.rs"]
+mod headless;
+#[cfg(feature = "mock-backend")]
+#[path = "backend/mock.rs"]
+mod mock;
+#[cfg(all(
+    feature = "sdl2-backend",
+    not(target_arch = "wasm32"),
+    not(feature = "mock-backend")
+))]
+#[path = "backend/sdl2.rs"]
+mod sdl2_backend;
+#[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+#[path = "backend/wasm.rs"]
+mod wasm_backend;
+
+#[cfg(feature = "mock-backend")]
+pub use mock::MockBackend;
+#[cfg(all(
+    feature = "sdl2-backend",
+    not(target_arch = "wasm32"),
+    not(feature = "mock-backend")
+))]
+pub use sdl2_backend::OwnedImplementationGamepad;
+
+use crate::analog::AnalogInputValue;
+use crate::guid::Guid;
+use crate::input::Input;
+use crate::motion::MotionSensor;
+use crate::power::PowerInfo;
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Axis, Button, GamepadId};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// A raw event read from a backend, not yet applied to any gamepad.
+pub enum Event {
+    Connected {
+        id: GamepadId,
+        name: String,
+        guid: Guid,
+        owned_internal_gamepad: Option<OwnedImplementationGamepad>,
+        supports_rumble: bool,
+    },
+    Disconnected(GamepadId),
+    AxisMotion(GamepadId, Axis, AnalogInputValue),
+    ButtonDown(GamepadId, Button),
+    ButtonUp(GamepadId, Button),
+}
+
+/// Converts a rumble intensity in `[0.0, 1.0]` to a motor magnitude.
+#[cfg(all(
+    any(
+        all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+        feature = "gilrs-backend"
+    ),
+    not(feature = "mock-backend")
+))]
+pub(crate) fn rumble_magnitude(intensity: f32) -> u16 {
+    (intensity.clamp(0.0, 1.0) * f32::from(u16::MAX)) as u16
+}
+
+/// A gamepad opened by the backend and owned by the context, which only SDL2 has.
+#[cfg(not(all(
+    feature = "sdl2-backend",
+    not(target_arch = "wasm32"),
+    not(feature = "mock-backend")
+)))]
+pub enum OwnedImplementationGamepad {}
+
+/// A built-in backend, read by the context alongside any custom backends.
+pub trait NativeBackend {
+    /// Drains all pending events from the backend into `events`, along with the time each
+    /// event occurred.
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()>;
+
+    /// Gets the current time, measured from the same epoch as event times.
+    fn time(&self) -> Duration;
+}
+
+/// A built-in backend, for choosing between backends at runtime with
+/// [`GamepadContextBuilder::prefer`](crate::builder::GamepadContextBuilder::prefer).
+///
+/// Every backend enabled by the crate's features is compiled in, and the context opens the
+/// first of those listed that initializes. All of them report the same [`Axis`] and
+/// [`Button`] layout, so the same code works whichever one is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackendKind {
+    /// SDL2, enabled with the `sdl2-backend` feature on every target but WebAssembly.
+    Sdl,
+    /// gilrs, enabled with the `gilrs-backend` feature.
+    Gilrs,
+    /// The browser's Gamepad API, enabled with the `wasm-backend` feature.
+    Wasm,
+    /// No devices, enabled with the `headless` feature.
+    Headless,
+}
+
+impl BackendKind {
+    /// Every backend, in the order they are tried when none is listed.
+    pub const ALL: &'static [Self] = &[
+        BackendKind::Sdl,
+        BackendKind::Gilrs,
+        BackendKind::Wasm,
+        BackendKind::Headless,
+    ];
+
+    /// Gets the name of the backend, as accepted by the `FISHSTICKS_BACKEND` environment
+    /// variable.
+    pub fn name(self) -> &'static str {
+        match self {
+            BackendKind::Sdl => "sdl2",
+            BackendKind::Gilrs => "gilrs",
+            BackendKind::Wasm => "wasm",
+            BackendKind::Headless => "headless",
+        }
+    }
+
+    /// Finds a backend by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Checks if the backend is compiled in. The `mock-backend` feature stands in for every
+    /// backend.
+    pub fn is_compiled(self) -> bool {
+        cfg!(feature = "mock-backend")
+            || match self {
+                BackendKind::Sdl => {
+                    cfg!(all(feature = "sdl2-backend", not(target_arch = "wasm32")))
+                }
+                BackendKind::Gilrs => cfg!(feature = "gilrs-backend"),
+                BackendKind::Wasm => cfg!(feature = "wasm-backend"),
+                BackendKind::Headless => cfg!(feature = "headless"),
+            }
     }
 }
 
-pub use implementation::*;
+/// Gets every button one of the compiled backends can report.
+pub(crate) fn compiled_buttons() -> impl Iterator<Item = Button> {
+    let backends: &[&[Button]] = &[
+        #[cfg(feature = "mock-backend")]
+        mock::BUTTONS,
+        #[cfg(all(
+            feature = "sdl2-backend",
+            not(target_arch = "wasm32"),
+            not(feature = "mock-backend")
+        ))]
+        sdl2_backend::BUTTONS,
+        #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+        gilrs_backend::BUTTONS,
+        #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+        wasm_backend::BUTTONS,
+        #[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+        headless::BUTTONS,
+    ];
+    Button::ALL
+        .iter()
+        .copied()
+        .filter(move |button| backends.iter().any(|buttons| buttons.contains(button)))
+}
+
+/// Calls a method on whichever built-in backend a context opened.
+macro_rules! dispatch {
+    ($context:expr, $backend:ident => $call:expr) => {
+        match $context {
+            #[cfg(feature = "mock-backend")]
+            ImplementationContext::Mock($backend) => $call,
+            #[cfg(all(
+                feature = "sdl2-backend",
+                not(target_arch = "wasm32"),
+                not(feature = "mock-backend")
+            ))]
+            ImplementationContext::Sdl($backend) => $call,
+            #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+            ImplementationContext::Gilrs($backend) => $call,
+            #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+            ImplementationContext::Wasm($backend) => $call,
+            #[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+            ImplementationContext::Headless($backend) => $call,
+            #[cfg(not(any(
+                all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+                feature = "gilrs-backend",
+                feature = "wasm-backend",
+                feature = "headless",
+                feature = "mock-backend"
+            )))]
+            ImplementationContext::Dummy($backend) => $call,
+        }
+    };
+}
+
+/// The built-in backend a context reads gamepads from, one of those compiled in, opened when
+/// the context is built.
+///
+/// The `mock-backend` feature replaces every other backend, so that tests run the same whatever
+/// else is enabled.
+pub(crate) enum ImplementationContext {
+    #[cfg(feature = "mock-backend")]
+    Mock(MockBackend),
+    #[cfg(all(
+        feature = "sdl2-backend",
+        not(target_arch = "wasm32"),
+        not(feature = "mock-backend")
+    ))]
+    Sdl(sdl2_backend::ImplementationContext),
+    #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+    // gilrs keeps its state inline, which would make every other variant as large.
+    Gilrs(Box<gilrs_backend::ImplementationContext>),
+    #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+    Wasm(wasm_backend::ImplementationContext),
+    #[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+    Headless(headless::ImplementationContext),
+    #[cfg(not(any(
+        all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+        feature = "gilrs-backend",
+        feature = "wasm-backend",
+        feature = "headless",
+        feature = "mock-backend"
+    )))]
+    Dummy(dummy::ImplementationContext),
+}
+
+impl ImplementationContext {
+    /// Opens a built-in backend, failing if it is not compiled in or does not initialize.
+    ///
+    /// SDL2 uses `sdl_context` if it is given, rather than initializing SDL itself. The mock
+    /// backend opens whichever backend is asked for.
+    pub(crate) fn open(
+        kind: BackendKind,
+        mappings: Option<&str>,
+        #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))] sdl_context: Option<
+            &sdl2::Sdl,
+        >,
+    ) -> Result<Self> {
+        match kind {
+            #[cfg(all(
+                feature = "sdl2-backend",
+                not(target_arch = "wasm32"),
+                not(feature = "mock-backend")
+            ))]
+            BackendKind::Sdl => match sdl_context {
+                Some(sdl_context) => {
+                    sdl2_backend::ImplementationContext::with_sdl(sdl_context, mappings)
+                }
+                None => sdl2_backend::ImplementationContext::new(mappings),
+            }
+            .map(ImplementationContext::Sdl),
+            #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+            BackendKind::Gilrs => gilrs_backend::ImplementationContext::new(mappings)
+                .map(|backend| ImplementationContext::Gilrs(Box::new(backend))),
+            #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+            BackendKind::Wasm => {
+                wasm_backend::ImplementationContext::new(mappings).map(ImplementationContext::Wasm)
+            }
+            #[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+            BackendKind::Headless => {
+                headless::ImplementationContext::new(mappings).map(ImplementationContext::Headless)
+            }
+            #[cfg(feature = "mock-backend")]
+            _ => {
+                #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+                let _ = sdl_context;
+                MockBackend::new(mappings).map(ImplementationContext::Mock)
+            }
+            #[cfg(not(feature = "mock-backend"))]
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::BackendInit(format!(
+                "the {} backend is not compiled in",
+                kind.name()
+            ))),
+        }
+    }
+
+    /// Gets the backend that was opened, or `None` for the mock backend.
+    pub(crate) fn kind(&self) -> Option<BackendKind> {
+        match self {
+            #[cfg(feature = "mock-backend")]
+            ImplementationContext::Mock(_) => None,
+            #[cfg(all(
+                feature = "sdl2-backend",
+                not(target_arch = "wasm32"),
+                not(feature = "mock-backend")
+            ))]
+            ImplementationContext::Sdl(_) => Some(BackendKind::Sdl),
+            #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+            ImplementationContext::Gilrs(_) => Some(BackendKind::Gilrs),
+            #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+            ImplementationContext::Wasm(_) => Some(BackendKind::Wasm),
+            #[cfg(all(feature = "headless", not(feature = "mock-backend")))]
+            ImplementationContext::Headless(_) => Some(BackendKind::Headless),
+            #[cfg(not(any(
+                all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+                feature = "gilrs-backend",
+                feature = "wasm-backend",
+                feature = "headless",
+                feature = "mock-backend"
+            )))]
+            ImplementationContext::Dummy(_) => None,
+        }
+    }
+
+    /// Gets the button the backend reports for the capture or share button, if it has one.
+    ///
+    /// gilrs and the browser's standard mapping do not map it.
+    pub(crate) fn capture_button(&self) -> Option<Button> {
+        match self.kind() {
+            Some(BackendKind::Sdl) => Some(Button::Misc),
+            Some(_) => None,
+            None => cfg!(feature = "mock-backend").then_some(Button::Misc),
+        }
+    }
+
+    #[cfg(feature = "mock-backend")]
+    pub(crate) fn mock(&mut self) -> &mut MockBackend {
+        match self {
+            ImplementationContext::Mock(mock) => mock,
+        }
+    }
+
+    /// Handles an event read from an event pump owned by the application, which only SDL2
+    /// reads.
+    #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+    pub(crate) fn handle_sdl_event(&mut self, event: &sdl2::event::Event) {
+        match self {
+            #[cfg(not(feature = "mock-backend"))]
+            ImplementationContext::Sdl(backend) => backend.handle_event(event),
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = event;
+            }
+        }
+    }
+
+    pub(crate) fn load_mappings(&mut self, mappings: &str) -> Result<()> {
+        dispatch!(self, backend => backend.load_mappings(mappings))
+    }
+
+    pub(crate) fn add_mappings(&mut self, mappings: &str) -> Result<()> {
+        dispatch!(self, backend => backend.add_mappings(mappings))
+    }
+
+    pub(crate) fn rumble(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.rumble(id, gamepad, strong, weak, duration))
+    }
+
+    pub(crate) fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.stop_rumble(id, gamepad))
+    }
+
+    pub(crate) fn power_info(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> PowerInfo {
+        dispatch!(self, backend => backend.power_info(id, gamepad))
+    }
+
+    pub(crate) fn has_sensor(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+    ) -> bool {
+        dispatch!(self, backend => backend.has_sensor(id, gamepad, sensor))
+    }
+
+    pub(crate) fn set_sensor_enabled(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+        enabled: bool,
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.set_sensor_enabled(id, gamepad, sensor, enabled))
+    }
+
+    pub(crate) fn sensor_data(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        dispatch!(self, backend => backend.sensor_data(id, gamepad, sensor))
+    }
+
+    pub(crate) fn touchpad_count(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> usize {
+        dispatch!(self, backend => backend.touchpad_count(id, gamepad))
+    }
+
+    pub(crate) fn read_touchpad(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        touchpad: usize,
+        fingers: &mut Vec<TouchFinger>,
+    ) -> bool {
+        dispatch!(self, backend => backend.read_touchpad(id, gamepad, touchpad, fingers))
+    }
+
+    pub(crate) fn has_button(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        button: Button,
+    ) -> bool {
+        dispatch!(self, backend => backend.has_button(id, gamepad, button))
+    }
+
+    pub(crate) fn read_raw(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        buttons: &mut Vec<bool>,
+        axes: &mut Vec<f32>,
+    ) {
+        dispatch!(self, backend => backend.read_raw(id, gamepad, buttons, axes))
+    }
+
+    pub(crate) fn has_led(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        dispatch!(self, backend => backend.has_led(id, gamepad))
+    }
+
+    pub(crate) fn set_led(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        color: (u8, u8, u8),
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.set_led(id, gamepad, color))
+    }
 
-use crate::{Gamepad, GamepadId};
-use std::collections::HashMap;
+    pub(crate) fn set_player_index(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        index: Option<u8>,
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.set_player_index(id, gamepad, index))
+    }
 
-use crate::Result;
+    pub(crate) fn supports_trigger_effects(
+        &self,
+        id: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        dispatch!(self, backend => backend.supports_trigger_effects(id, gamepad))
+    }
 
+    pub(crate) fn set_trigger_effect(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Result<()> {
+        dispatch!(self, backend => backend.set_trigger_effect(id, gamepad, trigger, effect))
+    }
+}
+
+impl NativeBackend for ImplementationContext {
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()> {
+        dispatch!(self, backend => backend.poll(events))
+    }
+
+    fn time(&self) -> Duration {
+        dispatch!(self, backend => backend.time())
+    }
+}
+
+/// The integer a [`GamepadId`] of a custom backend converts to has this bit set, so that it does
+/// not clash with those of the built-in backend.
+const CUSTOM_INDEX: usize = 1 << 31;
+
+/// The id a built-in backend knows a gamepad by, tagged with the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ImplementationId {
+    /// The number given to each virtual gamepad, in the order they were connected.
+    #[cfg(feature = "mock-backend")]
+    Mock(u32),
+    /// The instance id SDL2 gives each opened device.
+    #[cfg(all(
+        feature = "sdl2-backend",
+        not(target_arch = "wasm32"),
+        not(feature = "mock-backend")
+    ))]
+    Sdl(u32),
+    #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+    Gilrs(gilrs::GamepadId),
+    /// The index of the gamepad in `navigator.getGamepads()`.
+    #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+    Wasm(u32),
+}
+
+/// Where a gamepad comes from, and the id it has there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum RawId {
+    // The headless backend has no gamepads of its own.
+    #[cfg_attr(feature = "headless", allow(dead_code))]
+    Native(ImplementationId),
+    Custom {
+        backend: u16,
+        device: u16,
+    },
+}
+
+impl GamepadId {
+    #[cfg_attr(feature = "headless", allow(dead_code))]
+    pub(crate) fn native(id: ImplementationId) -> Self {
+        Self(RawId::Native(id))
+    }
+}
+
+impl From<GamepadId> for usize {
+    fn from(id: GamepadId) -> Self {
+        match id.0 {
+            RawId::Native(id) => match id {
+                #[cfg(feature = "mock-backend")]
+                ImplementationId::Mock(id) => id as usize,
+                #[cfg(all(
+                    feature = "sdl2-backend",
+                    not(target_arch = "wasm32"),
+                    not(feature = "mock-backend")
+                ))]
+                ImplementationId::Sdl(id) => id as usize,
+                #[cfg(all(feature = "gilrs-backend", not(feature = "mock-backend")))]
+                ImplementationId::Gilrs(id) => id.into(),
+                #[cfg(all(feature = "wasm-backend", not(feature = "mock-backend")))]
+                ImplementationId::Wasm(id) => id as usize,
+            },
+            RawId::Custom { backend, device } => {
+                CUSTOM_INDEX | usize::from(backend) << 16 | usize::from(device)
+            }
+        }
+    }
+}
+
+/// A source of gamepad data implemented outside the crate, such as controllers streamed over
+/// the network, Steam Input or a console SDK.
+///
+/// Custom backends are added to a context with
+/// [`GamepadContext::add_backend`](crate::GamepadContext::add_backend), and run alongside the
+/// built-in backend. Each gamepad is identified by a device number of the backend's choosing,
+/// which the context turns into a [`GamepadId`] that cannot clash with those of other backends.
+/// Gamepads report the same [`Axis`] and [`Button`] layout as the built-in backends, so that
+/// every gamepad is read the same way.
 pub trait Backend {
-    fn update(&mut self, gamepads: &mut HashMap<GamepadId, Gamepad>) -> Result<()>;
+    /// Drains all pending events from the backend into `events`, along with the time each
+    /// event occurred.
+    fn poll(&mut self, events: &mut Vec<(Duration, BackendEvent)>) -> Result<()>;
+
+    /// Gets the current time, measured from the same epoch as event times.
+    ///
+    /// The epoch may differ from that of the built-in backend, since event times are moved onto
+    /// the clock of the context as they are read.
+    fn time(&self) -> Duration;
+
+    /// Rumbles a gamepad for `duration`, replacing any rumble still playing on it.
+    ///
+    /// Only called for gamepads that connected with `supports_rumble`. Fails by default.
+    fn rumble(&mut self, device: u16, strong: f32, weak: f32, duration: Duration) -> Result<()> {
+        let _ = (device, strong, weak, duration);
+        Err(Error::Unsupported(
+            "rumble is not supported by this backend".into(),
+        ))
+    }
+
+    /// Stops any rumble playing on a gamepad. Rumbles with no intensity by default.
+    fn stop_rumble(&mut self, device: u16) -> Result<()> {
+        self.rumble(device, 0.0, 0.0, Duration::ZERO)
+    }
+
+    /// Reads the battery of a gamepad. Reports an unknown level by default.
+    fn power_info(&self, device: u16) -> PowerInfo {
+        let _ = device;
+        PowerInfo::default()
+    }
+
+    /// Checks if a gamepad has a motion sensor, checked once as it connects. Reports no sensors
+    /// by default.
+    fn has_sensor(&self, device: u16, sensor: MotionSensor) -> bool {
+        let _ = (device, sensor);
+        false
+    }
+
+    /// Turns a motion sensor of a gamepad on or off.
+    ///
+    /// Only called for sensors that [`has_sensor`](Self::has_sensor) reported. Fails by default.
+    fn set_sensor_enabled(
+        &mut self,
+        device: u16,
+        sensor: MotionSensor,
+        enabled: bool,
+    ) -> Result<()> {
+        let _ = (device, sensor, enabled);
+        Err(Error::Unsupported(
+            "motion sensors are not supported by this backend".into(),
+        ))
+    }
+
+    /// Reads an enabled motion sensor of a gamepad, in the units of [`MotionSensor`]. Reads
+    /// nothing by default.
+    fn sensor_data(&self, device: u16, sensor: MotionSensor) -> Option<[f32; 3]> {
+        let _ = (device, sensor);
+        None
+    }
+
+    /// Checks if a gamepad has a button, checked once for every button as it connects. Reports
+    /// every button by default.
+    fn has_button(&self, device: u16, button: Button) -> bool {
+        let _ = (device, button);
+        true
+    }
+
+    /// Counts the touchpads of a gamepad, checked once as it connects. Reports no touchpad by
+    /// default.
+    fn touchpad_count(&self, device: u16) -> usize {
+        let _ = device;
+        0
+    }
+
+    /// Checks if a gamepad has an LED whose color can be set, checked once as it connects.
+    /// Reports no LED by default.
+    fn has_led(&self, device: u16) -> bool {
+        let _ = device;
+        false
+    }
+
+    /// Sets the color of the LED of a gamepad.
+    ///
+    /// Only called for gamepads that [`has_led`](Self::has_led) reported. Fails by default.
+    fn set_led(&mut self, device: u16, red: u8, green: u8, blue: u8) -> Result<()> {
+        let _ = (device, red, green, blue);
+        Err(Error::Unsupported(
+            "LEDs are not supported by this backend".into(),
+        ))
+    }
+
+    /// Shows a player number on the LEDs of a gamepad, or clears it if `None`. Ignores the
+    /// index by default.
+    fn set_player_index(&mut self, device: u16, index: Option<u8>) -> Result<()> {
+        let _ = (device, index);
+        Ok(())
+    }
+
+    /// Checks if a gamepad has adaptive triggers, checked once as it connects. Reports none by
+    /// default.
+    fn supports_trigger_effects(&self, device: u16) -> bool {
+        let _ = device;
+        false
+    }
+
+    /// Sets the effect of an adaptive trigger of a gamepad.
+    ///
+    /// Only called for gamepads that
+    /// [`supports_trigger_effects`](Self::supports_trigger_effects) reported. Fails by default.
+    fn set_trigger_effect(
+        &mut self,
+        device: u16,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Result<()> {
+        let _ = (device, trigger, effect);
+        Err(Error::Unsupported(
+            "trigger effects are not supported by this backend".into(),
+        ))
+    }
+
+    /// Replaces the contents of `fingers` with the fingers touching a touchpad of a gamepad, by
+    /// its index among those counted by [`touchpad_count`](Self::touchpad_count), and returns
+    /// whether the touchpad is clicked down.
+    ///
+    /// A gamepad with a single touchpad reads it at index 0. The click of the first touchpad is
+    /// the [`Button::Touchpad`] button instead, so the returned value is ignored for it. Reads no
+    /// fingers by default.
+    fn read_touchpad_at(
+        &self,
+        device: u16,
+        touchpad: usize,
+        fingers: &mut Vec<TouchFinger>,
+    ) -> bool {
+        let _ = (device, touchpad);
+        fingers.clear();
+        false
+    }
+
+    /// Replaces the contents of `buttons` and `axes` with the raw, unmapped inputs of a gamepad,
+    /// by index, with axes from `-1.0` to `1.0`. Reads no inputs by default.
+    fn read_raw(&self, device: u16, buttons: &mut Vec<bool>, axes: &mut Vec<f32>) {
+        let _ = device;
+        buttons.clear();
+        axes.clear();
+    }
+}
+
+/// An event read from a custom [`Backend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendEvent {
+    /// A gamepad was connected.
+    Connected {
+        /// The number the backend identifies the gamepad by, unique among its connected
+        /// gamepads.
+        device: u16,
+        /// The name of the gamepad.
+        name: String,
+        /// The GUID of the model of the gamepad, or all zeroes if it is unknown.
+        guid: Guid,
+        /// Whether the gamepad has rumble motors.
+        supports_rumble: bool,
+    },
+    /// A gamepad was disconnected.
+    Disconnected(u16),
+    /// An axis moved to a value in `[ANALOG_MIN, ANALOG_MAX]`, or `[0.0, ANALOG_MAX]` for
+    /// triggers.
+    AxisMotion(u16, Axis, f32),
+    /// A button was pressed.
+    ButtonDown(u16, Button),
+    /// A button was released.
+    ButtonUp(u16, Button),
+}
+
+/// A custom backend added to a context.
+pub(crate) struct CustomBackend {
+    index: u16,
+    backend: Box<dyn Backend>,
+    events: Vec<(Duration, BackendEvent)>,
+}
+
+/// The backend a call for a gamepad goes to, from [`CustomBackend::route`].
+pub(crate) enum Route<'a> {
+    /// A custom backend, and the device number it knows the gamepad by.
+    Custom(&'a mut dyn Backend, u16),
+    /// The built-in backend.
+    Native(&'a mut ImplementationContext),
+}
+
+impl CustomBackend {
+    pub(crate) fn new(index: u16, backend: Box<dyn Backend>) -> Self {
+        Self {
+            index,
+            backend,
+            events: Vec::new(),
+        }
+    }
+
+    /// Gets the backend reporting a gamepad: the custom backend among `backends` along with the
+    /// device number it knows the gamepad by, or `gamepad_system` for any other gamepad.
+    pub(crate) fn route<'a>(
+        backends: &'a mut [Self],
+        gamepad_system: &'a mut ImplementationContext,
+        id: GamepadId,
+    ) -> Route<'a> {
+        let custom = match id.0 {
+            RawId::Native(_) => None,
+            RawId::Custom { backend, device } => backends
+                .get_mut(usize::from(backend))
+                .map(|custom| (custom.backend.as_mut(), device)),
+        };
+        match custom {
+            Some((backend, device)) => Route::Custom(backend, device),
+            None => Route::Native(gamepad_system),
+        }
+    }
+
+    /// Drains all pending events from the backend into `events`, with their times moved onto
+    /// the clock of the built-in backend, which reads `now`.
+    pub(crate) fn poll(
+        &mut self,
+        now: Duration,
+        events: &mut Vec<(Duration, Event)>,
+    ) -> Result<()> {
+        self.backend.poll(&mut self.events)?;
+        let backend_now = self.backend.time();
+        let backend = self.index;
+        let id = |device| GamepadId(RawId::Custom { backend, device });
+        for (time, event) in self.events.drain(..) {
+            let time = (time + now).saturating_sub(backend_now);
+            let event = match event {
+                BackendEvent::Connected {
+                    device,
+                    name,
+                    guid,
+                    supports_rumble,
+                } => Event::Connected {
+                    id: id(device),
+                    name,
+                    guid,
+                    owned_internal_gamepad: None,
+                    supports_rumble,
+                },
+                BackendEvent::Disconnected(device) => Event::Disconnected(id(device)),
+                BackendEvent::AxisMotion(device, axis, value) => {
+                    Event::AxisMotion(id(device), axis, AnalogInputValue::from(value))
+                }
+                BackendEvent::ButtonDown(device, button) => Event::ButtonDown(id(device), button),
+                BackendEvent::ButtonUp(device, button) => Event::ButtonUp(id(device), button),
+            };
+            events.push((time, event));
+        }
+        Ok(())
+    }
 }
@@ -1,36 +1,171 @@
-compile_error!("no gamepad backend chosen");
+compile_error!(
+    "no gamepad backend chosen: enable one of the `sdl2-backend`, `gilrs-backend`, \
+     `wasm-backend` or `headless` features, or disable `std` to build only the state tracking"
+);
 
 // The sole purpose of everything below this comment is to supress
 // irrelevant warnings and errors. All of it is dead code.
 
-use crate::{Gamepad, GamepadId};
-use std::collections::HashMap;
+use crate::motion::MotionSensor;
+use crate::power::PowerInfo;
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Button, GamepadId};
 
-use crate::Result;
+use super::{Event, OwnedImplementationGamepad};
+use std::time::Duration;
 
-/// Dummy axis.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Axis {}
+use crate::error::{Error, Result};
 
-/// Dummy button.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Button {}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ImplementationId {}
-
-pub enum OwnedImplementationGamepad {}
+pub const BUTTONS: &[Button] = &[];
 
 pub struct ImplementationContext;
 
 impl ImplementationContext {
-    pub fn new() -> Result<Self> {
+    pub fn new(_: Option<&str>) -> Result<Self> {
+        Err(Error::BackendInit(
+            "no gamepad backend is available on this platform".into(),
+        ))
+    }
+
+    pub fn load_mappings(&mut self, _: &str) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn add_mappings(&mut self, _: &str) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn rumble(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: f32,
+        _: f32,
+        _: Duration,
+    ) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn power_info(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> PowerInfo {
+        PowerInfo::default()
+    }
+
+    pub fn stop_rumble(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn has_sensor(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_sensor_enabled(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: MotionSensor,
+        _: bool,
+    ) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn sensor_data(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        None
+    }
+
+    pub fn touchpad_count(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> usize {
+        0
+    }
+
+    pub fn read_touchpad(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: usize,
+        _: &mut Vec<TouchFinger>,
+    ) -> bool {
+        false
+    }
+
+    pub fn has_button(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: Button,
+    ) -> bool {
+        false
+    }
+
+    pub fn read_raw(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: &mut Vec<bool>,
+        _: &mut Vec<f32>,
+    ) {
+    }
+
+    pub fn has_led(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> bool {
+        false
+    }
+
+    pub fn set_led(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: (u8, u8, u8),
+    ) -> Result<()> {
+        Err("Dummy context".into())
+    }
+
+    pub fn set_player_index(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Option<u8>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn supports_trigger_effects(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_trigger_effect(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Trigger,
+        _: TriggerEffect,
+    ) -> Result<()> {
         Err("Dummy context".into())
     }
 }
 
-impl super::Backend for ImplementationContext {
-    fn update(&mut self, _: &mut HashMap<GamepadId, Gamepad>) -> Result<()> {
+impl super::NativeBackend for ImplementationContext {
+    fn poll(&mut self, _: &mut Vec<(Duration, Event)>) -> Result<()> {
         Err("Dummy context".into())
     }
+
+    fn time(&self) -> Duration {
+        Duration::ZERO
+    }
 }
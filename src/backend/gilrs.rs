@@ -1,86 +1,558 @@
-pub use gilrs::{Axis, Button};
+use gilrs::Axis as NativeAxis;
+use gilrs::Button as NativeButton;
 
 use crate::analog::AnalogInputValue;
-use crate::{Gamepad, GamepadId};
+use crate::guid::Guid;
+use crate::mapping_db::DEFAULT_MAPPINGS;
+use crate::motion::MotionSensor;
+use crate::power::{PowerInfo, PowerLevel};
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Axis, Button, GamepadId};
+
+use super::{Event, ImplementationId, OwnedImplementationGamepad, RawId};
+use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks};
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
-use crate::Result;
+use crate::error::{Error, Result};
 
-pub type ImplementationId = gilrs::GamepadId;
+fn gamepad_id(id: gilrs::GamepadId) -> GamepadId {
+    GamepadId::native(ImplementationId::Gilrs(id))
+}
 
-impl From<GamepadId> for usize {
-    fn from(id: GamepadId) -> Self {
-        id.0.into()
+/// Gets the id gilrs knows a gamepad by, or fails if another backend reports it.
+fn native_id(id: GamepadId) -> Result<gilrs::GamepadId> {
+    match id.0 {
+        RawId::Native(ImplementationId::Gilrs(native)) => Ok(native),
+        _ => Err(Error::DeviceNotFound(id)),
     }
 }
 
-pub enum OwnedImplementationGamepad {}
+/// The buttons the backend can report.
+pub const BUTTONS: &[Button] = &[
+    Button::South,
+    Button::East,
+    Button::West,
+    Button::North,
+    Button::Select,
+    Button::Start,
+    Button::Guide,
+    Button::LeftStick,
+    Button::RightStick,
+    Button::LeftShoulder,
+    Button::RightShoulder,
+    Button::LeftTrigger,
+    Button::RightTrigger,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+    Button::C,
+    Button::Z,
+];
+
+/// Converts a gilrs button, which names the shoulder buttons as triggers.
+fn convert_button(button: NativeButton) -> Option<Button> {
+    Some(match button {
+        NativeButton::South => Button::South,
+        NativeButton::East => Button::East,
+        NativeButton::North => Button::North,
+        NativeButton::West => Button::West,
+        NativeButton::C => Button::C,
+        NativeButton::Z => Button::Z,
+        NativeButton::LeftTrigger => Button::LeftShoulder,
+        NativeButton::LeftTrigger2 => Button::LeftTrigger,
+        NativeButton::RightTrigger => Button::RightShoulder,
+        NativeButton::RightTrigger2 => Button::RightTrigger,
+        NativeButton::Select => Button::Select,
+        NativeButton::Start => Button::Start,
+        NativeButton::Mode => Button::Guide,
+        NativeButton::LeftThumb => Button::LeftStick,
+        NativeButton::RightThumb => Button::RightStick,
+        NativeButton::DPadUp => Button::DPadUp,
+        NativeButton::DPadDown => Button::DPadDown,
+        NativeButton::DPadLeft => Button::DPadLeft,
+        NativeButton::DPadRight => Button::DPadRight,
+        NativeButton::Unknown => return None,
+    })
+}
+
+/// Converts an axis and its value from gilrs, which reports the vertical axes of the thumbsticks
+/// positive upwards and triggers in the full range of an axis. D-pad axes are read as buttons
+/// instead, with [`dpad_axis_buttons`].
+fn convert_axis(axis: NativeAxis, value: f32) -> Option<(Axis, AnalogInputValue)> {
+    let (axis, value) = match axis {
+        NativeAxis::LeftStickX => (Axis::LeftX, value),
+        NativeAxis::LeftStickY => (Axis::LeftY, -value),
+        NativeAxis::RightStickX => (Axis::RightX, value),
+        NativeAxis::RightStickY => (Axis::RightY, -value),
+        NativeAxis::LeftZ => (Axis::TriggerLeft, (value + 1.0) / 2.0),
+        NativeAxis::RightZ => (Axis::TriggerRight, (value + 1.0) / 2.0),
+        NativeAxis::DPadX | NativeAxis::DPadY | NativeAxis::Unknown => return None,
+    };
+    Some((axis, AnalogInputValue::from(value)))
+}
+
+/// Gets the trigger axis for an analog trigger button, which gilrs reports as a button with a
+/// value when the mapping names it as one.
+fn trigger_button_axis(button: NativeButton) -> Option<Axis> {
+    match button {
+        NativeButton::LeftTrigger2 => Some(Axis::TriggerLeft),
+        NativeButton::RightTrigger2 => Some(Axis::TriggerRight),
+        _ => None,
+    }
+}
+
+/// Converts a button to the one gilrs reports it as, if gilrs has it.
+fn native_button(button: Button) -> Option<NativeButton> {
+    Some(match button {
+        Button::South => NativeButton::South,
+        Button::East => NativeButton::East,
+        Button::North => NativeButton::North,
+        Button::West => NativeButton::West,
+        Button::C => NativeButton::C,
+        Button::Z => NativeButton::Z,
+        Button::LeftShoulder => NativeButton::LeftTrigger,
+        Button::LeftTrigger => NativeButton::LeftTrigger2,
+        Button::RightShoulder => NativeButton::RightTrigger,
+        Button::RightTrigger => NativeButton::RightTrigger2,
+        Button::Select => NativeButton::Select,
+        Button::Start => NativeButton::Start,
+        Button::Guide => NativeButton::Mode,
+        Button::LeftStick => NativeButton::LeftThumb,
+        Button::RightStick => NativeButton::RightThumb,
+        Button::DPadUp => NativeButton::DPadUp,
+        Button::DPadDown => NativeButton::DPadDown,
+        Button::DPadLeft => NativeButton::DPadLeft,
+        Button::DPadRight => NativeButton::DPadRight,
+        Button::Misc
+        | Button::Paddle1
+        | Button::Paddle2
+        | Button::Paddle3
+        | Button::Paddle4
+        | Button::Touchpad => return None,
+    })
+}
+
+/// Gets the d-pad buttons for the negative and positive ends of a d-pad axis, which gilrs
+/// reports for d-pads that the device has as a hat rather than as buttons.
+fn dpad_axis_buttons(axis: NativeAxis) -> Option<(Button, Button)> {
+    match axis {
+        NativeAxis::DPadX => Some((Button::DPadLeft, Button::DPadRight)),
+        NativeAxis::DPadY => Some((Button::DPadDown, Button::DPadUp)),
+        _ => None,
+    }
+}
+
+/// Converts a battery percentage from gilrs.
+fn battery(percent: u8) -> PowerLevel {
+    PowerLevel::Battery((f32::from(percent) / 100.0).min(1.0))
+}
+
+/// The raw inputs of a gamepad, by index.
+#[derive(Default)]
+struct RawCodes {
+    buttons: Vec<gilrs::ev::Code>,
+    axes: Vec<gilrs::ev::Code>,
+}
+
+impl RawCodes {
+    /// Indexes an input of a gamepad that changed, unless it already has an index.
+    fn insert(
+        raw_codes: &mut HashMap<GamepadId, RawCodes>,
+        id: gilrs::GamepadId,
+        code: gilrs::ev::Code,
+        axis: bool,
+    ) {
+        let codes = raw_codes.entry(gamepad_id(id)).or_default();
+        let codes = if axis {
+            &mut codes.axes
+        } else {
+            &mut codes.buttons
+        };
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+}
 
 pub struct ImplementationContext {
     context: gilrs::Gilrs,
-    init_gamepads: Vec<GamepadId>,
+    init_gamepads: Vec<gilrs::GamepadId>,
+    // Gamepads of a replaced gilrs context, which are reported as disconnected.
+    stale_gamepads: Vec<GamepadId>,
+    // The rumble effect playing on each gamepad. Dropping an effect stops it.
+    effects: HashMap<GamepadId, gilrs::ff::Effect>,
+    // The inputs of each gamepad that have changed, in the order they first did.
+    raw_codes: HashMap<GamepadId, RawCodes>,
+    // The direction each d-pad axis of each gamepad points in, by axis, for d-pads reported as
+    // axes.
+    dpad_axes: HashMap<GamepadId, [i8; 2]>,
+    // The mappings file, and mappings added since, which gilrs needs together when reopened.
+    mappings: String,
+    added_mappings: String,
+    epoch: SystemTime,
 }
 
 impl ImplementationContext {
-    pub fn new() -> Result<Self> {
-        match gilrs::Gilrs::new() {
-            Ok(context) => {
-                let mut init_gamepads = Vec::new();
+    pub fn new(mappings: Option<&str>) -> Result<Self> {
+        let (context, init_gamepads) = Self::open(mappings)?;
 
-                for (gamepad_id, _) in context.gamepads() {
-                    init_gamepads.push(GamepadId(gamepad_id));
-                }
+        Ok(Self {
+            context,
+            init_gamepads,
+            stale_gamepads: Vec::new(),
+            effects: HashMap::new(),
+            raw_codes: HashMap::new(),
+            dpad_axes: HashMap::new(),
+            mappings: mappings.unwrap_or_default().to_owned(),
+            added_mappings: String::new(),
+            epoch: SystemTime::now(),
+        })
+    }
+
+    /// Replaces the mappings by reopening gilrs, since it only reads mappings on creation.
+    ///
+    /// Every gamepad is reported as disconnected and then connected again. Mappings added with
+    /// [`Self::add_mappings`] are kept.
+    pub fn load_mappings(&mut self, mappings: &str) -> Result<()> {
+        self.mappings = mappings.to_owned();
+        self.reopen()
+    }
 
-                Ok(Self {
-                    context,
-                    init_gamepads,
-                })
+    /// Adds mappings by reopening gilrs, as with [`Self::load_mappings`].
+    pub fn add_mappings(&mut self, mappings: &str) -> Result<()> {
+        self.added_mappings.push_str(mappings);
+        self.added_mappings.push('\n');
+        self.reopen()
+    }
+
+    /// Presses and releases the d-pad buttons as a d-pad axis moves, so that d-pads reported as
+    /// axes read the same as those reported as buttons.
+    fn press_dpad_axis(
+        &mut self,
+        id: GamepadId,
+        axis: NativeAxis,
+        value: f32,
+        time: Duration,
+        events: &mut Vec<(Duration, Event)>,
+    ) {
+        let Some((negative, positive)) = dpad_axis_buttons(axis) else {
+            return;
+        };
+        let directions = self.dpad_axes.entry(id).or_default();
+        let direction = &mut directions[(axis == NativeAxis::DPadY) as usize];
+        let next = match value {
+            value if value > 0.5 => 1,
+            value if value < -0.5 => -1,
+            _ => 0,
+        };
+        let button = |direction: i8| if direction > 0 { positive } else { negative };
+        if next != *direction {
+            if *direction != 0 {
+                events.push((time, Event::ButtonUp(id, button(*direction))));
+            }
+            if next != 0 {
+                events.push((time, Event::ButtonDown(id, button(next))));
             }
-            Err(e) => Err(e.to_string()),
+            *direction = next;
+        }
+    }
+
+    fn reopen(&mut self) -> Result<()> {
+        let mappings = format!("{}\n{}", self.mappings, self.added_mappings);
+        let (context, init_gamepads) = Self::open(Some(&mappings))?;
+
+        let old_context = std::mem::replace(&mut self.context, context);
+        self.stale_gamepads
+            .extend(old_context.gamepads().map(|(id, _)| gamepad_id(id)));
+        self.init_gamepads = init_gamepads;
+        self.effects.clear();
+        self.raw_codes.clear();
+        self.dpad_axes.clear();
+        Ok(())
+    }
+
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        let ticks = Ticks::from_ms(duration.as_millis().try_into().unwrap_or(u32::MAX));
+        let scheduling = Replay {
+            play_for: ticks,
+            ..Default::default()
+        };
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: super::rumble_magnitude(strong),
+                },
+                scheduling,
+                envelope: Default::default(),
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: super::rumble_magnitude(weak),
+                },
+                scheduling,
+                envelope: Default::default(),
+            })
+            .gamepads(&[native_id(id)?])
+            .repeat(Repeat::For(ticks))
+            .finish(&mut self.context)
+            .map_err(|error| Error::Backend(error.to_string()))?;
+        effect
+            .play()
+            .map_err(|error| Error::Backend(error.to_string()))?;
+
+        self.effects.insert(id, effect);
+        Ok(())
+    }
+
+    pub fn power_info(&self, id: GamepadId, _: Option<&OwnedImplementationGamepad>) -> PowerInfo {
+        let Ok(id) = native_id(id) else {
+            return PowerInfo::default();
+        };
+        let (level, charging) = match self.context.gamepad(id).power_info() {
+            gilrs::PowerInfo::Unknown => (PowerLevel::Unknown, false),
+            gilrs::PowerInfo::Wired => (PowerLevel::Wired, false),
+            gilrs::PowerInfo::Discharging(percent) => (battery(percent), false),
+            gilrs::PowerInfo::Charging(percent) => (battery(percent), true),
+            gilrs::PowerInfo::Charged => (PowerLevel::Battery(1.0), true),
+        };
+        PowerInfo { level, charging }
+    }
+
+    pub fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        self.effects.remove(&id);
+        Ok(())
+    }
+
+    /// gilrs does not read motion sensors.
+    pub fn has_sensor(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_sensor_enabled(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: MotionSensor,
+        _: bool,
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "motion sensors are not supported by gilrs".into(),
+        ))
+    }
+
+    pub fn sensor_data(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        None
+    }
+
+    /// gilrs does not read touchpads.
+    pub fn touchpad_count(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> usize {
+        0
+    }
+
+    pub fn read_touchpad(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: usize,
+        _: &mut Vec<TouchFinger>,
+    ) -> bool {
+        false
+    }
+
+    /// gilrs has no paddles or extra buttons. The d-pad is present if it is mapped as buttons or
+    /// as axes.
+    pub fn has_button(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        button: Button,
+    ) -> bool {
+        let (Ok(id), Some(native)) = (native_id(id), native_button(button)) else {
+            return false;
+        };
+        let gamepad = self.context.gamepad(id);
+        let dpad_axis = match button {
+            Button::DPadLeft | Button::DPadRight => Some(NativeAxis::DPadX),
+            Button::DPadUp | Button::DPadDown => Some(NativeAxis::DPadY),
+            _ => None,
+        };
+        gamepad.button_code(native).is_some()
+            || dpad_axis.is_some_and(|axis| gamepad.axis_code(axis).is_some())
+    }
+
+    /// gilrs lists only the inputs that have changed, so inputs are indexed in the order they
+    /// first changed after connecting, and are missing until then.
+    pub fn read_raw(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        buttons: &mut Vec<bool>,
+        axes: &mut Vec<f32>,
+    ) {
+        buttons.clear();
+        axes.clear();
+        let (Ok(native), Some(codes)) = (native_id(id), self.raw_codes.get(&id)) else {
+            return;
+        };
+        let gamepad = self.context.gamepad(native);
+        let state = gamepad.state();
+        buttons.extend(codes.buttons.iter().map(|&code| state.is_pressed(code)));
+        axes.extend(codes.axes.iter().map(|&code| state.value(code)));
+    }
+
+    /// gilrs does not control LEDs.
+    pub fn has_led(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> bool {
+        false
+    }
+
+    pub fn set_led(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: (u8, u8, u8),
+    ) -> Result<()> {
+        Err(Error::Unsupported("LEDs are not supported by gilrs".into()))
+    }
+
+    /// gilrs does not control player LEDs, so the index is ignored.
+    pub fn set_player_index(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Option<u8>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// gilrs does not drive adaptive triggers.
+    pub fn supports_trigger_effects(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_trigger_effect(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Trigger,
+        _: TriggerEffect,
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "trigger effects are not supported by gilrs".into(),
+        ))
+    }
+
+    fn open(mappings: Option<&str>) -> Result<(gilrs::Gilrs, Vec<gilrs::GamepadId>)> {
+        let mut builder = gilrs::GilrsBuilder::new().add_mappings(DEFAULT_MAPPINGS);
+        if let Some(mappings) = mappings {
+            builder = builder.add_mappings(mappings);
+        }
+
+        let context = builder
+            .build()
+            .map_err(|error| Error::BackendInit(error.to_string()))?;
+        let mut init_gamepads = Vec::new();
+
+        for (gamepad_id, _gamepad) in context.gamepads() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?gamepad_id, name = _gamepad.name(), "found gamepad");
+            init_gamepads.push(gamepad_id);
         }
+
+        Ok((context, init_gamepads))
     }
 }
 
-impl super::Backend for ImplementationContext {
-    fn update(&mut self, gamepads: &mut HashMap<GamepadId, Gamepad>) -> Result<()> {
-        for gamepad in self.init_gamepads.drain(..) {
-            gamepads.insert(gamepad, Gamepad::new(None));
+impl super::NativeBackend for ImplementationContext {
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()> {
+        for gamepad in self.stale_gamepads.drain(..) {
+            events.push((Duration::ZERO, Event::Disconnected(gamepad)));
         }
 
-        for (_, gamepad) in gamepads.iter_mut() {
-            gamepad.update_inputs();
+        for gamepad in self.init_gamepads.drain(..) {
+            events.push((
+                Duration::ZERO,
+                Event::Connected {
+                    id: gamepad_id(gamepad),
+                    name: self.context.gamepad(gamepad).name().to_owned(),
+                    guid: Guid::from_bytes(self.context.gamepad(gamepad).uuid()),
+                    owned_internal_gamepad: None,
+                    supports_rumble: self.context.gamepad(gamepad).is_ff_supported(),
+                },
+            ));
         }
 
-        while let Some(gilrs::Event { id, event, .. }) = self.context.next_event() {
+        while let Some(gilrs::Event { id, event, time }) = self.context.next_event() {
             use gilrs::EventType;
+            let time = time.duration_since(self.epoch).unwrap_or_default();
             match event {
                 EventType::Connected => {
-                    gamepads.insert(GamepadId(id), Gamepad::new(None));
-
-                    #[cfg(debug_assertions)]
-                    println!("Added gamepad \"{}\"", self.context.gamepad(id).name());
+                    events.push((
+                        time,
+                        Event::Connected {
+                            id: gamepad_id(id),
+                            name: self.context.gamepad(id).name().to_owned(),
+                            guid: Guid::from_bytes(self.context.gamepad(id).uuid()),
+                            owned_internal_gamepad: None,
+                            supports_rumble: self.context.gamepad(id).is_ff_supported(),
+                        },
+                    ));
                 }
                 EventType::Disconnected => {
-                    gamepads.remove(&GamepadId(id));
-
-                    #[cfg(debug_assertions)]
-                    println!("Removed gamepad \"{}\"", self.context.gamepad(id).name());
+                    self.effects.remove(&gamepad_id(id));
+                    self.raw_codes.remove(&gamepad_id(id));
+                    self.dpad_axes.remove(&gamepad_id(id));
+                    events.push((time, Event::Disconnected(gamepad_id(id))));
+                }
+                EventType::AxisChanged(axis, value, code) => {
+                    RawCodes::insert(&mut self.raw_codes, id, code, true);
+                    if let Some((axis, value)) = convert_axis(axis, value) {
+                        events.push((time, Event::AxisMotion(gamepad_id(id), axis, value)));
+                    }
+                    self.press_dpad_axis(gamepad_id(id), axis, value, time, events);
                 }
-                EventType::AxisChanged(axis, value, _) => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(id)) {
-                        gamepad
-                            .analog_inputs
-                            .set(axis, AnalogInputValue::from(value));
+                EventType::ButtonChanged(button, value, code) => {
+                    RawCodes::insert(&mut self.raw_codes, id, code, false);
+                    if let Some(axis) = trigger_button_axis(button) {
+                        let value = AnalogInputValue::from(value);
+                        events.push((time, Event::AxisMotion(gamepad_id(id), axis, value)));
                     }
                 }
                 EventType::ButtonPressed(button, _) => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(id)) {
-                        gamepad.digital_inputs.activate(button);
+                    if let Some(button) = convert_button(button) {
+                        events.push((time, Event::ButtonDown(gamepad_id(id), button)));
                     }
                 }
                 EventType::ButtonReleased(button, _) => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(id)) {
-                        gamepad.digital_inputs.deactivate(button);
+                    if let Some(button) = convert_button(button) {
+                        events.push((time, Event::ButtonUp(gamepad_id(id), button)));
                     }
                 }
                 _ => (),
@@ -89,4 +561,8 @@ impl super::Backend for ImplementationContext {
 
         Ok(())
     }
+
+    fn time(&self) -> Duration {
+        self.epoch.elapsed().unwrap_or_default()
+    }
 }
@@ -0,0 +1,171 @@
+use crate::motion::MotionSensor;
+use crate::power::PowerInfo;
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Button, GamepadId};
+
+use super::{Event, OwnedImplementationGamepad};
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+
+/// The buttons the backend can report, which is none.
+pub const BUTTONS: &[Button] = &[];
+
+/// A backend without devices, for dedicated servers and CI runs, which reports no gamepads and
+/// links no platform libraries.
+///
+/// Gamepads of custom backends are reported as usual.
+pub struct ImplementationContext {
+    epoch: Instant,
+}
+
+impl ImplementationContext {
+    pub fn new(_: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            epoch: Instant::now(),
+        })
+    }
+
+    /// There are no gamepads to map, so mappings are ignored.
+    pub fn load_mappings(&mut self, _: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn add_mappings(&mut self, _: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: f32,
+        _: f32,
+        _: Duration,
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+
+    pub fn power_info(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> PowerInfo {
+        PowerInfo::default()
+    }
+
+    pub fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+
+    pub fn has_sensor(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_sensor_enabled(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: MotionSensor,
+        _: bool,
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+
+    pub fn sensor_data(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        None
+    }
+
+    pub fn touchpad_count(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> usize {
+        0
+    }
+
+    pub fn read_touchpad(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: usize,
+        _: &mut Vec<TouchFinger>,
+    ) -> bool {
+        false
+    }
+
+    pub fn has_button(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: Button,
+    ) -> bool {
+        false
+    }
+
+    pub fn read_raw(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: &mut Vec<bool>,
+        _: &mut Vec<f32>,
+    ) {
+    }
+
+    pub fn has_led(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> bool {
+        false
+    }
+
+    pub fn set_led(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: (u8, u8, u8),
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+
+    pub fn set_player_index(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Option<u8>,
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+
+    pub fn supports_trigger_effects(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_trigger_effect(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Trigger,
+        _: TriggerEffect,
+    ) -> Result<()> {
+        Err(Error::DeviceNotFound(id))
+    }
+}
+
+impl super::NativeBackend for ImplementationContext {
+    fn poll(&mut self, _: &mut Vec<(Duration, Event)>) -> Result<()> {
+        Ok(())
+    }
+
+    fn time(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
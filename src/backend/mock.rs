@@ -0,0 +1,446 @@
+use crate::analog::AnalogInputValue;
+use crate::guid::Guid;
+use crate::input::Input;
+use crate::motion::MotionSensor;
+use crate::power::PowerInfo;
+use crate::prompt::DeviceKind;
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Axis, Button, GamepadId};
+
+use super::{Event, ImplementationId, OwnedImplementationGamepad};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// The buttons the backend can report, which is every button.
+pub const BUTTONS: &[Button] = Button::ALL;
+
+/// The state of a virtual gamepad that the backend reports to the context.
+#[derive(Debug, Default)]
+struct MockGamepad {
+    power_info: PowerInfo,
+    // The readings of the motion sensors, by sensor, while enabled.
+    sensors: [Option<[f32; 3]>; 2],
+    // The fingers down on each touchpad, by slot, and whether it is clicked down.
+    touchpads: Vec<(Vec<TouchFinger>, bool)>,
+    // The raw inputs, by index.
+    raw_buttons: Vec<bool>,
+    raw_axes: Vec<f32>,
+    led: Option<(u8, u8, u8)>,
+    player_index: Option<u8>,
+    // The effect of each trigger, by `Trigger`.
+    trigger_effects: [TriggerEffect; 2],
+    // The intensities of the motors, and when the rumble ends.
+    rumble: Option<(f32, f32, Duration)>,
+}
+
+/// A backend of virtual gamepads, driven by the application rather than by hardware, for
+/// testing input handling.
+///
+/// Enabled by the `mock-backend` feature, which takes precedence over every other backend, so
+/// that it can be enabled for tests only, as a feature of the dev-dependency. Reach it with
+/// [`GamepadContext::mock_backend`](crate::GamepadContext::mock_backend).
+///
+/// Every change is queued as an event, and applied on the next
+/// [`GamepadContext::update`](crate::GamepadContext::update), just like real input. The clock
+/// only moves when [`advance`](Self::advance) is called, so tests are deterministic.
+pub struct MockBackend {
+    time: Duration,
+    // The number given to the next virtual gamepad, in the order they were connected.
+    next_id: u32,
+    gamepads: HashMap<GamepadId, MockGamepad>,
+    events: Vec<(Duration, Event)>,
+}
+
+impl MockBackend {
+    pub(crate) fn new(_: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            time: Duration::ZERO,
+            next_id: 0,
+            gamepads: HashMap::new(),
+            events: Vec::new(),
+        })
+    }
+
+    pub(crate) fn load_mappings(&mut self, _: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub(crate) fn add_mappings(&mut self, _: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub(crate) fn rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        let gamepad = self
+            .gamepads
+            .get_mut(&id)
+            .ok_or(Error::DeviceNotFound(id))?;
+        gamepad.rumble = Some((strong, weak, self.time + duration));
+        Ok(())
+    }
+
+    pub(crate) fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        self.rumble(id, gamepad, 0.0, 0.0, Duration::ZERO)
+    }
+
+    pub(crate) fn power_info(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> PowerInfo {
+        self.gamepads
+            .get(&id)
+            .map(|gamepad| gamepad.power_info)
+            .unwrap_or_default()
+    }
+
+    /// Every virtual gamepad has both motion sensors.
+    pub(crate) fn has_sensor(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    pub(crate) fn set_sensor_enabled(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+        enabled: bool,
+    ) -> Result<()> {
+        let gamepad = self
+            .gamepads
+            .get_mut(&id)
+            .ok_or(Error::DeviceNotFound(id))?;
+        let reading = &mut gamepad.sensors[sensor as usize];
+        *reading = enabled.then(|| reading.unwrap_or_default());
+        Ok(())
+    }
+
+    pub(crate) fn sensor_data(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        self.gamepads.get(&id)?.sensors[sensor as usize]
+    }
+
+    /// Every virtual gamepad has a touchpad, and those named like a Steam Deck have two.
+    pub(crate) fn touchpad_count(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> usize {
+        self.gamepads
+            .get(&id)
+            .map_or(0, |gamepad| gamepad.touchpads.len())
+    }
+
+    pub(crate) fn read_touchpad(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        touchpad: usize,
+        fingers: &mut Vec<TouchFinger>,
+    ) -> bool {
+        fingers.clear();
+        match self
+            .gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.touchpads.get(touchpad))
+        {
+            Some((own, clicked)) => {
+                fingers.extend_from_slice(own);
+                *clicked
+            }
+            None => false,
+        }
+    }
+
+    /// Every virtual gamepad has every button.
+    pub(crate) fn has_button(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: Button,
+    ) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    pub(crate) fn read_raw(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        buttons: &mut Vec<bool>,
+        axes: &mut Vec<f32>,
+    ) {
+        buttons.clear();
+        axes.clear();
+        if let Some(gamepad) = self.gamepads.get(&id) {
+            buttons.extend_from_slice(&gamepad.raw_buttons);
+            axes.extend_from_slice(&gamepad.raw_axes);
+        }
+    }
+
+    /// Every virtual gamepad has an LED.
+    pub(crate) fn has_led(&self, id: GamepadId, _: Option<&OwnedImplementationGamepad>) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    pub(crate) fn set_led(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        color: (u8, u8, u8),
+    ) -> Result<()> {
+        let gamepad = self
+            .gamepads
+            .get_mut(&id)
+            .ok_or(Error::DeviceNotFound(id))?;
+        gamepad.led = Some(color);
+        Ok(())
+    }
+
+    pub(crate) fn set_player_index(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        index: Option<u8>,
+    ) -> Result<()> {
+        let gamepad = self
+            .gamepads
+            .get_mut(&id)
+            .ok_or(Error::DeviceNotFound(id))?;
+        gamepad.player_index = index;
+        Ok(())
+    }
+
+    /// Every virtual gamepad has adaptive triggers.
+    pub(crate) fn supports_trigger_effects(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    pub(crate) fn set_trigger_effect(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Result<()> {
+        let gamepad = self
+            .gamepads
+            .get_mut(&id)
+            .ok_or(Error::DeviceNotFound(id))?;
+        gamepad.trigger_effects[trigger as usize] = effect;
+        Ok(())
+    }
+
+    /// Connects a virtual gamepad with the given name, which supports rumble.
+    pub fn connect(&mut self, name: &str) -> GamepadId {
+        self.connect_with_guid(name, Guid::default())
+    }
+
+    /// Connects a virtual gamepad with the given name and GUID, such as to test settings kept
+    /// per device.
+    pub fn connect_with_guid(&mut self, name: &str, guid: Guid) -> GamepadId {
+        let id = GamepadId::native(ImplementationId::Mock(self.next_id));
+        self.next_id += 1;
+        let touchpads = match DeviceKind::detect(name, guid) {
+            DeviceKind::SteamDeck => 2,
+            _ => 1,
+        };
+        let gamepad = MockGamepad {
+            touchpads: vec![Default::default(); touchpads],
+            ..MockGamepad::default()
+        };
+        self.gamepads.insert(id, gamepad);
+        self.push(Event::Connected {
+            id,
+            name: name.to_owned(),
+            guid,
+            owned_internal_gamepad: None,
+            supports_rumble: true,
+        });
+        id
+    }
+
+    /// Disconnects a virtual gamepad.
+    pub fn disconnect(&mut self, id: GamepadId) {
+        if self.gamepads.remove(&id).is_some() {
+            self.push(Event::Disconnected(id));
+        }
+    }
+
+    /// Presses a button of a virtual gamepad.
+    pub fn press(&mut self, id: GamepadId, button: Button) {
+        self.push(Event::ButtonDown(id, button));
+    }
+
+    /// Releases a button of a virtual gamepad.
+    pub fn release(&mut self, id: GamepadId, button: Button) {
+        self.push(Event::ButtonUp(id, button));
+    }
+
+    /// Moves an axis of a virtual gamepad to a value, which is clamped to
+    /// `[ANALOG_MIN, ANALOG_MAX]`.
+    pub fn set_axis(&mut self, id: GamepadId, axis: Axis, value: f32) {
+        self.push(Event::AxisMotion(id, axis, AnalogInputValue::from(value)));
+    }
+
+    /// Sets the battery that a virtual gamepad reports.
+    pub fn set_power_info(&mut self, id: GamepadId, power_info: PowerInfo) {
+        if let Some(gamepad) = self.gamepads.get_mut(&id) {
+            gamepad.power_info = power_info;
+        }
+    }
+
+    /// Sets the reading of a motion sensor of a virtual gamepad, which it reports while the sensor
+    /// is enabled.
+    pub fn set_sensor(&mut self, id: GamepadId, sensor: MotionSensor, values: [f32; 3]) {
+        if let Some(reading) = self
+            .gamepads
+            .get_mut(&id)
+            .and_then(|gamepad| gamepad.sensors[sensor as usize].as_mut())
+        {
+            *reading = values;
+        }
+    }
+
+    /// Puts a finger down on the first touchpad of a virtual gamepad, or moves the finger
+    /// already in its slot.
+    pub fn touch(&mut self, id: GamepadId, finger: TouchFinger) {
+        self.touch_at(id, 0, finger);
+    }
+
+    /// Lifts the finger in a slot off the first touchpad of a virtual gamepad.
+    pub fn lift(&mut self, id: GamepadId, slot: usize) {
+        self.lift_at(id, 0, slot);
+    }
+
+    /// Puts a finger down on a touchpad of a virtual gamepad, by index, or moves the finger
+    /// already in its slot.
+    pub fn touch_at(&mut self, id: GamepadId, touchpad: usize, finger: TouchFinger) {
+        if let Some((fingers, _)) = self.touchpad(id, touchpad) {
+            fingers.retain(|other| other.slot != finger.slot);
+            fingers.push(finger);
+            fingers.sort_by_key(|finger| finger.slot);
+        }
+    }
+
+    /// Lifts the finger in a slot off a touchpad of a virtual gamepad, by index.
+    pub fn lift_at(&mut self, id: GamepadId, touchpad: usize, slot: usize) {
+        if let Some((fingers, _)) = self.touchpad(id, touchpad) {
+            fingers.retain(|finger| finger.slot != slot);
+        }
+    }
+
+    /// Clicks a touchpad of a virtual gamepad other than the first down, or releases it.
+    ///
+    /// The click of the first touchpad is [`Button::Touchpad`], pressed with
+    /// [`press`](Self::press).
+    pub fn click_touchpad(&mut self, id: GamepadId, touchpad: usize, clicked: bool) {
+        if let Some((_, own)) = self.touchpad(id, touchpad) {
+            *own = clicked;
+        }
+    }
+
+    /// Sets the raw button at an index of a virtual gamepad, adding released buttons up to it
+    /// if it has fewer.
+    pub fn set_raw_button(&mut self, id: GamepadId, index: usize, pressed: bool) {
+        if let Some(gamepad) = self.gamepads.get_mut(&id) {
+            if gamepad.raw_buttons.len() <= index {
+                gamepad.raw_buttons.resize(index + 1, false);
+            }
+            gamepad.raw_buttons[index] = pressed;
+        }
+    }
+
+    /// Sets the raw axis at an index of a virtual gamepad, adding centered axes up to it if it
+    /// has fewer.
+    pub fn set_raw_axis(&mut self, id: GamepadId, index: usize, value: f32) {
+        if let Some(gamepad) = self.gamepads.get_mut(&id) {
+            if gamepad.raw_axes.len() <= index {
+                gamepad.raw_axes.resize(index + 1, 0.0);
+            }
+            gamepad.raw_axes[index] = value.clamp(-1.0, 1.0);
+        }
+    }
+
+    /// Gets the intensities of the strong and weak motors of a virtual gamepad, or `None` if it
+    /// is not rumbling.
+    ///
+    /// Rumble ends once the clock passes its duration.
+    pub fn rumble_intensities(&self, id: GamepadId) -> Option<(f32, f32)> {
+        match self.gamepads.get(&id)?.rumble? {
+            (strong, weak, end) if self.time < end && (strong, weak) != (0.0, 0.0) => {
+                Some((strong, weak))
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the color last set on the LED of a virtual gamepad, or `None` if it was never set.
+    pub fn led(&self, id: GamepadId) -> Option<(u8, u8, u8)> {
+        self.gamepads.get(&id)?.led
+    }
+
+    /// Gets the player index shown on the LEDs of a virtual gamepad.
+    pub fn player_index(&self, id: GamepadId) -> Option<u8> {
+        self.gamepads.get(&id)?.player_index
+    }
+
+    /// Gets the effect last set on a trigger of a virtual gamepad.
+    pub fn trigger_effect(&self, id: GamepadId, trigger: Trigger) -> Option<TriggerEffect> {
+        Some(self.gamepads.get(&id)?.trigger_effects[trigger as usize])
+    }
+
+    /// Moves the clock forward, such as to test held buttons, key repeat or reconnect grace
+    /// periods.
+    pub fn advance(&mut self, duration: Duration) {
+        self.time += duration;
+    }
+
+    fn touchpad(
+        &mut self,
+        id: GamepadId,
+        touchpad: usize,
+    ) -> Option<&mut (Vec<TouchFinger>, bool)> {
+        self.gamepads.get_mut(&id)?.touchpads.get_mut(touchpad)
+    }
+
+    fn push(&mut self, event: Event) {
+        self.events.push((self.time, event));
+    }
+}
+
+impl super::NativeBackend for MockBackend {
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()> {
+        events.append(&mut self.events);
+        Ok(())
+    }
+
+    fn time(&self) -> Duration {
+        self.time
+    }
+}
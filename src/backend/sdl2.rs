@@ -1,103 +1,792 @@
-pub use sdl2::controller::{Axis, Button};
+use sdl2::controller::Axis as NativeAxis;
+use sdl2::controller::Button as NativeButton;
+
+use sdl2::joystick::HatState;
 
 use crate::analog::AnalogInputValue;
-use crate::{Gamepad, GamepadId};
+use crate::guid::Guid;
+use crate::mapping_db::DEFAULT_MAPPINGS;
+use crate::motion::MotionSensor;
+use crate::power::{PowerInfo, PowerLevel};
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Axis, Button, GamepadId};
+
+use super::{Event, ImplementationId};
 use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+fn gamepad_id(id: u32) -> GamepadId {
+    GamepadId::native(ImplementationId::Sdl(id))
+}
+
+/// The buttons the backend can report.
+pub const BUTTONS: &[Button] = &[
+    Button::South,
+    Button::East,
+    Button::West,
+    Button::North,
+    Button::Select,
+    Button::Start,
+    Button::Guide,
+    Button::LeftStick,
+    Button::RightStick,
+    Button::LeftShoulder,
+    Button::RightShoulder,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+    Button::Misc,
+    Button::Paddle1,
+    Button::Paddle2,
+    Button::Paddle3,
+    Button::Paddle4,
+    Button::Touchpad,
+];
+
+fn convert_button(button: NativeButton) -> Button {
+    match button {
+        NativeButton::A => Button::South,
+        NativeButton::B => Button::East,
+        NativeButton::X => Button::West,
+        NativeButton::Y => Button::North,
+        NativeButton::Back => Button::Select,
+        NativeButton::Guide => Button::Guide,
+        NativeButton::Start => Button::Start,
+        NativeButton::LeftStick => Button::LeftStick,
+        NativeButton::RightStick => Button::RightStick,
+        NativeButton::LeftShoulder => Button::LeftShoulder,
+        NativeButton::RightShoulder => Button::RightShoulder,
+        NativeButton::DPadUp => Button::DPadUp,
+        NativeButton::DPadDown => Button::DPadDown,
+        NativeButton::DPadLeft => Button::DPadLeft,
+        NativeButton::DPadRight => Button::DPadRight,
+        NativeButton::Misc1 => Button::Misc,
+        NativeButton::Paddle1 => Button::Paddle1,
+        NativeButton::Paddle2 => Button::Paddle2,
+        NativeButton::Paddle3 => Button::Paddle3,
+        NativeButton::Paddle4 => Button::Paddle4,
+        NativeButton::Touchpad => Button::Touchpad,
+    }
+}
 
-use crate::Result;
+/// Converts a button to the one SDL2 reports it as.
+fn native_button(button: Button) -> Option<NativeButton> {
+    Some(match button {
+        Button::South => NativeButton::A,
+        Button::East => NativeButton::B,
+        Button::West => NativeButton::X,
+        Button::North => NativeButton::Y,
+        Button::Select => NativeButton::Back,
+        Button::Guide => NativeButton::Guide,
+        Button::Start => NativeButton::Start,
+        Button::LeftStick => NativeButton::LeftStick,
+        Button::RightStick => NativeButton::RightStick,
+        Button::LeftShoulder => NativeButton::LeftShoulder,
+        Button::RightShoulder => NativeButton::RightShoulder,
+        Button::DPadUp => NativeButton::DPadUp,
+        Button::DPadDown => NativeButton::DPadDown,
+        Button::DPadLeft => NativeButton::DPadLeft,
+        Button::DPadRight => NativeButton::DPadRight,
+        Button::Misc => NativeButton::Misc1,
+        Button::Paddle1 => NativeButton::Paddle1,
+        Button::Paddle2 => NativeButton::Paddle2,
+        Button::Paddle3 => NativeButton::Paddle3,
+        Button::Paddle4 => NativeButton::Paddle4,
+        Button::Touchpad => NativeButton::Touchpad,
+        Button::LeftTrigger | Button::RightTrigger | Button::C | Button::Z => return None,
+    })
+}
 
-pub type ImplementationId = u32;
+fn convert_guid(guid: sdl2::joystick::Guid) -> Guid {
+    Guid::from_bytes(guid.raw().data)
+}
 
-impl From<GamepadId> for usize {
-    fn from(id: GamepadId) -> Self {
-        id.0 as usize
+fn convert_axis(axis: NativeAxis) -> Axis {
+    match axis {
+        NativeAxis::LeftX => Axis::LeftX,
+        NativeAxis::LeftY => Axis::LeftY,
+        NativeAxis::RightX => Axis::RightX,
+        NativeAxis::RightY => Axis::RightY,
+        NativeAxis::TriggerLeft => Axis::TriggerLeft,
+        NativeAxis::TriggerRight => Axis::TriggerRight,
     }
 }
 
-pub struct OwnedImplementationGamepad(sdl2::controller::GameController);
+/// The default mapping of generic joystick buttons, by index, into the gamepad layout.
+///
+/// This follows the usual order of arcade sticks and fight pads: four face buttons, then the
+/// shoulder buttons, then back and start.
+const JOYSTICK_BUTTONS: &[Button] = &[
+    Button::South,
+    Button::East,
+    Button::West,
+    Button::North,
+    Button::LeftShoulder,
+    Button::RightShoulder,
+    Button::Select,
+    Button::Start,
+    Button::LeftStick,
+    Button::RightStick,
+    Button::Guide,
+];
+
+/// The default mapping of generic joystick axes, by index, into the gamepad layout.
+///
+/// Further axes are ignored, since their resting value differs between devices.
+const JOYSTICK_AXES: &[Axis] = &[Axis::LeftX, Axis::LeftY, Axis::RightX, Axis::RightY];
+
+/// The d-pad buttons for the bits of a joystick hat.
+const HAT_BUTTONS: [(u8, Button); 4] = [
+    (HatState::Up as u8, Button::DPadUp),
+    (HatState::Right as u8, Button::DPadRight),
+    (HatState::Down as u8, Button::DPadDown),
+    (HatState::Left as u8, Button::DPadLeft),
+];
+
+pub enum OwnedImplementationGamepad {
+    /// A device with a gamepad mapping, along with the same device opened as a joystick, if that
+    /// succeeded, for what the gamepad API does not expose, such as the battery.
+    Controller(
+        sdl2::controller::GameController,
+        Option<sdl2::joystick::Joystick>,
+    ),
+    /// A device SDL has no gamepad mapping for, read with the default joystick mapping.
+    Joystick(sdl2::joystick::Joystick),
+}
 
 pub struct ImplementationContext {
     sdl_context: sdl2::Sdl,
     controller_subsystem: sdl2::GameControllerSubsystem,
+    joystick_subsystem: sdl2::JoystickSubsystem,
+    // The state of the first hat of every open generic joystick, by instance id.
+    joystick_hats: HashMap<u32, u8>,
+    timer_subsystem: sdl2::TimerSubsystem,
+    // When the application owns the event pump, it forwards events here instead.
+    external_events: Option<Vec<(Duration, Event)>>,
 }
 
 impl ImplementationContext {
-    pub fn new() -> Result<Self> {
-        let sdl_context = sdl2::init()?;
-        let controller_subsystem = sdl_context.game_controller()?;
+    pub fn new(mappings: Option<&str>) -> Result<Self> {
+        let sdl_context = sdl2::init().map_err(Error::BackendInit)?;
+        let mut context = Self::with_sdl(&sdl_context, mappings)?;
+        context.external_events = None;
+        Ok(context)
+    }
+
+    /// Creates a context from an SDL context owned by the application.
+    ///
+    /// No event pump is created; events must be forwarded with [`Self::handle_event`].
+    pub fn with_sdl(sdl_context: &sdl2::Sdl, mappings: Option<&str>) -> Result<Self> {
+        let controller_subsystem = sdl_context.game_controller().map_err(Error::BackendInit)?;
+        for mappings in [Some(DEFAULT_MAPPINGS), mappings].into_iter().flatten() {
+            controller_subsystem
+                .load_mappings_from_read(&mut mappings.as_bytes())
+                .map_err(|error| Error::Invalid(error.to_string()))?;
+        }
+        let joystick_subsystem = sdl_context.joystick().map_err(Error::BackendInit)?;
+        let timer_subsystem = sdl_context.timer().map_err(Error::BackendInit)?;
 
         Ok(Self {
-            sdl_context,
+            sdl_context: sdl_context.clone(),
             controller_subsystem,
+            joystick_subsystem,
+            joystick_hats: HashMap::new(),
+            timer_subsystem,
+            external_events: Some(Vec::new()),
         })
     }
-}
 
-impl super::Backend for ImplementationContext {
-    fn update(&mut self, gamepads: &mut HashMap<GamepadId, Gamepad>) -> Result<()> {
-        let mut event_pump = self.sdl_context.event_pump()?;
+    /// Adds or replaces mappings. SDL applies them to open gamepads immediately.
+    pub fn load_mappings(&mut self, mappings: &str) -> Result<()> {
+        self.controller_subsystem
+            .load_mappings_from_read(&mut mappings.as_bytes())
+            .map(|_| ())
+            .map_err(|error| Error::Invalid(error.to_string()))
+    }
+
+    /// SDL keeps every mapping loaded, so added mappings are loaded the same way.
+    pub fn add_mappings(&mut self, mappings: &str) -> Result<()> {
+        self.load_mappings(mappings)
+    }
 
-        for (_, gamepad) in gamepads.iter_mut() {
-            gamepad.update_inputs();
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        // Very long durations overflow inside SDL and end the rumble at once.
+        let duration_ms = duration.as_millis().min(i32::MAX as u128) as u32;
+        let (strong, weak) = (
+            super::rumble_magnitude(strong),
+            super::rumble_magnitude(weak),
+        );
+        let result = match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                controller.set_rumble(strong, weak, duration_ms)
+            }
+            Some(OwnedImplementationGamepad::Joystick(joystick)) => {
+                joystick.set_rumble(strong, weak, duration_ms)
+            }
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        result.map_err(|error| Error::Backend(error.to_string()))
+    }
+
+    pub fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        self.rumble(id, gamepad, 0.0, 0.0, Duration::ZERO)
+    }
+
+    pub fn power_info(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> PowerInfo {
+        let joystick = match gamepad {
+            Some(OwnedImplementationGamepad::Controller(_, joystick)) => joystick.as_ref(),
+            Some(OwnedImplementationGamepad::Joystick(joystick)) => Some(joystick),
+            None => None,
+        };
+        let level = match joystick.map(sdl2::joystick::Joystick::power_level) {
+            Some(Ok(sdl2::joystick::PowerLevel::Empty)) => PowerLevel::Battery(0.05),
+            Some(Ok(sdl2::joystick::PowerLevel::Low)) => PowerLevel::Battery(0.2),
+            Some(Ok(sdl2::joystick::PowerLevel::Medium)) => PowerLevel::Battery(0.7),
+            Some(Ok(sdl2::joystick::PowerLevel::Full)) => PowerLevel::Battery(1.0),
+            Some(Ok(sdl2::joystick::PowerLevel::Wired)) => PowerLevel::Wired,
+            _ => PowerLevel::Unknown,
+        };
+        // SDL2 does not report whether a battery is charging.
+        PowerInfo {
+            level,
+            charging: false,
         }
+    }
 
-        for event in event_pump.poll_iter() {
-            use sdl2::event::Event;
-            match event {
-                Event::ControllerDeviceAdded { which, .. } => {
-                    let gamepad = self.controller_subsystem.open(which);
-                    if let Ok(gamepad) = gamepad {
-                        #[cfg(debug_assertions)]
-                        let name = gamepad.name();
-
-                        gamepads.insert(
-                            GamepadId(gamepad.instance_id()),
-                            Gamepad::new(Some(OwnedImplementationGamepad(gamepad))),
-                        );
-
-                        #[cfg(debug_assertions)]
-                        println!("Added gamepad \"{}\"", name);
-                    }
+    /// Only devices with a gamepad mapping expose their sensors through SDL2.
+    pub fn has_sensor(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+    ) -> bool {
+        match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                controller.has_sensor(sensor_type(sensor))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_sensor_enabled(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+        enabled: bool,
+    ) -> Result<()> {
+        match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => controller
+                .sensor_set_enabled(sensor_type(sensor), enabled)
+                .map_err(|error| Error::Backend(error.to_string())),
+            Some(OwnedImplementationGamepad::Joystick(_)) => {
+                Err(Error::Unsupported("gamepad has no motion sensors".into()))
+            }
+            None => Err(Error::DeviceNotFound(id)),
+        }
+    }
+
+    pub fn sensor_data(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        sensor: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        let Some(OwnedImplementationGamepad::Controller(controller, _)) = gamepad else {
+            return None;
+        };
+        let mut data = [0.0; 3];
+        controller
+            .sensor_get_data(sensor_type(sensor), &mut data)
+            .ok()
+            .map(|_| data)
+    }
+
+    /// Only devices with a gamepad mapping expose their touchpads through SDL2.
+    pub fn touchpad_count(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> usize {
+        match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                let controller = raw_controller(controller);
+                if controller.is_null() {
+                    return 0;
                 }
-                Event::ControllerDeviceRemoved { which, .. } => {
-                    #[cfg(debug_assertions)]
-                    let name = gamepads
-                        .get(&GamepadId(which))
-                        .unwrap()
-                        .owned_internal_gamepad
-                        .as_ref()
-                        .unwrap()
-                        .0
-                        .name();
-
-                    gamepads.remove(&GamepadId(which));
-
-                    #[cfg(debug_assertions)]
-                    println!("Removed gamepad \"{}\"", name);
+                // SAFETY: `controller` is open for as long as the borrow of it.
+                let count = unsafe { sdl2::sys::SDL_GameControllerGetNumTouchpads(controller) };
+                count.max(0) as usize
+            }
+            _ => 0,
+        }
+    }
+
+    /// SDL2 reports no clicks of touchpads other than the first, so those are never clicked.
+    pub fn read_touchpad(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        touchpad: usize,
+        fingers: &mut Vec<TouchFinger>,
+    ) -> bool {
+        fingers.clear();
+        let Some(OwnedImplementationGamepad::Controller(controller, _)) = gamepad else {
+            return false;
+        };
+        let controller = raw_controller(controller);
+        if controller.is_null() {
+            return false;
+        }
+        let touchpad = touchpad as i32;
+        // SAFETY: `controller` is open for as long as the borrow of it, and every pointer passed
+        // to SDL points to a live local.
+        unsafe {
+            let count = sdl2::sys::SDL_GameControllerGetNumTouchpadFingers(controller, touchpad);
+            for slot in 0..count.max(0) {
+                let (mut down, mut x, mut y, mut pressure) = (0, 0.0, 0.0, 0.0);
+                let result = sdl2::sys::SDL_GameControllerGetTouchpadFinger(
+                    controller,
+                    touchpad,
+                    slot,
+                    &mut down,
+                    &mut x,
+                    &mut y,
+                    &mut pressure,
+                );
+                if result == 0 && down != 0 {
+                    fingers.push(TouchFinger {
+                        slot: slot as usize,
+                        x,
+                        y,
+                        pressure,
+                    });
                 }
-                Event::ControllerAxisMotion {
-                    which, axis, value, ..
-                } => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(which)) {
-                        gamepad
-                            .analog_inputs
-                            .set(axis, AnalogInputValue::from(value));
-                    }
+            }
+        }
+        false
+    }
+
+    /// Devices with a gamepad mapping have the buttons it maps. Generic joysticks have the buttons
+    /// of the default joystick mapping up to their number of buttons, and the d-pad if they have
+    /// a hat.
+    pub fn has_button(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        button: Button,
+    ) -> bool {
+        match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                let (Some(native), controller) =
+                    (native_button(button), raw_controller(controller))
+                else {
+                    return false;
+                };
+                // SAFETY: `controller` is open for as long as the borrow of it.
+                !controller.is_null()
+                    && unsafe { sdl2::sys::SDL_GameControllerHasButton(controller, native.to_ll()) }
+                        == sdl2::sys::SDL_bool::SDL_TRUE
+            }
+            Some(OwnedImplementationGamepad::Joystick(joystick)) => {
+                let count = (joystick.num_buttons() as usize).min(JOYSTICK_BUTTONS.len());
+                JOYSTICK_BUTTONS[..count].contains(&button)
+                    || (joystick.num_hats() > 0
+                        && HAT_BUTTONS
+                            .iter()
+                            .any(|&(_, hat_button)| hat_button == button))
+            }
+            None => false,
+        }
+    }
+
+    /// Reads the device as a joystick, before the gamepad mapping. Hats are not included.
+    pub fn read_raw(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+        buttons: &mut Vec<bool>,
+        axes: &mut Vec<f32>,
+    ) {
+        buttons.clear();
+        axes.clear();
+        let joystick = match gamepad {
+            Some(OwnedImplementationGamepad::Controller(_, joystick)) => joystick.as_ref(),
+            Some(OwnedImplementationGamepad::Joystick(joystick)) => Some(joystick),
+            None => None,
+        };
+        let Some(joystick) = joystick else {
+            return;
+        };
+        buttons.extend(
+            (0..joystick.num_buttons()).map(|index| joystick.button(index).unwrap_or_default()),
+        );
+        axes.extend(
+            (0..joystick.num_axes()).map(|index| {
+                AnalogInputValue::from(joystick.axis(index).unwrap_or_default()).get()
+            }),
+        );
+    }
+
+    pub fn has_led(&self, _: GamepadId, gamepad: Option<&OwnedImplementationGamepad>) -> bool {
+        match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                let controller = raw_controller(controller);
+                // SAFETY: `controller` is open for as long as the borrow of it.
+                !controller.is_null()
+                    && unsafe { sdl2::sys::SDL_GameControllerHasLED(controller) }
+                        == sdl2::sys::SDL_bool::SDL_TRUE
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_led(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        (red, green, blue): (u8, u8, u8),
+    ) -> Result<()> {
+        let controller = match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => {
+                raw_controller(controller)
+            }
+            Some(OwnedImplementationGamepad::Joystick(_)) => {
+                return Err(Error::Unsupported("gamepad has no LED".into()))
+            }
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        if controller.is_null() {
+            return Err(Error::DeviceNotFound(id));
+        }
+        // SAFETY: `controller` is open for as long as the borrow of it.
+        match unsafe { sdl2::sys::SDL_GameControllerSetLED(controller, red, green, blue) } {
+            0 => Ok(()),
+            _ => Err(Error::Backend(sdl2::get_error())),
+        }
+    }
+
+    /// SDL2 ignores the index on devices without player LEDs.
+    pub fn set_player_index(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        index: Option<u8>,
+    ) -> Result<()> {
+        let instance_id = match gamepad {
+            Some(OwnedImplementationGamepad::Controller(controller, _)) => controller.instance_id(),
+            Some(OwnedImplementationGamepad::Joystick(joystick)) => joystick.instance_id(),
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        // SAFETY: looking up an instance ID has no preconditions, and the joystick it returns is
+        // open for as long as the borrow of the gamepad.
+        unsafe {
+            let joystick = sdl2::sys::SDL_JoystickFromInstanceID(instance_id as i32);
+            if joystick.is_null() {
+                return Err(Error::DeviceNotFound(id));
+            }
+            sdl2::sys::SDL_JoystickSetPlayerIndex(joystick, index.map_or(-1, i32::from));
+        }
+        Ok(())
+    }
+
+    /// Only the DualSense has adaptive triggers that SDL2 can drive.
+    pub fn supports_trigger_effects(
+        &self,
+        _: GamepadId,
+        gamepad: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        let Some(OwnedImplementationGamepad::Controller(controller, _)) = gamepad else {
+            return false;
+        };
+        let controller = raw_controller(controller);
+        // SAFETY: `controller` is open for as long as the borrow of it.
+        !controller.is_null()
+            && unsafe { sdl2::sys::SDL_GameControllerGetType(controller) }
+                == sdl2::sys::SDL_GameControllerType::SDL_CONTROLLER_TYPE_PS5
+    }
+
+    pub fn set_trigger_effect(
+        &mut self,
+        id: GamepadId,
+        gamepad: Option<&mut OwnedImplementationGamepad>,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Result<()> {
+        let Some(OwnedImplementationGamepad::Controller(controller, _)) = gamepad else {
+            return Err(Error::Unsupported(
+                "gamepad has no adaptive triggers".into(),
+            ));
+        };
+        let controller = raw_controller(controller);
+        if controller.is_null() {
+            return Err(Error::DeviceNotFound(id));
+        }
+
+        // The output report of the DualSense, as laid out by `DS5EffectsState_t` in SDL2. Only
+        // the effect of the trigger flagged in the first byte is applied.
+        let mut report = [0u8; 47];
+        let (flag, offset) = match trigger {
+            Trigger::Right => (0x04, 10),
+            Trigger::Left => (0x08, 21),
+        };
+        report[0] = flag;
+        report[offset..offset + 11].copy_from_slice(&dualsense_trigger_effect(effect));
+
+        // SAFETY: `controller` is open for as long as the borrow of it, and `report` outlives
+        // the call.
+        let result = unsafe {
+            SDL_GameControllerSendEffect(controller, report.as_ptr().cast(), report.len() as i32)
+        };
+        match result {
+            0 => Ok(()),
+            _ => Err(Error::Backend(sdl2::get_error())),
+        }
+    }
+
+    /// Handles an event read from an event pump owned by the application.
+    pub fn handle_event(&mut self, event: &sdl2::event::Event) {
+        if let Some(mut events) = self.external_events.take() {
+            self.translate(event, &mut events);
+            self.external_events = Some(events);
+        }
+    }
+
+    fn translate(&mut self, event: &sdl2::event::Event, events: &mut Vec<(Duration, Event)>) {
+        use sdl2::event::Event as SdlEvent;
+        let time = Duration::from_millis(event.get_timestamp().into());
+        match *event {
+            SdlEvent::ControllerDeviceAdded { which, .. } => {
+                let gamepad = self.controller_subsystem.open(which);
+                #[cfg(feature = "tracing")]
+                if let Err(error) = &gamepad {
+                    tracing::warn!(which, %error, "failed to open gamepad");
                 }
-                Event::ControllerButtonDown { which, button, .. } => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(which)) {
-                        gamepad.digital_inputs.activate(button);
-                    }
+                if let Ok(mut gamepad) = gamepad {
+                    let joystick = self.joystick_subsystem.open(which).ok();
+                    let guid = self
+                        .joystick_subsystem
+                        .device_guid(which)
+                        .map(convert_guid)
+                        .unwrap_or_default();
+                    // SDL has no direct query, but rumbling at zero intensity fails on gamepads
+                    // without rumble motors.
+                    let supports_rumble = gamepad.set_rumble(0, 0, 0).is_ok();
+                    events.push((
+                        time,
+                        Event::Connected {
+                            id: gamepad_id(gamepad.instance_id()),
+                            name: gamepad.name(),
+                            guid,
+                            owned_internal_gamepad: Some(OwnedImplementationGamepad::Controller(
+                                gamepad, joystick,
+                            )),
+                            supports_rumble,
+                        },
+                    ));
+                }
+            }
+            SdlEvent::ControllerDeviceRemoved { which, .. } => {
+                events.push((time, Event::Disconnected(gamepad_id(which))));
+            }
+            // Joysticks with a gamepad mapping are handled through the controller events above.
+            SdlEvent::JoyDeviceAdded { which, .. }
+                if !self.controller_subsystem.is_game_controller(which) =>
+            {
+                let joystick = self.joystick_subsystem.open(which);
+                #[cfg(feature = "tracing")]
+                if let Err(error) = &joystick {
+                    tracing::warn!(which, %error, "failed to open joystick");
+                }
+                if let Ok(mut joystick) = joystick {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(
+                        name = %joystick.name(),
+                        buttons = joystick.num_buttons(),
+                        hats = joystick.num_hats(),
+                        "using default mapping for joystick"
+                    );
+                    self.joystick_hats.insert(joystick.instance_id(), 0);
+                    let supports_rumble = joystick.set_rumble(0, 0, 0).is_ok();
+                    events.push((
+                        time,
+                        Event::Connected {
+                            id: gamepad_id(joystick.instance_id()),
+                            name: joystick.name(),
+                            guid: convert_guid(joystick.guid()),
+                            owned_internal_gamepad: Some(OwnedImplementationGamepad::Joystick(
+                                joystick,
+                            )),
+                            supports_rumble,
+                        },
+                    ));
+                }
+            }
+            SdlEvent::JoyDeviceRemoved { which, .. } if self.joystick_hats.contains_key(&which) => {
+                self.joystick_hats.remove(&which);
+                events.push((time, Event::Disconnected(gamepad_id(which))));
+            }
+            SdlEvent::JoyAxisMotion {
+                which,
+                axis_idx,
+                value,
+                ..
+            } if self.joystick_hats.contains_key(&which) => {
+                if let Some(&axis) = JOYSTICK_AXES.get(usize::from(axis_idx)) {
+                    events.push((
+                        time,
+                        Event::AxisMotion(gamepad_id(which), axis, AnalogInputValue::from(value)),
+                    ));
                 }
-                Event::ControllerButtonUp { which, button, .. } => {
-                    if let Some(gamepad) = gamepads.get_mut(&GamepadId(which)) {
-                        gamepad.digital_inputs.deactivate(button);
+            }
+            SdlEvent::JoyButtonDown {
+                which, button_idx, ..
+            } if self.joystick_hats.contains_key(&which) => {
+                if let Some(&button) = JOYSTICK_BUTTONS.get(usize::from(button_idx)) {
+                    events.push((time, Event::ButtonDown(gamepad_id(which), button)));
+                }
+            }
+            SdlEvent::JoyButtonUp {
+                which, button_idx, ..
+            } if self.joystick_hats.contains_key(&which) => {
+                if let Some(&button) = JOYSTICK_BUTTONS.get(usize::from(button_idx)) {
+                    events.push((time, Event::ButtonUp(gamepad_id(which), button)));
+                }
+            }
+            SdlEvent::JoyHatMotion {
+                which,
+                hat_idx: 0,
+                state,
+                ..
+            } => {
+                if let Some(previous) = self.joystick_hats.get_mut(&which) {
+                    let state = state as u8;
+                    for (bit, button) in HAT_BUTTONS {
+                        let id = gamepad_id(which);
+                        match (*previous & bit != 0, state & bit != 0) {
+                            (false, true) => events.push((time, Event::ButtonDown(id, button))),
+                            (true, false) => events.push((time, Event::ButtonUp(id, button))),
+                            _ => (),
+                        }
                     }
+                    *previous = state;
                 }
-                _ => (),
             }
+            SdlEvent::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                events.push((
+                    time,
+                    Event::AxisMotion(
+                        gamepad_id(which),
+                        convert_axis(axis),
+                        AnalogInputValue::from(value),
+                    ),
+                ));
+            }
+            SdlEvent::ControllerButtonDown { which, button, .. } => {
+                let button = convert_button(button);
+                events.push((time, Event::ButtonDown(gamepad_id(which), button)));
+            }
+            SdlEvent::ControllerButtonUp { which, button, .. } => {
+                let button = convert_button(button);
+                events.push((time, Event::ButtonUp(gamepad_id(which), button)));
+            }
+            _ => (),
+        }
+    }
+}
+
+impl super::NativeBackend for ImplementationContext {
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()> {
+        if let Some(external_events) = &mut self.external_events {
+            events.append(external_events);
+            return Ok(());
+        }
+
+        let mut event_pump = self.sdl_context.event_pump().map_err(Error::Backend)?;
+        for event in event_pump.poll_iter() {
+            self.translate(&event, events);
         }
 
         Ok(())
     }
+
+    fn time(&self) -> Duration {
+        Duration::from_millis(self.timer_subsystem.ticks().into())
+    }
+}
+
+fn sensor_type(sensor: MotionSensor) -> sdl2::sensor::SensorType {
+    match sensor {
+        MotionSensor::Gyroscope => sdl2::sensor::SensorType::Gyroscope,
+        MotionSensor::Accelerometer => sdl2::sensor::SensorType::Accelerometer,
+    }
+}
+
+/// Gets the SDL handle of an open controller, for the parts of the SDL API that the `sdl2`
+/// crate does not wrap.
+fn raw_controller(
+    controller: &sdl2::controller::GameController,
+) -> *mut sdl2::sys::SDL_GameController {
+    // SAFETY: looking up an instance ID has no preconditions, and returns null if no controller
+    // has it.
+    unsafe { sdl2::sys::SDL_GameControllerFromInstanceID(controller.instance_id() as i32) }
+}
+
+/// Encodes a trigger effect in the simple modes of the DualSense, as used by SDL2's test
+/// program.
+fn dualsense_trigger_effect(effect: TriggerEffect) -> [u8; 11] {
+    let byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0) as u8;
+    let mut data = [0; 11];
+    match effect {
+        TriggerEffect::Off => data[0] = 0x05,
+        TriggerEffect::Resistance { start, strength } => {
+            data[..3].copy_from_slice(&[0x01, byte(start), byte(strength)]);
+        }
+        TriggerEffect::Weapon {
+            start,
+            end,
+            strength,
+        } => {
+            let end = end.max(start);
+            data[..4].copy_from_slice(&[0x02, byte(start), byte(end), byte(strength)]);
+        }
+        TriggerEffect::Vibration {
+            start,
+            amplitude,
+            frequency,
+        } => {
+            data[..4].copy_from_slice(&[0x06, frequency, byte(amplitude), byte(start)]);
+        }
+    }
+    data
+}
+
+// Added in SDL 2.0.16, after the bindings of `sdl2-sys`.
+extern "C" {
+    fn SDL_GameControllerSendEffect(
+        gamecontroller: *mut sdl2::sys::SDL_GameController,
+        data: *const std::ffi::c_void,
+        size: std::ffi::c_int,
+    ) -> std::ffi::c_int;
 }
@@ -0,0 +1,574 @@
+use crate::analog::AnalogInputValue;
+use crate::guid::Guid;
+use crate::input::Input;
+use crate::mapping_db::{self, MappedAxis, Mapping, MappingDatabase, Target};
+use crate::motion::MotionSensor;
+use crate::power::PowerInfo;
+use crate::touchpad::TouchFinger;
+use crate::trigger::{Trigger, TriggerEffect};
+use crate::{Axis, Button, GamepadId};
+
+use super::{Event, ImplementationId, NativeBackend, OwnedImplementationGamepad, RawId};
+use js_sys::Array;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use web_sys::{GamepadButton, GamepadHapticActuator};
+
+use crate::error::{Error, Result};
+
+/// Gets the id of a gamepad at an index of `navigator.getGamepads()`, which the browser keeps
+/// for as long as the gamepad stays connected.
+fn gamepad_id(index: u32) -> GamepadId {
+    GamepadId::native(ImplementationId::Wasm(index))
+}
+
+/// Gets the index of a gamepad in `navigator.getGamepads()`, or fails if another backend
+/// reports it.
+fn native_id(id: GamepadId) -> Result<u32> {
+    match id.0 {
+        RawId::Native(ImplementationId::Wasm(index)) => Ok(index),
+        _ => Err(Error::DeviceNotFound(id)),
+    }
+}
+
+/// The buttons the backend can report.
+pub const BUTTONS: &[Button] = &[
+    Button::South,
+    Button::East,
+    Button::West,
+    Button::North,
+    Button::LeftShoulder,
+    Button::RightShoulder,
+    Button::Select,
+    Button::Start,
+    Button::LeftStick,
+    Button::RightStick,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+    Button::Guide,
+    Button::Touchpad,
+    Button::Misc,
+    Button::Paddle1,
+    Button::Paddle2,
+    Button::Paddle3,
+    Button::Paddle4,
+];
+
+/// The buttons of the standard mapping, by index.
+///
+/// Indices 6 and 7 are the triggers, which are read as axes instead. Index 17 is not part of
+/// the standard, but browsers report the touchpad of PlayStation controllers there.
+const STANDARD_BUTTONS: [Option<Button>; 18] = [
+    Some(Button::South),
+    Some(Button::East),
+    Some(Button::West),
+    Some(Button::North),
+    Some(Button::LeftShoulder),
+    Some(Button::RightShoulder),
+    None,
+    None,
+    Some(Button::Select),
+    Some(Button::Start),
+    Some(Button::LeftStick),
+    Some(Button::RightStick),
+    Some(Button::DPadUp),
+    Some(Button::DPadDown),
+    Some(Button::DPadLeft),
+    Some(Button::DPadRight),
+    Some(Button::Guide),
+    Some(Button::Touchpad),
+];
+
+/// The axes of the standard mapping, by index.
+const STANDARD_AXES: [Axis; 4] = [Axis::LeftX, Axis::LeftY, Axis::RightX, Axis::RightY];
+
+/// The indices of the trigger buttons of the standard mapping, and the axes they are read as.
+const STANDARD_TRIGGERS: [(u32, Axis); 2] = [(6, Axis::TriggerLeft), (7, Axis::TriggerRight)];
+
+/// The state of a connected gamepad as of the previous poll.
+///
+/// The browser only exposes the current state of each gamepad, so changes are found by comparing
+/// against it.
+struct GamepadState {
+    timestamp: f64,
+    // The SDL mapping applied to a gamepad the browser has no standard mapping for.
+    mapping: Option<Mapping>,
+    buttons: [bool; Button::COUNT],
+    axes: [f32; Axis::COUNT],
+}
+
+pub struct ImplementationContext {
+    navigator: web_sys::Navigator,
+    performance: web_sys::Performance,
+    // The operating system, as named in SDL mappings.
+    platform: Option<&'static str>,
+    mappings: MappingDatabase,
+    added_mappings: Vec<Mapping>,
+    // Indexed like the array returned by `navigator.getGamepads()`.
+    gamepads: Vec<Option<GamepadState>>,
+}
+
+impl ImplementationContext {
+    pub fn new(mappings: Option<&str>) -> Result<Self> {
+        let window = web_sys::window()
+            .ok_or_else(|| Error::BackendInit("no window to read gamepads from".into()))?;
+        let performance = window.performance().ok_or_else(|| {
+            Error::BackendInit("the browser does not support the Performance API".into())
+        })?;
+        let navigator = window.navigator();
+        let platform = navigator.platform().ok().and_then(|platform| {
+            [
+                ("Win", "Windows"),
+                ("Mac", "Mac OS X"),
+                ("iP", "iOS"),
+                ("Android", "Android"),
+                ("Linux", "Linux"),
+            ]
+            .into_iter()
+            .find(|(prefix, _)| platform.starts_with(prefix))
+            .map(|(_, name)| name)
+        });
+
+        let mut context = Self {
+            navigator,
+            performance,
+            platform,
+            mappings: MappingDatabase::default(),
+            added_mappings: Vec::new(),
+            gamepads: Vec::new(),
+        };
+        if let Some(mappings) = mappings {
+            context.load_mappings(mappings)?;
+        }
+        Ok(context)
+    }
+
+    /// Replaces the mappings, keeping those added with [`Self::add_mappings`].
+    ///
+    /// Mappings only apply to gamepads the browser has no standard mapping for, from the next
+    /// time they connect.
+    pub fn load_mappings(&mut self, mappings: &str) -> Result<()> {
+        let mut database = MappingDatabase::default();
+        database.add(mapping_db::parse(mappings)?);
+        database.add(self.added_mappings.clone());
+        self.mappings = database;
+        Ok(())
+    }
+
+    /// Adds mappings, as with [`Self::load_mappings`].
+    pub fn add_mappings(&mut self, mappings: &str) -> Result<()> {
+        let mappings = mapping_db::parse(mappings)?;
+        self.added_mappings.extend(mappings.iter().cloned());
+        self.mappings.add(mappings);
+        Ok(())
+    }
+
+    /// Rumbles with the stronger of the two intensities, as browsers only drive both motors
+    /// together.
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        let actuator = self.actuator(id)?;
+        let intensity = f64::from(strong.max(weak).clamp(0.0, 1.0));
+        actuator
+            .pulse(intensity, duration.as_secs_f64() * 1000.0)
+            .map(|_| ())
+            .map_err(|error| Error::Backend(format!("failed to rumble gamepad: {:?}", error)))
+    }
+
+    /// Browsers do not expose the battery of gamepads.
+    pub fn power_info(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> PowerInfo {
+        PowerInfo::default()
+    }
+
+    pub fn stop_rumble(
+        &mut self,
+        id: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+    ) -> Result<()> {
+        self.actuator(id)?
+            .pulse(0.0, 0.0)
+            .map(|_| ())
+            .map_err(|error| Error::Backend(format!("failed to stop rumble: {:?}", error)))
+    }
+
+    /// Browsers do not expose the motion sensors of gamepads.
+    pub fn has_sensor(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_sensor_enabled(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: MotionSensor,
+        _: bool,
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "motion sensors are not supported by browsers".into(),
+        ))
+    }
+
+    pub fn sensor_data(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: MotionSensor,
+    ) -> Option<[f32; 3]> {
+        None
+    }
+
+    /// Browsers do not expose the touchpads of gamepads.
+    pub fn touchpad_count(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> usize {
+        0
+    }
+
+    pub fn read_touchpad(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        _: usize,
+        _: &mut Vec<TouchFinger>,
+    ) -> bool {
+        false
+    }
+
+    /// Gamepads with an added mapping have the buttons it maps. Gamepads with the standard mapping
+    /// have the buttons of the standard that the browser lists for them, which never include the
+    /// paddles or extra buttons.
+    pub fn has_button(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        button: Button,
+    ) -> bool {
+        let Ok(index) = native_id(id) else {
+            return false;
+        };
+        if let Some(Some(state)) = self.gamepads.get(index as usize) {
+            if let Some(mapping) = &state.mapping {
+                return mapping
+                    .bindings
+                    .iter()
+                    .any(|&(target, _)| target == Target::Button(button));
+            }
+        }
+        let Some(count) = self
+            .read_gamepads()
+            .ok()
+            .and_then(|gamepads| gamepads.get(index).dyn_into::<web_sys::Gamepad>().ok())
+            .map(|gamepad| gamepad.buttons().length() as usize)
+        else {
+            return false;
+        };
+        STANDARD_BUTTONS[..count.min(STANDARD_BUTTONS.len())].contains(&Some(button))
+    }
+
+    /// Reads the buttons and axes of the gamepad as the browser lists them, before any mapping.
+    pub fn read_raw(
+        &self,
+        id: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+        buttons: &mut Vec<bool>,
+        axes: &mut Vec<f32>,
+    ) {
+        buttons.clear();
+        axes.clear();
+        let Ok(index) = native_id(id) else {
+            return;
+        };
+        let Some(gamepad) = self
+            .read_gamepads()
+            .ok()
+            .and_then(|gamepads| gamepads.get(index).dyn_into::<web_sys::Gamepad>().ok())
+        else {
+            return;
+        };
+        buttons.extend(gamepad.buttons().iter().map(|button| {
+            button
+                .dyn_into::<GamepadButton>()
+                .is_ok_and(|button| button.pressed())
+        }));
+        axes.extend(
+            gamepad
+                .axes()
+                .iter()
+                .map(|axis| axis.as_f64().unwrap_or_default() as f32),
+        );
+    }
+
+    /// Browsers do not expose the LEDs of gamepads.
+    pub fn has_led(&self, _: GamepadId, _: Option<&OwnedImplementationGamepad>) -> bool {
+        false
+    }
+
+    pub fn set_led(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: (u8, u8, u8),
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "LEDs are not supported by browsers".into(),
+        ))
+    }
+
+    /// Browsers do not expose player LEDs, so the index is ignored.
+    pub fn set_player_index(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Option<u8>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Browsers do not expose adaptive triggers.
+    pub fn supports_trigger_effects(
+        &self,
+        _: GamepadId,
+        _: Option<&OwnedImplementationGamepad>,
+    ) -> bool {
+        false
+    }
+
+    pub fn set_trigger_effect(
+        &mut self,
+        _: GamepadId,
+        _: Option<&mut OwnedImplementationGamepad>,
+        _: Trigger,
+        _: TriggerEffect,
+    ) -> Result<()> {
+        Err(Error::Unsupported(
+            "trigger effects are not supported by browsers".into(),
+        ))
+    }
+
+    fn actuator(&self, id: GamepadId) -> Result<GamepadHapticActuator> {
+        let gamepads = self.read_gamepads()?;
+        let gamepad = gamepads
+            .get(native_id(id)?)
+            .dyn_into::<web_sys::Gamepad>()
+            .map_err(|_| Error::DeviceNotFound(id))?;
+        gamepad
+            .haptic_actuators()
+            .get(0)
+            .dyn_into::<GamepadHapticActuator>()
+            .map_err(|_| Error::Unsupported("gamepad does not support rumble".into()))
+    }
+
+    fn read_gamepads(&self) -> Result<Array> {
+        self.navigator
+            .get_gamepads()
+            .map_err(|error| Error::Backend(format!("failed to read gamepads: {:?}", error)))
+    }
+
+    /// Reports the changes to a gamepad since the previous poll.
+    fn read_gamepad(
+        &mut self,
+        index: u32,
+        gamepad: Option<web_sys::Gamepad>,
+        events: &mut Vec<(Duration, Event)>,
+    ) {
+        let id = gamepad_id(index);
+        let slot = index as usize;
+        if self.gamepads.len() <= slot {
+            self.gamepads.resize_with(slot + 1, || None);
+        }
+
+        let Some(gamepad) = gamepad.filter(web_sys::Gamepad::connected) else {
+            if self.gamepads[slot].take().is_some() {
+                events.push((self.time(), Event::Disconnected(id)));
+            }
+            return;
+        };
+
+        let timestamp = gamepad.timestamp();
+        let time = from_millis(timestamp);
+        let state = match &mut self.gamepads[slot] {
+            Some(state) if state.timestamp == timestamp => return,
+            Some(state) => state,
+            state @ None => {
+                let guid = guid_from_name(&gamepad.id());
+                let mapping = if gamepad.mapping() == web_sys::GamepadMappingType::Standard {
+                    None
+                } else {
+                    let mapping = self.mappings.find(guid, self.platform).cloned();
+                    #[cfg(feature = "tracing")]
+                    match &mapping {
+                        Some(mapping) => tracing::debug!(
+                            name = %gamepad.id(),
+                            mapping = %mapping.name,
+                            "browser has no standard mapping for gamepad, using added mapping"
+                        ),
+                        None => tracing::debug!(
+                            name = %gamepad.id(),
+                            "browser has no standard mapping for gamepad, assuming one"
+                        ),
+                    }
+                    mapping
+                };
+                let supports_rumble = gamepad.haptic_actuators().length() > 0;
+                events.push((
+                    time,
+                    Event::Connected {
+                        id,
+                        name: gamepad.id(),
+                        guid,
+                        owned_internal_gamepad: None,
+                        supports_rumble,
+                    },
+                ));
+                state.insert(GamepadState {
+                    timestamp,
+                    mapping,
+                    buttons: Default::default(),
+                    axes: Default::default(),
+                })
+            }
+        };
+        state.timestamp = timestamp;
+
+        let buttons = gamepad.buttons();
+        let button_at = |index: u32| buttons.get(index).dyn_into::<GamepadButton>().ok();
+        let axes = gamepad.axes();
+        let axis_at = |index: u32| axes.get(index).as_f64().unwrap_or_default() as f32;
+
+        let mut pressed = [false; Button::COUNT];
+        let mut values = [0.0; Axis::COUNT];
+        match &state.mapping {
+            Some(mapping) => {
+                let button_value =
+                    |index: u32| button_at(index).map_or(0.0, |input| input.value() as f32);
+                for &(target, source) in &mapping.bindings {
+                    let value = source.read(button_value, axis_at);
+                    match target {
+                        Target::Button(button) => pressed[button.index()] |= value > 0.5,
+                        Target::Axis { axis, half } => {
+                            let axis = mapped_axis(axis);
+                            let value = match half {
+                                _ if axis.is_trigger() && source.is_full_axis() => {
+                                    (value + 1.0) / 2.0
+                                }
+                                Some(false) => -value,
+                                _ => value,
+                            };
+                            values[axis.index()] += value;
+                        }
+                    }
+                }
+            }
+            None => {
+                for (index, button) in STANDARD_BUTTONS.iter().enumerate() {
+                    if let Some(button) = button {
+                        pressed[button.index()] =
+                            button_at(index as u32).is_some_and(|input| input.pressed());
+                    }
+                }
+                for (index, &axis) in STANDARD_AXES.iter().enumerate() {
+                    values[axis.index()] = axis_at(index as u32);
+                }
+                for &(index, axis) in &STANDARD_TRIGGERS {
+                    values[axis.index()] =
+                        button_at(index).map_or(0.0, |input| input.value() as f32);
+                }
+            }
+        }
+
+        for &button in BUTTONS {
+            let now_pressed = pressed[button.index()];
+            let last = &mut state.buttons[button.index()];
+            if now_pressed != *last {
+                *last = now_pressed;
+                let event = if now_pressed {
+                    Event::ButtonDown(id, button)
+                } else {
+                    Event::ButtonUp(id, button)
+                };
+                events.push((time, event));
+            }
+        }
+
+        for &axis in Axis::ALL {
+            let value = values[axis.index()];
+            let last = &mut state.axes[axis.index()];
+            if value != *last {
+                *last = value;
+                events.push((
+                    time,
+                    Event::AxisMotion(id, axis, AnalogInputValue::from(value)),
+                ));
+            }
+        }
+    }
+}
+
+/// Converts an axis of the SDL layout to the one the browser reports it as.
+fn mapped_axis(axis: MappedAxis) -> Axis {
+    match axis {
+        MappedAxis::LeftX => Axis::LeftX,
+        MappedAxis::LeftY => Axis::LeftY,
+        MappedAxis::RightX => Axis::RightX,
+        MappedAxis::RightY => Axis::RightY,
+        MappedAxis::LeftTrigger => Axis::TriggerLeft,
+        MappedAxis::RightTrigger => Axis::TriggerRight,
+    }
+}
+
+impl NativeBackend for ImplementationContext {
+    fn poll(&mut self, events: &mut Vec<(Duration, Event)>) -> Result<()> {
+        let gamepads = self.read_gamepads()?;
+        let count = (gamepads.length() as usize).max(self.gamepads.len());
+        for index in 0..count as u32 {
+            let gamepad = gamepads.get(index).dyn_into::<web_sys::Gamepad>().ok();
+            self.read_gamepad(index, gamepad, events);
+        }
+
+        Ok(())
+    }
+
+    fn time(&self) -> Duration {
+        from_millis(self.performance.now())
+    }
+}
+
+/// Builds a GUID from the vendor and product IDs that browsers put in the name of a gamepad,
+/// such as `Pad (Vendor: 045e Product: 02ea)` in Chromium or `045e-02ea-Pad` in Firefox.
+fn guid_from_name(name: &str) -> Guid {
+    let hex = |text: Option<&str>| {
+        let text = text?.get(..4)?;
+        u16::from_str_radix(text, 16).ok()
+    };
+    let chromium = || {
+        let vendor = hex(name.split("Vendor: ").nth(1))?;
+        let product = hex(name.split("Product: ").nth(1))?;
+        Some((vendor, product))
+    };
+    let firefox = || {
+        let mut parts = name.splitn(3, '-');
+        let vendor = hex(parts.next().filter(|part| part.len() == 4))?;
+        let product = hex(parts.next().filter(|part| part.len() == 4))?;
+        Some((vendor, product))
+    };
+    chromium()
+        .or_else(firefox)
+        .map(|(vendor, product)| Guid::from_ids(vendor, product))
+        .unwrap_or_default()
+}
+
+/// Converts a `DOMHighResTimeStamp`, in milliseconds since the page loaded.
+fn from_millis(timestamp: f64) -> Duration {
+    Duration::try_from_secs_f64(timestamp / 1000.0).unwrap_or_default()
+}
@@ -0,0 +1,85 @@
+//! Integration with the [Bevy](https://bevyengine.org) game engine.
+//!
+//! [`FishsticksPlugin`] inserts a [`GamepadContext`] as a non-send resource, updates it at the
+//! start of every frame, and publishes a [`FishsticksEvent`] for every change.
+//! Systems that need more than the events can read the context through
+//! `NonSend<GamepadContext>`.
+
+use crate::input::Input;
+use crate::{Button, GamepadContext, GamepadId};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::prelude::*;
+use std::collections::HashSet;
+
+/// A change to the state of a gamepad, published once per update.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FishsticksEvent {
+    /// A gamepad was connected.
+    Connected(GamepadId),
+    /// A gamepad was disconnected.
+    Disconnected(GamepadId),
+    /// A button was just activated.
+    ButtonPressed(GamepadId, Button),
+    /// A button was just deactivated.
+    ButtonReleased(GamepadId, Button),
+}
+
+/// The system set in which the gamepad context is updated, during [`PreUpdate`].
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FishsticksSystem;
+
+/// Plugin that drives a [`GamepadContext`].
+///
+/// Errors are logged with `tracing`, which Bevy's `LogPlugin` prints. If the context fails to
+/// initialize, no systems are added.
+#[derive(Default)]
+pub struct FishsticksPlugin;
+
+impl Plugin for FishsticksPlugin {
+    fn build(&self, app: &mut App) {
+        let context = match GamepadContext::new() {
+            Ok(context) => context,
+            Err(error) => {
+                tracing::error!(%error, "failed to initialize gamepad context");
+                return;
+            }
+        };
+
+        app.insert_non_send_resource(context)
+            .add_event::<FishsticksEvent>()
+            .add_systems(PreUpdate, update_context.in_set(FishsticksSystem));
+    }
+}
+
+fn update_context(
+    mut context: NonSendMut<GamepadContext>,
+    mut events: EventWriter<FishsticksEvent>,
+) {
+    let connected: HashSet<GamepadId> = context.gamepads().map(|(id, _)| id).collect();
+
+    if let Err(error) = context.update() {
+        tracing::error!(%error, "failed to update gamepad context");
+        return;
+    }
+
+    for &id in &connected {
+        if context.gamepad(id).is_none() {
+            events.send(FishsticksEvent::Disconnected(id));
+        }
+    }
+
+    for (id, gamepad) in context.gamepads() {
+        if !connected.contains(&id) {
+            events.send(FishsticksEvent::Connected(id));
+        }
+
+        for &button in Button::ALL {
+            if gamepad.digital_inputs.just_activated(button) {
+                events.send(FishsticksEvent::ButtonPressed(id, button));
+            }
+            if gamepad.digital_inputs.just_deactivated(button) {
+                events.send(FishsticksEvent::ButtonReleased(id, button));
+            }
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Configuration of a [`GamepadContext`] before it is initialized.
+//!
+//! Unless disabled with [`GamepadContextBuilder::env_overrides`], these environment variables
+//! override the settings given to the builder, so that settings can be changed without
+//! rebuilding the application:
+//!
+//! - `FISHSTICKS_BACKEND`: the backend to try first, see [`GamepadContextBuilder::prefer`].
+//!   Any compiled backend can be named, by its [`BackendKind::name`].
+//! - `FISHSTICKS_DEADZONE`: the analog deadzone, see [`GamepadContextBuilder::deadzone`].
+//! - `FISHSTICKS_DIGITAL_DEADZONE`: the digital deadzone, see
+//!   [`GamepadContextBuilder::digital_deadzone`].
+//! - `FISHSTICKS_MAPPINGS`: a mappings file, see [`GamepadContextBuilder::mappings`].
+//!
+//! A variable with an invalid value, such as a deadzone out of range or a backend that is not
+//! compiled in, is ignored in favor of the setting given to the builder, with a warning logged
+//! when the `tracing` feature is enabled.
+
+use crate::analog::{Coalescing, Smoothing};
+use crate::backend::ImplementationContext;
+use crate::config::{Config, Haptics};
+use crate::digital::KeyRepeat;
+use crate::error::{Error, Result};
+use crate::mappings::MappingsWatch;
+use crate::{Backend, BackendKind, GamepadContext, Mode};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A builder for a [`GamepadContext`], created with [`GamepadContext::builder`].
+///
+/// Every option left unset keeps the default of the corresponding setter on [`GamepadContext`].
+/// Settings given here also apply to gamepads connected after initialization.
+///
+/// Every backend enabled by the crate's features is compiled in, and [`prefer`](Self::prefer)
+/// and [`fallback`](Self::fallback) list the ones to try at runtime. Custom backends can be
+/// added alongside it with [`GamepadContextBuilder::backend`].
+#[derive(Default)]
+pub struct GamepadContextBuilder {
+    mode: Mode,
+    deadzone: Option<f32>,
+    deadzone_rescaling: Option<bool>,
+    digital_deadzone: Option<f32>,
+    extrapolation: Option<Duration>,
+    coalescing: Coalescing,
+    smoothing: Smoothing,
+    repeat: Option<KeyRepeat>,
+    debounce: Option<Duration>,
+    haptics: Haptics,
+    mappings: Option<PathBuf>,
+    watch_mappings: bool,
+    config: Option<Config>,
+    ignore_env: bool,
+    event_log: Option<Duration>,
+    backends: Vec<Box<dyn Backend>>,
+    backend_kinds: Vec<BackendKind>,
+    #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+    sdl_context: Option<sdl2::Sdl>,
+}
+
+impl GamepadContextBuilder {
+    /// Sets how the context reads events from its backend.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Adds a custom backend, run alongside the built-in one.
+    ///
+    /// See [`GamepadContext::add_backend`].
+    pub fn backend(mut self, backend: impl Backend + 'static) -> Self {
+        self.backends.push(Box::new(backend));
+        self
+    }
+
+    /// Tries a built-in backend before those added with [`fallback`](Self::fallback).
+    ///
+    /// Backends are tried in order until one initializes, skipping those that are not compiled
+    /// in, and the context fails with [`Error::BackendInit`] if none does. Without any backend
+    /// listed, the compiled ones are tried in the order of [`BackendKind::ALL`].
+    pub fn prefer(mut self, kind: BackendKind) -> Self {
+        self.backend_kinds.insert(0, kind);
+        self
+    }
+
+    /// Tries a built-in backend if those listed before it fail to initialize or are not
+    /// compiled in.
+    ///
+    /// See [`prefer`](Self::prefer).
+    pub fn fallback(mut self, kind: BackendKind) -> Self {
+        self.backend_kinds.push(kind);
+        self
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    ///
+    /// See [`GamepadContext::set_deadzone`].
+    pub fn deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = Some(deadzone);
+        self
+    }
+
+    /// Sets whether values outside the analog deadzone are rescaled.
+    ///
+    /// See [`GamepadContext::set_deadzone_rescaling`].
+    pub fn deadzone_rescaling(mut self, rescale: bool) -> Self {
+        self.deadzone_rescaling = Some(rescale);
+        self
+    }
+
+    /// Sets the digital deadzone for all analog inputs.
+    ///
+    /// See [`GamepadContext::set_digital_deadzone`].
+    pub fn digital_deadzone(mut self, deadzone: f32) -> Self {
+        self.digital_deadzone = Some(deadzone);
+        self
+    }
+
+    /// Enables extrapolation of analog inputs up to the given horizon.
+    ///
+    /// See [`GamepadContext::set_extrapolation`].
+    pub fn extrapolation(mut self, horizon: Duration) -> Self {
+        self.extrapolation = Some(horizon);
+        self
+    }
+
+    /// Sets how consecutive motion events for the same analog input are handled.
+    ///
+    /// See [`GamepadContext::set_coalescing`].
+    pub fn coalescing(mut self, coalescing: Coalescing) -> Self {
+        self.coalescing = coalescing;
+        self
+    }
+
+    /// Sets how analog inputs are smoothed against noise.
+    ///
+    /// See [`GamepadContext::set_smoothing`].
+    pub fn smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Makes held buttons of all gamepads repeat.
+    ///
+    /// See [`GamepadContext::set_default_repeat`].
+    pub fn repeat(mut self, repeat: KeyRepeat) -> Self {
+        self.repeat = Some(repeat);
+        self
+    }
+
+    /// Debounces the buttons of all gamepads.
+    ///
+    /// See [`GamepadContext::set_default_debounce`].
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = Some(debounce);
+        self
+    }
+
+    /// Sets the policy for rumble and adaptive trigger effects.
+    ///
+    /// See [`GamepadContext::set_haptics`].
+    pub fn haptics(mut self, haptics: Haptics) -> Self {
+        self.haptics = haptics;
+        self
+    }
+
+    /// Applies every setting of a configuration, replacing any set before.
+    ///
+    /// The configuration is validated when the context is built.
+    pub fn config(mut self, config: Config) -> Self {
+        self.deadzone = Some(config.deadzone);
+        self.deadzone_rescaling = Some(config.deadzone_rescaling);
+        self.digital_deadzone = Some(config.digital_deadzone);
+        self.extrapolation = config.extrapolation;
+        self.coalescing = config.coalescing;
+        self.smoothing = config.smoothing;
+        self.repeat = config.repeat;
+        self.debounce = config.debounce;
+        self.haptics = config.haptics;
+        self.config = Some(config);
+        self
+    }
+
+    /// Loads additional gamepad mappings from a file in the
+    /// [SDL_GameControllerDB](https://github.com/gabomdq/SDL_GameControllerDB) format.
+    pub fn mappings(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mappings = Some(path.into());
+        self
+    }
+
+    /// Reloads the mappings file whenever it changes on disk, remapping connected gamepads.
+    ///
+    /// This is meant for developing mappings; it has no effect unless a file is given with
+    /// [`mappings`](Self::mappings). With the `gilrs` backend, every gamepad is reported as
+    /// disconnected and connected again when the mappings are reloaded.
+    ///
+    /// A file that fails to read or holds a malformed mapping does not fail
+    /// [`GamepadContext::update`]; the previous mappings stay in place, the failure is logged, and
+    /// the file is tried again until it loads.
+    pub fn watch_mappings(mut self, watch: bool) -> Self {
+        self.watch_mappings = watch;
+        self
+    }
+
+    /// Enables the event log, keeping entries for `retention`.
+    ///
+    /// See [`GamepadContext::enable_event_log`].
+    pub fn event_log(mut self, retention: Duration) -> Self {
+        self.event_log = Some(retention);
+        self
+    }
+
+    /// Sets whether the `FISHSTICKS_*` environment variables override the settings of the
+    /// builder. They do by default.
+    ///
+    /// See the [module documentation](self) for the supported variables.
+    pub fn env_overrides(mut self, enabled: bool) -> Self {
+        self.ignore_env = !enabled;
+        self
+    }
+
+    /// Uses an SDL context owned by the application.
+    ///
+    /// See [`GamepadContext::init_with_sdl`].
+    #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+    pub fn sdl_context(mut self, sdl_context: &sdl2::Sdl) -> Self {
+        self.sdl_context = Some(sdl_context.clone());
+        self
+    }
+
+    /// Initializes the gamepad context.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, err)
+    )]
+    pub fn build(mut self) -> Result<GamepadContext> {
+        if let Some(config) = &self.config {
+            config.validate()?;
+        }
+        if !self.ignore_env {
+            self.apply_env();
+        }
+
+        let mappings = match &self.mappings {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|error| {
+                Error::Io(format!(
+                    "failed to read mappings from {}: {}",
+                    path.display(),
+                    error
+                ))
+            })?),
+            None => None,
+        };
+        let mappings = mappings.as_deref();
+
+        let gamepad_system = self.open_backend(mappings)?;
+
+        let mut context = GamepadContext::with_backend(gamepad_system, self.mode);
+        for backend in self.backends {
+            context.add_boxed_backend(backend)?;
+        }
+        if let Some(retention) = self.event_log {
+            context.enable_event_log(retention);
+        }
+        if self.watch_mappings {
+            let now = context.time();
+            context.mappings_watch = self
+                .mappings
+                .zip(mappings)
+                .map(|(path, contents)| MappingsWatch::new(path, contents, now));
+        }
+        if let Some(deadzone) = self.deadzone {
+            context.set_deadzone(deadzone);
+        }
+        if let Some(rescale) = self.deadzone_rescaling {
+            context.set_deadzone_rescaling(rescale);
+        }
+        if let Some(deadzone) = self.digital_deadzone {
+            context.set_digital_deadzone(deadzone);
+        }
+        context.set_extrapolation(self.extrapolation);
+        context.set_coalescing(self.coalescing);
+        context.set_smoothing(self.smoothing);
+        context.set_default_repeat(self.repeat);
+        context.set_default_debounce(self.debounce);
+        context.set_haptics(self.haptics);
+
+        Ok(context)
+    }
+
+    /// Opens the first of the listed backends that initializes, or of the compiled ones if none
+    /// is listed, collecting why each before it failed.
+    fn open_backend(&self, mappings: Option<&str>) -> Result<ImplementationContext> {
+        let kinds = if self.backend_kinds.is_empty() {
+            BackendKind::ALL
+        } else {
+            &self.backend_kinds[..]
+        };
+
+        let mut failures = Vec::new();
+        for &kind in kinds {
+            if !kind.is_compiled() {
+                if !self.backend_kinds.is_empty() {
+                    failures.push(format!("{} is not compiled in", kind.name()));
+                }
+                continue;
+            }
+            let result = ImplementationContext::open(
+                kind,
+                mappings,
+                #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+                self.sdl_context.as_ref(),
+            );
+            match result {
+                Ok(gamepad_system) => return Ok(gamepad_system),
+                Err(Error::BackendInit(message)) => {
+                    failures.push(format!("{}: {}", kind.name(), message))
+                }
+                Err(error) => failures.push(format!("{}: {}", kind.name(), error)),
+            }
+            #[cfg(feature = "tracing")]
+            tracing::warn!(backend = kind.name(), "backend failed to initialize");
+        }
+        Err(Error::BackendInit(failures.join("; ")))
+    }
+
+    /// Applies the overrides of the environment, ignoring any that is invalid so that a mistyped
+    /// variable cannot leave the game without input.
+    fn apply_env(&mut self) {
+        if let Some(name) = env_var("FISHSTICKS_BACKEND") {
+            match BackendKind::from_name(&name).filter(|kind| kind.is_compiled()) {
+                Some(kind) => {
+                    // Keep the backends that would have been tried otherwise as fallbacks.
+                    if self.backend_kinds.is_empty() {
+                        self.backend_kinds = BackendKind::ALL
+                            .iter()
+                            .copied()
+                            .filter(|kind| kind.is_compiled())
+                            .collect();
+                    }
+                    self.backend_kinds.retain(|&other| other != kind);
+                    self.backend_kinds.insert(0, kind);
+                }
+                None => ignore_env(
+                    "FISHSTICKS_BACKEND",
+                    &format!("the {} backend is not compiled in", name),
+                ),
+            }
+        }
+
+        if let Some(deadzone) = env_deadzone("FISHSTICKS_DEADZONE", |deadzone| Config {
+            deadzone,
+            ..Config::default()
+        }) {
+            self.deadzone = Some(deadzone);
+        }
+        if let Some(deadzone) =
+            env_deadzone("FISHSTICKS_DIGITAL_DEADZONE", |digital_deadzone| Config {
+                digital_deadzone,
+                ..Config::default()
+            })
+        {
+            self.digital_deadzone = Some(deadzone);
+        }
+        if let Some(path) = std::env::var_os("FISHSTICKS_MAPPINGS") {
+            let path = PathBuf::from(path);
+            if path.is_file() {
+                self.mappings = Some(path);
+            } else {
+                ignore_env(
+                    "FISHSTICKS_MAPPINGS",
+                    &format!("{} is not a file", path.display()),
+                );
+            }
+        }
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    match std::env::var(name) {
+        Ok(value) => {
+            #[cfg(feature = "tracing")]
+            tracing::info!(name, %value, "overriding setting from environment");
+            Some(value)
+        }
+        Err(std::env::VarError::NotPresent) => None,
+        Err(error) => {
+            ignore_env(name, &error.to_string());
+            None
+        }
+    }
+}
+
+/// Reads a deadzone from the environment, checking it with the config `with_deadzone` gives.
+fn env_deadzone(name: &str, with_deadzone: impl FnOnce(f32) -> Config) -> Option<f32> {
+    let value = env_var(name)?;
+    let result = value
+        .trim()
+        .parse()
+        .map_err(|error| format!("invalid value {:?}: {}", value, error))
+        .and_then(|deadzone| match with_deadzone(deadzone).validate() {
+            Ok(()) => Ok(deadzone),
+            Err(error) => Err(error.to_string()),
+        });
+    match result {
+        Ok(deadzone) => Some(deadzone),
+        Err(reason) => {
+            ignore_env(name, &reason);
+            None
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn ignore_env(name: &str, reason: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(name, reason, "ignoring invalid setting from environment");
+}
@@ -0,0 +1,134 @@
+//! The buttons of a gamepad, independent of the backend.
+
+use crate::input::Input;
+
+/// A button, named by its position on a standard gamepad layout.
+///
+/// Every backend maps its own buttons into this layout, so face buttons are named by direction
+/// rather than by label: [`South`](Button::South) is A on an Xbox controller, Cross on a
+/// PlayStation controller and B on a Nintendo controller. Not every backend reports every
+/// button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Button {
+    /// The bottom face button.
+    South,
+    /// The right face button.
+    East,
+    /// The left face button.
+    West,
+    /// The top face button.
+    North,
+    /// The left of the center buttons, such as Back, Select, Share or Minus.
+    Select,
+    /// The right of the center buttons, such as Start, Options or Plus.
+    Start,
+    /// The button in the middle of the gamepad, such as the Xbox, PS or Home button.
+    Guide,
+    /// Pressing the left thumbstick.
+    LeftStick,
+    /// Pressing the right thumbstick.
+    RightStick,
+    /// The upper left shoulder button.
+    LeftShoulder,
+    /// The upper right shoulder button.
+    RightShoulder,
+    /// The lower left shoulder button, on gamepads where it is digital.
+    LeftTrigger,
+    /// The lower right shoulder button, on gamepads where it is digital.
+    RightTrigger,
+    /// Up on the d-pad.
+    DPadUp,
+    /// Down on the d-pad.
+    DPadDown,
+    /// Left on the d-pad.
+    DPadLeft,
+    /// Right on the d-pad.
+    DPadRight,
+    /// An extra button, such as Share on an Xbox Series controller or Capture on a Switch Pro
+    /// controller.
+    Misc,
+    /// The first rear paddle.
+    Paddle1,
+    /// The second rear paddle.
+    Paddle2,
+    /// The third rear paddle.
+    Paddle3,
+    /// The fourth rear paddle.
+    Paddle4,
+    /// Pressing the touchpad.
+    Touchpad,
+    /// The extra face button found on some older gamepads.
+    C,
+    /// The extra shoulder button found on some older gamepads.
+    Z,
+}
+
+impl Button {
+    /// Gets the name of the button, as used for button prompts.
+    pub fn name(self) -> &'static str {
+        match self {
+            Button::South => "south",
+            Button::East => "east",
+            Button::West => "west",
+            Button::North => "north",
+            Button::Select => "back",
+            Button::Start => "start",
+            Button::Guide => "guide",
+            Button::LeftStick => "left_stick",
+            Button::RightStick => "right_stick",
+            Button::LeftShoulder => "left_shoulder",
+            Button::RightShoulder => "right_shoulder",
+            Button::LeftTrigger => "left_trigger",
+            Button::RightTrigger => "right_trigger",
+            Button::DPadUp => "dpad_up",
+            Button::DPadDown => "dpad_down",
+            Button::DPadLeft => "dpad_left",
+            Button::DPadRight => "dpad_right",
+            Button::Misc => "misc",
+            Button::Paddle1 => "paddle1",
+            Button::Paddle2 => "paddle2",
+            Button::Paddle3 => "paddle3",
+            Button::Paddle4 => "paddle4",
+            Button::Touchpad => "touchpad",
+            Button::C => "c",
+            Button::Z => "z",
+        }
+    }
+}
+
+impl Input for Button {
+    const COUNT: usize = Button::Z as usize + 1;
+
+    const ALL: &'static [Self] = &[
+        Button::South,
+        Button::East,
+        Button::West,
+        Button::North,
+        Button::Select,
+        Button::Start,
+        Button::Guide,
+        Button::LeftStick,
+        Button::RightStick,
+        Button::LeftShoulder,
+        Button::RightShoulder,
+        Button::LeftTrigger,
+        Button::RightTrigger,
+        Button::DPadUp,
+        Button::DPadDown,
+        Button::DPadLeft,
+        Button::DPadRight,
+        Button::Misc,
+        Button::Paddle1,
+        Button::Paddle2,
+        Button::Paddle3,
+        Button::Paddle4,
+        Button::Touchpad,
+        Button::C,
+        Button::Z,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
@@ -0,0 +1,103 @@
+//! Axes driven by pairs of buttons, for gamepads without analog sticks.
+//!
+//! Movement code written against an axis, such as [`Axis`] values of the left stick, does not
+//! move on a digital-only gamepad. A [`ButtonAxis`] added with
+//! [`GamepadContext::set_button_axis`](crate::GamepadContext::set_button_axis) drives an axis of
+//! every gamepad from two of its buttons, such as the left and right of the d-pad, optionally
+//! ramping up and down like a stick being pushed rather than jumping between values.
+
+use crate::analog::{AnalogInput, AnalogInputValue, Sample};
+use crate::digital::DigitalInput;
+use crate::{Axis, Button};
+use std::time::Duration;
+
+/// A pair of buttons driving an axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonAxis {
+    /// The button that moves the axis towards [`ANALOG_MIN`](crate::analog::ANALOG_MIN).
+    pub negative: Button,
+    /// The button that moves the axis towards [`ANALOG_MAX`](crate::analog::ANALOG_MAX).
+    pub positive: Button,
+    /// How long the axis takes to move from the center to either end while a button is held.
+    pub ramp_up: Duration,
+    /// How long the axis takes to return from either end to the center once released.
+    pub ramp_down: Duration,
+}
+
+impl ButtonAxis {
+    /// Drives an axis from two buttons, jumping straight to each value. Holding both buttons
+    /// centers the axis.
+    pub fn new(negative: Button, positive: Button) -> Self {
+        Self {
+            negative,
+            positive,
+            ramp_up: Duration::ZERO,
+            ramp_down: Duration::ZERO,
+        }
+    }
+
+    /// Sets how long the axis takes to move to the end and back to the center.
+    pub fn with_ramp(mut self, ramp_up: Duration, ramp_down: Duration) -> Self {
+        self.ramp_up = ramp_up;
+        self.ramp_down = ramp_down;
+        self
+    }
+
+    /// Moves `value` towards the value of the buttons over `elapsed`.
+    fn step(&self, value: f32, buttons: &DigitalInput<Button>, elapsed: Duration) -> f32 {
+        let target =
+            buttons.activated(self.positive) as i8 - buttons.activated(self.negative) as i8;
+        let target = f32::from(target);
+        let ramp = if target != 0.0 && (value == 0.0 || value.signum() == target.signum()) {
+            self.ramp_up
+        } else {
+            self.ramp_down
+        };
+        if ramp.is_zero() {
+            return target;
+        }
+        let step = elapsed.as_secs_f32() / ramp.as_secs_f32();
+        if value < target {
+            (value + step).min(target)
+        } else {
+            (value - step).max(target)
+        }
+    }
+}
+
+/// The values a gamepad's button axes have ramped to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ButtonAxisState {
+    values: Vec<f32>,
+    last_update: Option<Duration>,
+}
+
+impl ButtonAxisState {
+    /// Moves every button axis towards the value of its buttons, setting the axes whose values
+    /// changed.
+    pub(crate) fn update(
+        &mut self,
+        button_axes: &[(Axis, ButtonAxis)],
+        digital_inputs: &DigitalInput<Button>,
+        analog_inputs: &mut AnalogInput<Axis>,
+        time: Duration,
+    ) {
+        let elapsed = time.saturating_sub(self.last_update.unwrap_or(time));
+        self.last_update = Some(time);
+        self.values.resize(button_axes.len(), 0.0);
+
+        for (value, (axis, button_axis)) in self.values.iter_mut().zip(button_axes) {
+            let next = button_axis.step(*value, digital_inputs, elapsed);
+            if next != *value {
+                *value = next;
+                let sample = Sample::new(AnalogInputValue::from(next), time);
+                analog_inputs.set(*axis, sample);
+            }
+        }
+    }
+
+    /// Forgets the values, such as after the button axes changed.
+    pub(crate) fn reset(&mut self) {
+        self.values.clear();
+    }
+}
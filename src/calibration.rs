@@ -0,0 +1,234 @@
+//! Guided calibration of a gamepad's analog inputs.
+//!
+//! A [`Calibrator`] walks the player through three steps, which the game presents with its own
+//! UI: leaving the sticks and triggers untouched while their resting positions are measured,
+//! rotating the sticks and pressing the triggers fully while their extents are measured, and
+//! confirming the result. The resulting [`GamepadCalibration`] is handed to
+//! [`GamepadContext::set_calibration`](crate::GamepadContext::set_calibration), which applies it
+//! to that gamepad from then on, including after it reconnects.
+//!
+//! For a quicker setup, [`GamepadContext::begin_calibration`] runs the same steps on a timer and
+//! applies the result by itself, such as to compensate for the drift of a worn stick from a
+//! settings menu.
+//!
+//! [`GamepadContext::begin_calibration`]: crate::GamepadContext::begin_calibration
+
+use crate::analog::AxisCalibration;
+use crate::input::Input;
+use crate::{Axis, Gamepad, GamepadId};
+use std::time::Duration;
+
+/// The calibration of all analog inputs of a gamepad.
+///
+/// Enable the `serde` feature to store calibrations between sessions.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadCalibration {
+    /// The calibration of each calibrated axis.
+    pub axes: Vec<(Axis, AxisCalibration)>,
+}
+
+impl GamepadCalibration {
+    /// Gets the calibration of an axis, if it has one.
+    pub fn axis(&self, axis: Axis) -> Option<AxisCalibration> {
+        self.axes
+            .iter()
+            .find(|&&(other, _)| other == axis)
+            .map(|&(_, calibration)| calibration)
+    }
+}
+
+/// A step of a calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStep {
+    /// The player should leave every stick and trigger untouched.
+    Center,
+    /// The player should rotate every stick along its edge and press every trigger fully.
+    Extents,
+    /// The player should confirm the result, or start over.
+    Confirm,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Observation {
+    axis: Axis,
+    sum: f32,
+    count: u32,
+    min: f32,
+    max: f32,
+}
+
+impl Observation {
+    fn center(&self) -> f32 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f32
+        }
+    }
+}
+
+/// A state machine for calibrating a gamepad, driven by the game's UI.
+///
+/// The gamepad should be uncalibrated while it is measured, which
+/// [`GamepadContext::set_calibration`](crate::GamepadContext::set_calibration) with `None`
+/// ensures.
+#[derive(Debug, Clone)]
+pub struct Calibrator {
+    step: CalibrationStep,
+    observations: Vec<Observation>,
+}
+
+impl Calibrator {
+    /// Starts calibrating every axis.
+    pub fn new() -> Self {
+        Self::with_axes(Axis::ALL.iter().copied())
+    }
+
+    /// Starts calibrating the given axes.
+    pub fn with_axes(axes: impl IntoIterator<Item = Axis>) -> Self {
+        let observations = axes
+            .into_iter()
+            .map(|axis| Observation {
+                axis,
+                sum: 0.0,
+                count: 0,
+                min: 0.0,
+                max: 0.0,
+            })
+            .collect();
+
+        Self {
+            step: CalibrationStep::Center,
+            observations,
+        }
+    }
+
+    /// Gets the current step.
+    pub fn step(&self) -> CalibrationStep {
+        self.step
+    }
+
+    /// Measures the gamepad being calibrated.
+    ///
+    /// Call this once per update during the [`Center`](CalibrationStep::Center) and
+    /// [`Extents`](CalibrationStep::Extents) steps.
+    pub fn update(&mut self, gamepad: &Gamepad) {
+        for observation in self.observations.iter_mut() {
+            let value = gamepad.analog_inputs.raw_value(observation.axis);
+            match self.step {
+                CalibrationStep::Center => {
+                    observation.sum += value;
+                    observation.count += 1;
+                }
+                CalibrationStep::Extents => {
+                    observation.min = observation.min.min(value);
+                    observation.max = observation.max.max(value);
+                }
+                CalibrationStep::Confirm => (),
+            }
+        }
+    }
+
+    /// Moves on to the next step, such as when the player presses a button to continue.
+    ///
+    /// Does nothing in the [`Confirm`](CalibrationStep::Confirm) step.
+    pub fn next(&mut self) {
+        self.step = match self.step {
+            CalibrationStep::Center => {
+                // The extents always include the resting position.
+                for observation in self.observations.iter_mut() {
+                    let center = observation.center();
+                    observation.min = center;
+                    observation.max = center;
+                }
+                CalibrationStep::Extents
+            }
+            CalibrationStep::Extents | CalibrationStep::Confirm => CalibrationStep::Confirm,
+        };
+    }
+
+    /// Discards all measurements and returns to the [`Center`](CalibrationStep::Center) step.
+    pub fn restart(&mut self) {
+        let axes: Vec<Axis> = self.observations.iter().map(|o| o.axis).collect();
+        *self = Self::with_axes(axes);
+    }
+
+    /// Gets the calibration measured so far, such as for previewing it during the
+    /// [`Confirm`](CalibrationStep::Confirm) step.
+    pub fn calibration(&self) -> GamepadCalibration {
+        let axes = self
+            .observations
+            .iter()
+            .map(|observation| {
+                let calibration = AxisCalibration {
+                    center: observation.center(),
+                    min: observation.min,
+                    max: observation.max,
+                };
+                (observation.axis, calibration)
+            })
+            .collect();
+
+        GamepadCalibration { axes }
+    }
+
+    /// Finishes the calibration, returning the result if the player reached the
+    /// [`Confirm`](CalibrationStep::Confirm) step.
+    pub fn confirm(self) -> Option<GamepadCalibration> {
+        match self.step {
+            CalibrationStep::Confirm => Some(self.calibration()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Calibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A calibration run by the context on a timer, started with
+/// [`GamepadContext::begin_calibration`](crate::GamepadContext::begin_calibration).
+#[derive(Debug, Clone)]
+pub(crate) struct TimedCalibration {
+    pub(crate) gamepad: GamepadId,
+    calibrator: Calibrator,
+    step_duration: Duration,
+    step_started: Duration,
+}
+
+impl TimedCalibration {
+    pub(crate) fn new(gamepad: GamepadId, step_duration: Duration, time: Duration) -> Self {
+        Self {
+            gamepad,
+            calibrator: Calibrator::new(),
+            step_duration,
+            step_started: time,
+        }
+    }
+
+    pub(crate) fn step(&self) -> CalibrationStep {
+        self.calibrator.step()
+    }
+
+    /// Measures the gamepad, moving on to the next step once the current one has run for its
+    /// duration. Returns the calibration once both steps have run.
+    pub(crate) fn update(
+        &mut self,
+        gamepad: &Gamepad,
+        time: Duration,
+    ) -> Option<GamepadCalibration> {
+        self.calibrator.update(gamepad);
+        if time.saturating_sub(self.step_started) < self.step_duration {
+            return None;
+        }
+        self.calibrator.next();
+        self.step_started = time;
+        match self.calibrator.step() {
+            CalibrationStep::Confirm => Some(self.calibrator.calibration()),
+            _ => None,
+        }
+    }
+}
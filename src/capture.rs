@@ -0,0 +1,37 @@
+//! Routing of the Capture or Share button, for games with their own screenshot or clip hooks.
+
+use crate::GamepadId;
+use std::time::Duration;
+
+/// How presses of the capture button are routed.
+///
+/// On most platforms the button is free for the game to use, but some, such as handhelds
+/// running an overlay, reserve it for the operating system. Games can select the policy
+/// appropriate for each platform they ship on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CapturePolicy {
+    /// Presses are reported as [`CaptureEvent`]s and also activate the button as usual.
+    #[default]
+    Deliver,
+    /// Presses are dropped entirely.
+    Ignore,
+    /// The button is reserved by the operating system. Presses are reported as
+    /// [`CaptureEvent`]s marked as reserved, but never activate the button, so that bindings
+    /// on it do not fire while the operating system handles it.
+    Reserved,
+}
+
+/// A press of the capture button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureEvent {
+    /// The gamepad the button was pressed on.
+    pub gamepad: GamepadId,
+    /// When the button was pressed, on the clock of [`GamepadContext::time`].
+    ///
+    /// [`GamepadContext::time`]: crate::GamepadContext::time
+    pub time: Duration,
+    /// Whether the press was handled by the operating system, under
+    /// [`CapturePolicy::Reserved`].
+    pub reserved: bool,
+}
@@ -0,0 +1,148 @@
+//! Several physical devices acting as one logical gamepad.
+//!
+//! Some players split their input across devices, such as a flight stick with a separate
+//! throttle, or a pair of Joy-Cons. A [`CompositeGamepad`] reads the inputs of each of its
+//! source gamepads through a [`SourceMapping`], and combines them into one set of analog and
+//! digital inputs, which go through the same deadzones and state tracking as a gamepad's.
+
+use crate::analog::{AnalogInput, AnalogInputValue, Deadzone, Sample};
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use crate::{Axis, Button, GamepadContext, GamepadId};
+
+/// Which inputs of a source gamepad drive which inputs of a [`CompositeGamepad`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMapping {
+    axes: Vec<(Axis, Axis)>,
+    buttons: Vec<(Button, Button)>,
+}
+
+impl SourceMapping {
+    /// Creates a mapping with no inputs mapped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a mapping of every axis and button to itself.
+    pub fn identity() -> Self {
+        Self {
+            axes: Axis::ALL.iter().map(|&axis| (axis, axis)).collect(),
+            buttons: Button::ALL.iter().map(|&button| (button, button)).collect(),
+        }
+    }
+
+    /// Maps an axis of the source to an axis of the composite gamepad.
+    pub fn axis(mut self, from: Axis, to: Axis) -> Self {
+        self.axes.push((from, to));
+        self
+    }
+
+    /// Maps a button of the source to a button of the composite gamepad.
+    pub fn button(mut self, from: Button, to: Button) -> Self {
+        self.buttons.push((from, to));
+        self
+    }
+}
+
+/// One logical gamepad combining the inputs of several physical gamepads.
+///
+/// A button is pressed while it is pressed on any source mapped to it, and an axis takes the
+/// value furthest from the center among the sources mapped to it. Sources that are not connected
+/// count as released.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeGamepad {
+    /// Analog inputs, combined from the axes of the sources.
+    pub analog_inputs: AnalogInput<Axis>,
+    /// Digital inputs, combined from the buttons of the sources.
+    pub digital_inputs: DigitalInput<Button>,
+    sources: Vec<(GamepadId, SourceMapping)>,
+    values: Vec<f32>,
+}
+
+impl CompositeGamepad {
+    /// Creates a composite gamepad with no sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a gamepad as a source, replacing its mapping if it is one already.
+    pub fn add_source(&mut self, id: GamepadId, mapping: SourceMapping) {
+        match self.sources.iter_mut().find(|(source, _)| *source == id) {
+            Some((_, existing)) => *existing = mapping,
+            None => self.sources.push((id, mapping)),
+        }
+    }
+
+    /// Removes a source, returning its mapping.
+    pub fn remove_source(&mut self, id: GamepadId) -> Option<SourceMapping> {
+        let index = self.sources.iter().position(|(source, _)| *source == id)?;
+        Some(self.sources.remove(index).1)
+    }
+
+    /// Gets an iterator over the sources, in the order they were added.
+    pub fn sources(&self) -> impl Iterator<Item = (GamepadId, &SourceMapping)> {
+        self.sources.iter().map(|(id, mapping)| (*id, mapping))
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    ///
+    /// Sources are read before their own deadzones, so only this deadzone applies.
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        self.analog_inputs.set_deadzone(deadzone);
+    }
+
+    /// Updates the inputs from the current state of the sources.
+    ///
+    /// Call this after every [`GamepadContext::update`].
+    pub fn update(&mut self, context: &GamepadContext) {
+        let time = context.time();
+        self.analog_inputs.update(time);
+        self.digital_inputs.update(time);
+        self.values.resize(Axis::COUNT, 0.0);
+
+        let sources = || {
+            self.sources
+                .iter()
+                .filter_map(|(id, mapping)| context.gamepad(*id).map(|gamepad| (gamepad, mapping)))
+        };
+
+        for &axis in Axis::ALL {
+            let mut value = 0.0f32;
+            for (gamepad, mapping) in sources() {
+                for &(from, _) in mapping.axes.iter().filter(|&&(_, to)| to == axis) {
+                    let source_value = gamepad.analog_inputs.raw_value(from);
+                    if source_value.abs() > value.abs() {
+                        value = source_value;
+                    }
+                }
+            }
+
+            let last = &mut self.values[axis.index()];
+            if *last != value {
+                *last = value;
+                let sample = Sample::new(AnalogInputValue::from(value), time);
+                self.analog_inputs.set(axis, sample);
+            }
+        }
+
+        for &button in Button::ALL {
+            let pressed = sources().any(|(gamepad, mapping)| {
+                mapping
+                    .buttons
+                    .iter()
+                    .any(|&(from, to)| to == button && gamepad.digital_inputs.activated(from))
+            });
+            if pressed == self.digital_inputs.activated(button) {
+                continue;
+            }
+            if pressed {
+                self.digital_inputs.activate_at(button, time);
+            } else {
+                self.digital_inputs.deactivate_at(button, time);
+            }
+        }
+
+        self.analog_inputs.flush();
+    }
+}
@@ -0,0 +1,184 @@
+//! Tunable settings of a gamepad context, for settings menus and configuration files.
+
+use crate::analog::{
+    Coalescing, Smoothing, ANALOG_MAX, DEFAULT_DEADZONE, DEFAULT_DEADZONE_DIGITAL,
+    MAX_EXTRAPOLATION,
+};
+use crate::digital::KeyRepeat;
+use crate::error::{Error, Result};
+use alloc::format;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// All tunable settings of a [`GamepadContext`](crate::GamepadContext).
+///
+/// A configuration is read from a live context with
+/// [`GamepadContext::config`](crate::GamepadContext::config) and applied with
+/// [`GamepadContext::apply_config`](crate::GamepadContext::apply_config).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Config {
+    /// The analog deadzone for all analog inputs.
+    pub deadzone: f32,
+    /// Whether values outside the analog deadzone are rescaled to start at `0.0`.
+    pub deadzone_rescaling: bool,
+    /// The digital deadzone for all analog inputs.
+    pub digital_deadzone: f32,
+    /// The extrapolation horizon for analog inputs, or `None` to disable extrapolation.
+    pub extrapolation: Option<Duration>,
+    /// How consecutive motion events for the same analog input are handled within an update.
+    pub coalescing: Coalescing,
+    /// How analog inputs are smoothed against noise.
+    pub smoothing: Smoothing,
+    /// How held buttons repeat, or `None` to disable repeating.
+    pub repeat: Option<KeyRepeat>,
+    /// The debounce interval of buttons, or `None` to disable debouncing.
+    pub debounce: Option<Duration>,
+    /// How rumble and adaptive trigger effects are played.
+    pub haptics: Haptics,
+}
+
+impl Default for Config {
+    /// Matches the settings of a newly initialized context.
+    fn default() -> Self {
+        Self {
+            deadzone: DEFAULT_DEADZONE,
+            deadzone_rescaling: true,
+            digital_deadzone: DEFAULT_DEADZONE_DIGITAL,
+            extrapolation: None,
+            coalescing: Coalescing::default(),
+            smoothing: Smoothing::default(),
+            repeat: None,
+            debounce: None,
+            haptics: Haptics::default(),
+        }
+    }
+}
+
+/// How rumble and adaptive trigger effects are played, such as to tone them down for players
+/// who find them uncomfortable.
+///
+/// The policy applies to effects started after it is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Haptics {
+    /// The factor rumble intensities are multiplied by, from `0.0` to turn rumble off to `1.0`
+    /// to play it as requested.
+    pub rumble_intensity: f32,
+    /// Whether adaptive trigger effects are played. If not, requested effects are ignored.
+    pub trigger_effects: bool,
+}
+
+impl Default for Haptics {
+    /// Plays rumble and trigger effects as requested.
+    fn default() -> Self {
+        Self {
+            rumble_intensity: 1.0,
+            trigger_effects: true,
+        }
+    }
+}
+
+/// Identifies a single setting of a [`Config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Setting {
+    /// [`Config::deadzone`]
+    Deadzone,
+    /// [`Config::deadzone_rescaling`]
+    DeadzoneRescaling,
+    /// [`Config::digital_deadzone`]
+    DigitalDeadzone,
+    /// [`Config::extrapolation`]
+    Extrapolation,
+    /// [`Config::coalescing`]
+    Coalescing,
+    /// [`Config::smoothing`]
+    Smoothing,
+    /// [`Config::repeat`]
+    Repeat,
+    /// [`Config::debounce`]
+    Debounce,
+    /// [`Config::haptics`]
+    Haptics,
+}
+
+impl Config {
+    /// Checks that every setting is within its valid range.
+    ///
+    /// Deadzones must lie in `[0.0, ANALOG_MAX)`, the extrapolation horizon must not exceed
+    /// [`MAX_EXTRAPOLATION`], the repeat interval must not be zero, and the rumble intensity
+    /// must lie in `[0.0, 1.0]`.
+    pub fn validate(&self) -> Result<()> {
+        validate_deadzone("deadzone", self.deadzone)?;
+        validate_deadzone("digital deadzone", self.digital_deadzone)?;
+
+        if let Some(horizon) = self.extrapolation {
+            if horizon > MAX_EXTRAPOLATION {
+                return Err(Error::Invalid(format!(
+                    "extrapolation horizon {:?} exceeds the maximum of {:?}",
+                    horizon, MAX_EXTRAPOLATION
+                )));
+            }
+        }
+        if let Some(repeat) = self.repeat {
+            if repeat.interval.is_zero() {
+                return Err(Error::Invalid("repeat interval is zero".into()));
+            }
+        }
+        let intensity = self.haptics.rumble_intensity;
+        if !(0.0..=1.0).contains(&intensity) {
+            return Err(Error::Invalid(format!(
+                "rumble intensity {} is outside the range [0.0, 1.0]",
+                intensity
+            )));
+        }
+        Ok(())
+    }
+
+    /// Lists the settings that differ between this configuration and another.
+    pub fn diff(&self, other: &Config) -> Vec<Setting> {
+        let mut settings = Vec::new();
+        if self.deadzone != other.deadzone {
+            settings.push(Setting::Deadzone);
+        }
+        if self.deadzone_rescaling != other.deadzone_rescaling {
+            settings.push(Setting::DeadzoneRescaling);
+        }
+        if self.digital_deadzone != other.digital_deadzone {
+            settings.push(Setting::DigitalDeadzone);
+        }
+        if self.extrapolation != other.extrapolation {
+            settings.push(Setting::Extrapolation);
+        }
+        if self.coalescing != other.coalescing {
+            settings.push(Setting::Coalescing);
+        }
+        if self.smoothing != other.smoothing {
+            settings.push(Setting::Smoothing);
+        }
+        if self.repeat != other.repeat {
+            settings.push(Setting::Repeat);
+        }
+        if self.debounce != other.debounce {
+            settings.push(Setting::Debounce);
+        }
+        if self.haptics != other.haptics {
+            settings.push(Setting::Haptics);
+        }
+        settings
+    }
+}
+
+fn validate_deadzone(name: &str, deadzone: f32) -> Result<()> {
+    if (0.0..ANALOG_MAX).contains(&deadzone) {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!(
+            "{} {} is outside the range [0.0, {})",
+            name, deadzone, ANALOG_MAX
+        )))
+    }
+}
@@ -0,0 +1,76 @@
+//! Inspection of the full state of a gamepad context, for debug overlays and bug reports.
+
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad, GamepadId, Mode};
+
+/// Everything a [`GamepadContext`](crate::GamepadContext) knows, at a point in time.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DebugState {
+    /// The mode the context was initialized in.
+    pub mode: Mode,
+    /// The state of every connected gamepad.
+    pub gamepads: Vec<GamepadDebugState>,
+}
+
+/// The state of a single gamepad.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GamepadDebugState {
+    /// The instance Id of the gamepad.
+    pub id: usize,
+    /// The name of the gamepad, as reported by the backend.
+    pub name: String,
+    /// The analog deadzone.
+    pub deadzone: f32,
+    /// The digital deadzone.
+    pub digital_deadzone: f32,
+    /// The state of every axis.
+    pub axes: Vec<AxisDebugState>,
+    /// The names of all activated buttons.
+    pub activated_buttons: Vec<String>,
+}
+
+/// The state of a single axis.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AxisDebugState {
+    /// The name of the axis.
+    pub axis: String,
+    /// The value reported by the backend, before the deadzone is applied.
+    pub raw_value: f32,
+    /// The value after the analog deadzone is applied.
+    pub value: f32,
+    /// The value after the digital deadzone is applied.
+    pub digital_value: f32,
+}
+
+impl GamepadDebugState {
+    pub(crate) fn new(id: GamepadId, gamepad: &Gamepad) -> Self {
+        let analog_inputs = &gamepad.analog_inputs;
+        let axes = Axis::ALL
+            .iter()
+            .map(|&axis| AxisDebugState {
+                axis: format!("{:?}", axis),
+                raw_value: analog_inputs.raw_value(axis),
+                value: analog_inputs.value(axis),
+                digital_value: analog_inputs.digital_value(axis),
+            })
+            .collect();
+
+        let activated_buttons = Button::ALL
+            .iter()
+            .filter(|&&button| gamepad.digital_inputs.activated(button))
+            .map(|button| format!("{:?}", button))
+            .collect();
+
+        Self {
+            id: id.into(),
+            name: gamepad.name.clone(),
+            deadzone: analog_inputs.deadzone(),
+            digital_deadzone: analog_inputs.digital_deadzone(),
+            axes,
+            activated_buttons,
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! A guided test of every input of a gamepad, for "my controller doesn't work" support cases.
+//!
+//! [`Diagnostics`] asks the player to press each button and move each axis in turn. The game
+//! shows the [current step](Diagnostics::step), calls [`Diagnostics::update`] every frame, and
+//! offers a way to [skip](Diagnostics::skip) inputs the player cannot get to respond. The
+//! resulting [`DiagnosticReport`] can be shown to the player or attached to a support request.
+
+use crate::backend::compiled_buttons;
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad};
+
+/// How far an axis must move towards each end of its range to pass.
+const AXIS_EXTENT: f32 = 0.9;
+
+/// A step of a diagnostics run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStep {
+    /// The player should press this button.
+    Button(Button),
+    /// The player should move this axis through its full range.
+    Axis(Axis),
+    /// Every input has been tested.
+    Done,
+}
+
+/// The outcome of testing a button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ButtonResult {
+    /// The tested button.
+    pub button: Button,
+    /// Whether the button was pressed, rather than skipped.
+    pub passed: bool,
+    /// Other buttons that activated while waiting for this one, which may indicate a wrong
+    /// mapping or a short circuit.
+    pub unexpected: Vec<Button>,
+}
+
+/// The outcome of testing an axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisResult {
+    /// The tested axis.
+    pub axis: Axis,
+    /// Whether the axis reached the ends of its range, rather than being skipped.
+    pub passed: bool,
+    /// The raw value when the step started, which should be near the center.
+    pub rest: f32,
+    /// The lowest raw value observed.
+    pub min: f32,
+    /// The highest raw value observed.
+    pub max: f32,
+}
+
+/// The results of a diagnostics run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiagnosticReport {
+    /// The results of the button steps, in the order they were tested.
+    pub buttons: Vec<ButtonResult>,
+    /// The results of the axis steps, in the order they were tested.
+    pub axes: Vec<AxisResult>,
+}
+
+impl DiagnosticReport {
+    /// Checks if every tested input passed.
+    pub fn passed(&self) -> bool {
+        self.buttons.iter().all(|result| result.passed)
+            && self.axes.iter().all(|result| result.passed)
+    }
+}
+
+/// A diagnostics run, stepping through every button and then every axis.
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    steps: Vec<DiagnosticStep>,
+    step: usize,
+    unexpected: Vec<Button>,
+    range: Option<(f32, f32, f32)>,
+    report: DiagnosticReport,
+}
+
+impl Diagnostics {
+    /// Starts a run over all axes, and all buttons known to the compiled backends.
+    pub fn new() -> Self {
+        Self::with_steps(
+            compiled_buttons()
+                .map(DiagnosticStep::Button)
+                .chain(Axis::ALL.iter().map(|&axis| DiagnosticStep::Axis(axis))),
+        )
+    }
+
+    /// Starts a run over the given steps, such as only the inputs the game uses.
+    pub fn with_steps(steps: impl IntoIterator<Item = DiagnosticStep>) -> Self {
+        Self {
+            steps: steps
+                .into_iter()
+                .filter(|&step| step != DiagnosticStep::Done)
+                .collect(),
+            step: 0,
+            unexpected: Vec::new(),
+            range: None,
+            report: DiagnosticReport::default(),
+        }
+    }
+
+    /// Gets the current step.
+    pub fn step(&self) -> DiagnosticStep {
+        self.steps
+            .get(self.step)
+            .copied()
+            .unwrap_or(DiagnosticStep::Done)
+    }
+
+    /// Gets the index of the current step and the total number of steps, for showing progress.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.step, self.steps.len())
+    }
+
+    /// Gets the lowest and highest raw values observed so far in the current axis step.
+    pub fn observed_range(&self) -> Option<(f32, f32)> {
+        self.range.map(|(_, min, max)| (min, max))
+    }
+
+    /// Gets the results of the steps finished so far.
+    pub fn report(&self) -> &DiagnosticReport {
+        &self.report
+    }
+
+    /// Finishes the run early, returning the results of the steps finished so far.
+    pub fn into_report(self) -> DiagnosticReport {
+        self.report
+    }
+
+    /// Observes the state of the gamepad under test, moving to the next step once the current
+    /// input has responded.
+    ///
+    /// Call this once per update.
+    pub fn update(&mut self, gamepad: &Gamepad) {
+        match self.step() {
+            DiagnosticStep::Button(button) => {
+                for &other in Button::ALL {
+                    if other != button
+                        && gamepad.digital_inputs.just_activated(other)
+                        && !self.unexpected.contains(&other)
+                    {
+                        self.unexpected.push(other);
+                    }
+                }
+                if gamepad.digital_inputs.just_activated(button) {
+                    self.finish(true);
+                }
+            }
+            DiagnosticStep::Axis(axis) => {
+                let value = gamepad.analog_inputs.raw_value(axis);
+                let (_, min, max) = self.range.get_or_insert((value, value, value));
+                *min = min.min(value);
+                *max = max.max(value);
+
+                // Triggers rest at one end of their range, so they only need to reach the other.
+                let reached_min = axis.is_trigger() || *min <= -AXIS_EXTENT;
+                if reached_min && *max >= AXIS_EXTENT {
+                    self.finish(true);
+                }
+            }
+            DiagnosticStep::Done => (),
+        }
+    }
+
+    /// Marks the current input as failed and moves to the next step.
+    pub fn skip(&mut self) {
+        self.finish(false);
+    }
+
+    fn finish(&mut self, passed: bool) {
+        match self.step() {
+            DiagnosticStep::Button(button) => self.report.buttons.push(ButtonResult {
+                button,
+                passed,
+                unexpected: std::mem::take(&mut self.unexpected),
+            }),
+            DiagnosticStep::Axis(axis) => {
+                let (rest, min, max) = self.range.take().unwrap_or_default();
+                self.report.axes.push(AxisResult {
+                    axis,
+                    passed,
+                    rest,
+                    min,
+                    max,
+                });
+            }
+            DiagnosticStep::Done => return,
+        }
+        self.step += 1;
+    }
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,59 +1,282 @@
 //! Generic digital input support.
 
-use std::collections::HashSet;
-use std::hash::Hash;
+use crate::input::{Input, InputSet};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Timings for repeating held digital inputs, like the key repeat of a keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyRepeat {
+    /// How long an input must be held before it first repeats.
+    pub initial_delay: Duration,
+    /// The interval between repeats after the first. Must not be zero.
+    pub interval: Duration,
+}
+
+impl Default for KeyRepeat {
+    /// Repeats after 400 ms, then every 100 ms.
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(400),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+impl KeyRepeat {
+    /// Counts the repeats of an input activated at `since`, up to `time`.
+    fn count(&self, since: Duration, time: Duration) -> u128 {
+        let held = time.saturating_sub(since);
+        match held.checked_sub(self.initial_delay) {
+            Some(repeating) => 1 + repeating.as_nanos() / self.interval.as_nanos().max(1),
+            None => 0,
+        }
+    }
+}
 
 /// Container for digital inputs.
 #[derive(Debug)]
 pub struct DigitalInput<T> {
-    activated: HashSet<T>,
-    just_activated: HashSet<T>,
-    just_deactivated: HashSet<T>,
+    activated: InputSet<T>,
+    just_activated: InputSet<T>,
+    just_deactivated: InputSet<T>,
+    just_repeated: InputSet<T>,
+    repeat: Option<KeyRepeat>,
+    debounce: Option<Duration>,
+    // When each input was last deactivated, indexed by input. Only tracked while debouncing.
+    deactivation_times: Vec<Option<Duration>>,
+    // When each activated input was activated, indexed by input.
+    activation_times: Vec<Duration>,
+    // When each input was last activated or deactivated, indexed by input.
+    change_times: Vec<Option<Duration>>,
+    // The time of the most recent update.
+    time: Duration,
 }
 
 impl<T> DigitalInput<T>
 where
-    T: Hash + Eq,
+    T: Input,
 {
     /// Checks if a digital input is activated.
     pub fn activated(&self, input: T) -> bool {
-        self.activated.contains(&input)
+        self.activated.contains(input)
     }
 
     /// Checks if a digital input has just been activated.
     pub fn just_activated(&self, input: T) -> bool {
-        self.just_activated.contains(&input)
+        self.just_activated.contains(input)
     }
 
     /// Checks if a digital input has just been deactivated.
     pub fn just_deactivated(&self, input: T) -> bool {
-        self.just_deactivated.contains(&input)
+        self.just_deactivated.contains(input)
+    }
+
+    /// Checks if a digital input has just been activated, or has been held long enough to
+    /// repeat since the last update.
+    ///
+    /// This is meant for scrolling through menus by holding a direction. Inputs only repeat when
+    /// repeating is enabled, such as with
+    /// [`GamepadContext::set_repeat`](crate::GamepadContext::set_repeat); otherwise this is the
+    /// same as [`just_activated`](Self::just_activated).
+    pub fn just_activated_repeat(&self, input: T) -> bool {
+        self.just_activated(input) || self.just_repeated.contains(input)
+    }
+
+    /// Gets the timings for repeating held inputs, or `None` if repeating is disabled.
+    pub fn repeat(&self) -> Option<KeyRepeat> {
+        self.repeat
+    }
+
+    /// Iterates over the digital inputs that were activated or deactivated since the last
+    /// update.
+    ///
+    /// This only visits inputs that changed, so it costs next to nothing when none did.
+    pub fn changed(&self) -> impl Iterator<Item = T> {
+        let mut changed = self.just_activated;
+        changed.union(&self.just_deactivated);
+        changed.iter()
+    }
+
+    /// Gets how long a digital input has been held, as of the most recent update, or `None` if
+    /// it is not activated.
+    pub fn held_duration(&self, input: T) -> Option<Duration> {
+        if !self.activated(input) {
+            return None;
+        }
+        let since = self
+            .activation_times
+            .get(input.index())
+            .copied()
+            .unwrap_or(self.time);
+        Some(self.time.saturating_sub(since))
+    }
+
+    /// Gets when a digital input was last activated or deactivated, or `None` if it never was.
+    ///
+    /// Times are those of the backend events where available, on the clock of
+    /// [`GamepadContext::time`](crate::GamepadContext::time), so they can be compared with it to
+    /// measure input latency.
+    pub fn last_changed(&self, input: T) -> Option<Duration> {
+        self.change_times.get(input.index()).copied().flatten()
+    }
+
+    /// Gets the time of the most recent update.
+    pub(crate) fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Gets the debounce interval, or `None` if debouncing is disabled.
+    pub fn debounce(&self) -> Option<Duration> {
+        self.debounce
     }
 }
 
 impl<T> DigitalInput<T>
 where
-    T: Hash + Copy + Eq,
+    T: Input,
 {
+    /// Activates an input as of the most recent update.
     pub(crate) fn activate(&mut self, input: T) {
+        let time = self.time;
+        self.activate_since(input, time);
+    }
+
+    fn activate_since(&mut self, input: T, time: Duration) {
         if !self.activated(input) {
             self.activated.insert(input);
             self.just_activated.insert(input);
-            self.just_deactivated.remove(&input);
+            self.just_deactivated.remove(input);
+            if self.activation_times.len() < T::COUNT {
+                self.activation_times.resize(T::COUNT, Duration::ZERO);
+            }
+            self.activation_times[input.index()] = time;
+            self.record_change(input, time);
         }
     }
 
+    /// Deactivates an input as of the most recent update.
     pub(crate) fn deactivate(&mut self, input: T) {
+        let time = self.time;
+        self.deactivate_since(input, time);
+    }
+
+    fn deactivate_since(&mut self, input: T, time: Duration) {
         if self.activated(input) {
-            self.activated.remove(&input);
-            self.just_activated.remove(&input);
+            self.activated.remove(input);
+            self.just_activated.remove(input);
             self.just_deactivated.insert(input);
+            self.record_change(input, time);
+        }
+    }
+
+    fn record_change(&mut self, input: T, time: Duration) {
+        if self.change_times.len() < T::COUNT {
+            self.change_times.resize(T::COUNT, None);
+        }
+        self.change_times[input.index()] = Some(time);
+    }
+
+    /// Activates an input at the given time, unless it was deactivated less than the debounce
+    /// interval ago.
+    pub(crate) fn activate_at(&mut self, input: T, time: Duration) {
+        let bouncing = match (self.debounce, self.deactivation_times.get(input.index())) {
+            (Some(debounce), Some(&Some(deactivation_time))) => {
+                time.saturating_sub(deactivation_time) < debounce
+            }
+            _ => false,
+        };
+
+        if bouncing {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(index = input.index(), "ignored bouncing activation");
+        } else {
+            self.activate_since(input, time);
+        }
+    }
+
+    /// Deactivates an input at the given time, recording the time for debouncing.
+    pub(crate) fn deactivate_at(&mut self, input: T, time: Duration) {
+        if self.debounce.is_some() && self.activated(input) {
+            if let Some(deactivation_time) = self.deactivation_times.get_mut(input.index()) {
+                *deactivation_time = Some(time);
+            }
+        }
+        self.deactivate_since(input, time);
+    }
+
+    pub(crate) fn set_debounce(&mut self, debounce: Option<Duration>) {
+        self.debounce = debounce;
+        self.deactivation_times.clear();
+        if debounce.is_some() {
+            self.deactivation_times.resize(T::COUNT, None);
         }
     }
 
-    pub(crate) fn update(&mut self) {
+    pub(crate) fn set_repeat(&mut self, repeat: Option<KeyRepeat>) {
+        self.repeat = repeat;
+    }
+
+    /// Starts a new update at `time`, which must never decrease between updates.
+    pub(crate) fn update(&mut self, time: Duration) {
+        let previous = self.time;
+        self.time = time;
+        self.clear_changes();
+
+        if let Some(repeat) = self.repeat {
+            for input in self.activated.iter() {
+                let Some(&since) = self.activation_times.get(input.index()) else {
+                    continue;
+                };
+                if repeat.count(since, time) > repeat.count(since, previous.max(since)) {
+                    self.just_repeated.insert(input);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn clear_changes(&mut self) {
         self.just_activated.clear();
         self.just_deactivated.clear();
+        self.just_repeated.clear();
+    }
+
+    /// Copies the state of `other`, while keeping any changes that have already been recorded.
+    pub(crate) fn accumulate(&mut self, other: &Self) {
+        self.activated = other.activated;
+        self.time = other.time;
+        self.activation_times.clone_from(&other.activation_times);
+        self.change_times.clone_from(&other.change_times);
+        self.just_activated.union(&other.just_activated);
+        self.just_deactivated.union(&other.just_deactivated);
+        self.just_repeated.union(&other.just_repeated);
+    }
+}
+
+impl<T> Clone for DigitalInput<T> {
+    fn clone(&self) -> Self {
+        Self {
+            deactivation_times: self.deactivation_times.clone(),
+            activation_times: self.activation_times.clone(),
+            change_times: self.change_times.clone(),
+            ..*self
+        }
+    }
+
+    /// Reuses the storage of `self`, since snapshots copy inputs every frame.
+    fn clone_from(&mut self, source: &Self) {
+        self.activated = source.activated;
+        self.just_activated = source.just_activated;
+        self.just_deactivated = source.just_deactivated;
+        self.just_repeated = source.just_repeated;
+        self.repeat = source.repeat;
+        self.debounce = source.debounce;
+        self.deactivation_times
+            .clone_from(&source.deactivation_times);
+        self.activation_times.clone_from(&source.activation_times);
+        self.change_times.clone_from(&source.change_times);
+        self.time = source.time;
     }
 }
 
@@ -63,6 +286,13 @@ impl<T> Default for DigitalInput<T> {
             activated: Default::default(),
             just_activated: Default::default(),
             just_deactivated: Default::default(),
+            just_repeated: Default::default(),
+            repeat: None,
+            debounce: None,
+            deactivation_times: Vec::new(),
+            activation_times: Vec::new(),
+            change_times: Vec::new(),
+            time: Duration::ZERO,
         }
     }
 }
@@ -0,0 +1,69 @@
+//! Debug overlay widgets for [egui](https://docs.rs/egui).
+//!
+//! These render a [`DebugState`], as returned by
+//! [`GamepadContext::debug_state`](crate::GamepadContext::debug_state), so they can be shown in
+//! any egui-based debug menu.
+
+use crate::debug::{AxisDebugState, DebugState, GamepadDebugState};
+use egui::{CollapsingHeader, Grid, ProgressBar, Ui};
+
+/// Shows the state of every gamepad, one collapsible section per gamepad.
+pub fn debug_state(ui: &mut Ui, state: &DebugState) {
+    ui.label(format!("Mode: {:?}", state.mode));
+
+    if state.gamepads.is_empty() {
+        ui.label("No gamepads connected");
+    }
+
+    for gamepad in &state.gamepads {
+        CollapsingHeader::new(format!("{} ({})", gamepad.name, gamepad.id))
+            .id_salt(gamepad.id)
+            .default_open(true)
+            .show(ui, |ui| gamepad_debug_state(ui, gamepad));
+    }
+}
+
+/// Shows the state of a single gamepad: its deadzones, axes, and activated buttons.
+pub fn gamepad_debug_state(ui: &mut Ui, gamepad: &GamepadDebugState) {
+    ui.label(format!(
+        "Deadzone: {:.2}, digital deadzone: {:.2}",
+        gamepad.deadzone, gamepad.digital_deadzone
+    ));
+
+    Grid::new(("fishsticks_axes", gamepad.id))
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Axis");
+            ui.strong("Raw");
+            ui.strong("Value");
+            ui.strong("Digital");
+            ui.end_row();
+
+            for axis in &gamepad.axes {
+                axis_row(ui, axis);
+            }
+        });
+
+    let buttons = if gamepad.activated_buttons.is_empty() {
+        "none".to_owned()
+    } else {
+        gamepad.activated_buttons.join(", ")
+    };
+    ui.label(format!("Activated buttons: {}", buttons));
+}
+
+fn axis_row(ui: &mut Ui, axis: &AxisDebugState) {
+    ui.label(&axis.axis);
+    ui.add(axis_bar(axis.raw_value));
+    ui.add(axis_bar(axis.value));
+    ui.label(format!("{:+.0}", axis.digital_value));
+    ui.end_row();
+}
+
+/// A bar centered on zero, so that negative and positive values are both visible.
+fn axis_bar(value: f32) -> ProgressBar {
+    ProgressBar::new((value + 1.0) / 2.0)
+        .desired_width(120.0)
+        .text(format!("{:+.3}", value))
+}
@@ -1,9 +1,67 @@
 //! Common types for error handling.
 
-use std::result;
+use alloc::string::String;
+use core::fmt;
+use core::result;
+
+#[cfg(feature = "std")]
+use crate::GamepadId;
 
 /// Common error type
-pub type Error = String;
+///
+/// Every error displays as a sentence that can be shown to players, such as in a warning that
+/// gamepads are unavailable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The backend could not be started, such as when the platform has no gamepad support or
+    /// another library holds the devices.
+    BackendInit(String),
+    /// A gamepad is not connected, or was disconnected before the call.
+    #[cfg(feature = "std")]
+    DeviceNotFound(GamepadId),
+    /// The backend or gamepad does not support a feature, such as LEDs or motion sensors.
+    Unsupported(String),
+    /// The backend failed to carry out a call, such as starting rumble.
+    Backend(String),
+    /// An argument or loaded data is invalid, such as a deadzone out of range or a malformed
+    /// mapping.
+    Invalid(String),
+    /// A file could not be read or written.
+    Io(String),
+    /// Any other error, such as one returned by a custom [`Backend`](crate::Backend).
+    Other(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::BackendInit(message) => write!(f, "failed to initialize gamepads: {}", message),
+            #[cfg(feature = "std")]
+            Error::DeviceNotFound(id) => write!(f, "gamepad {:?} is not connected", id),
+            Error::Unsupported(message)
+            | Error::Backend(message)
+            | Error::Invalid(message)
+            | Error::Io(message)
+            | Error::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.into())
+    }
+}
 
 /// Common result type
 pub type Result<T> = result::Result<T, Error>;
@@ -0,0 +1,250 @@
+//! C ABI for embedding fishsticks in non-Rust engines.
+//!
+//! Gamepads are identified by the integer form of their [`GamepadId`](crate::GamepadId),
+//! and inputs by their [`Input::index`].
+//! Functions that can fail return `0` on success and `-1` on failure.
+//!
+//! The `ffi` directory of the repository holds a wrapper crate that builds these functions as a
+//! shared and a static library, along with a C header declaring them.
+
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad, GamepadContext, GamepadId};
+use std::ptr;
+use std::time::Duration;
+
+unsafe fn with_button(
+    context: *const GamepadContext,
+    id: usize,
+    button: u32,
+    f: impl FnOnce(&Gamepad, Button) -> bool,
+) -> bool {
+    let context = match context.as_ref() {
+        Some(context) => context,
+        None => return false,
+    };
+
+    match (
+        context.gamepad_by_index(id),
+        Button::from_index(button as usize),
+    ) {
+        (Some(gamepad), Some(button)) => f(gamepad, button),
+        _ => false,
+    }
+}
+
+unsafe fn with_axis(
+    context: *const GamepadContext,
+    id: usize,
+    axis: u32,
+    f: impl FnOnce(&Gamepad, Axis) -> f32,
+) -> f32 {
+    let context = match context.as_ref() {
+        Some(context) => context,
+        None => return 0.0,
+    };
+
+    match (
+        context.gamepad_by_index(id),
+        Axis::from_index(axis as usize),
+    ) {
+        (Some(gamepad), Some(axis)) => f(gamepad, axis),
+        _ => 0.0,
+    }
+}
+
+fn gamepad_id(context: &GamepadContext, index: usize) -> Option<GamepadId> {
+    context
+        .gamepads()
+        .map(|(id, _)| id)
+        .find(|&id| usize::from(id) == index)
+}
+
+/// Creates a gamepad context.
+///
+/// Returns null if the context could not be initialized.
+#[no_mangle]
+pub extern "C" fn fishsticks_context_create() -> *mut GamepadContext {
+    match GamepadContext::new() {
+        Ok(context) => Box::into_raw(Box::new(context)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Destroys a gamepad context.
+///
+/// # Safety
+///
+/// `context` must be null or a pointer returned by [`fishsticks_context_create`]
+/// that has not been destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_context_destroy(context: *mut GamepadContext) {
+    if !context.is_null() {
+        drop(Box::from_raw(context));
+    }
+}
+
+/// Updates the state of all gamepads.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_context_update(context: *mut GamepadContext) -> i32 {
+    match context.as_mut().map(GamepadContext::update) {
+        Some(Ok(())) => 0,
+        _ => -1,
+    }
+}
+
+/// Writes the Ids of up to `capacity` connected gamepads to `ids`.
+///
+/// Returns the total number of connected gamepads, which may exceed `capacity`.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`],
+/// and `ids` must be valid for writing `capacity` elements.
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_context_gamepads(
+    context: *const GamepadContext,
+    ids: *mut usize,
+    capacity: usize,
+) -> usize {
+    let context = match context.as_ref() {
+        Some(context) => context,
+        None => return 0,
+    };
+
+    let mut count = 0;
+    for (id, _) in context.gamepads() {
+        if count < capacity {
+            *ids.add(count) = id.into();
+        }
+        count += 1;
+    }
+
+    count
+}
+
+/// Checks if a button is activated.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_button_activated(
+    context: *const GamepadContext,
+    gamepad: usize,
+    button: u32,
+) -> bool {
+    with_button(context, gamepad, button, |gamepad, button| {
+        gamepad.digital_inputs.activated(button)
+    })
+}
+
+/// Checks if a button has just been activated.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_button_just_activated(
+    context: *const GamepadContext,
+    gamepad: usize,
+    button: u32,
+) -> bool {
+    with_button(context, gamepad, button, |gamepad, button| {
+        gamepad.digital_inputs.just_activated(button)
+    })
+}
+
+/// Checks if a button has just been deactivated.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_button_just_deactivated(
+    context: *const GamepadContext,
+    gamepad: usize,
+    button: u32,
+) -> bool {
+    with_button(context, gamepad, button, |gamepad, button| {
+        gamepad.digital_inputs.just_deactivated(button)
+    })
+}
+
+/// Gets the value of an axis, with the analog deadzone applied.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_axis_value(
+    context: *const GamepadContext,
+    gamepad: usize,
+    axis: u32,
+) -> f32 {
+    with_axis(context, gamepad, axis, |gamepad, axis| {
+        gamepad.analog_inputs.value(axis)
+    })
+}
+
+/// Gets the value of an axis, with the digital deadzone applied.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_axis_digital_value(
+    context: *const GamepadContext,
+    gamepad: usize,
+    axis: u32,
+) -> f32 {
+    with_axis(context, gamepad, axis, |gamepad, axis| {
+        gamepad.analog_inputs.digital_value(axis)
+    })
+}
+
+/// Rumbles a gamepad for `duration_ms` milliseconds, with the strong and weak motors at
+/// intensities from `0.0` to `1.0`.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_rumble(
+    context: *mut GamepadContext,
+    gamepad: usize,
+    strong: f32,
+    weak: f32,
+    duration_ms: u32,
+) -> i32 {
+    let Some(context) = context.as_mut() else {
+        return -1;
+    };
+    let duration = Duration::from_millis(duration_ms.into());
+    match gamepad_id(context, gamepad).map(|id| context.rumble(id, strong, weak, duration)) {
+        Some(Ok(())) => 0,
+        _ => -1,
+    }
+}
+
+/// Stops any rumble playing on a gamepad.
+///
+/// # Safety
+///
+/// `context` must be null or a valid pointer returned by [`fishsticks_context_create`].
+#[no_mangle]
+pub unsafe extern "C" fn fishsticks_stop_rumble(
+    context: *mut GamepadContext,
+    gamepad: usize,
+) -> i32 {
+    let Some(context) = context.as_mut() else {
+        return -1;
+    };
+    match gamepad_id(context, gamepad).map(|id| context.stop_rumble(id)) {
+        Some(Ok(())) => 0,
+        _ => -1,
+    }
+}
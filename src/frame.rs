@@ -0,0 +1,61 @@
+//! Support for engine integrations that update gamepads once per frame.
+
+use crate::error::Error;
+use crate::{Gamepad, GamepadContext, GamepadId};
+
+/// A gamepad context that may be unavailable on the current platform.
+///
+/// When the backend cannot start, such as when SDL2 fails on the player's machine or the browser
+/// has no Gamepad API, the context fails to initialize. Rather than aborting the game, the integrations
+/// then log a warning and behave as if no gamepads were connected, so the same game loop can be
+/// shipped to every platform.
+pub struct FrameGamepads {
+    context: Option<GamepadContext>,
+}
+
+impl FrameGamepads {
+    pub(crate) fn new(warn: impl Fn(&Error)) -> Self {
+        let context = match GamepadContext::new() {
+            Ok(context) => Some(context),
+            Err(error) => {
+                warn(&error);
+                None
+            }
+        };
+
+        Self { context }
+    }
+
+    pub(crate) fn update(&mut self, warn: impl Fn(&Error)) {
+        if let Some(context) = &mut self.context {
+            if let Err(error) = context.update() {
+                warn(&error);
+            }
+        }
+    }
+
+    /// Checks if gamepad input is available on this platform.
+    pub fn is_available(&self) -> bool {
+        self.context.is_some()
+    }
+
+    /// Gets the gamepad context, if it was initialized.
+    pub fn context(&self) -> Option<&GamepadContext> {
+        self.context.as_ref()
+    }
+
+    /// Gets the gamepad context mutably, if it was initialized.
+    pub fn context_mut(&mut self) -> Option<&mut GamepadContext> {
+        self.context.as_mut()
+    }
+
+    /// Gets a reference to a specific gamepad.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.context.as_ref()?.gamepad(id)
+    }
+
+    /// Gets an iterator over all gamepads.
+    pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        self.context.iter().flat_map(GamepadContext::gamepads)
+    }
+}
@@ -0,0 +1,162 @@
+//! Taps, double taps and holds of digital inputs.
+//!
+//! [`Gestures`] follows a [`DigitalInput`] from update to update, and tells short taps apart from
+//! holds, and single taps from double taps, such as for a dash on double tapping a direction or
+//! an action that differs between tapping and holding a button.
+
+use crate::digital::DigitalInput;
+use crate::input::{Input, InputSet};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// The timing windows of gestures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureTimings {
+    /// The longest time from releasing a tap to pressing again that counts as a double tap.
+    pub double_tap_window: Duration,
+    /// How long an input must be held to count as a hold rather than a tap.
+    pub hold_threshold: Duration,
+}
+
+impl Default for GestureTimings {
+    /// Allows 250 ms between the taps of a double tap, and counts presses of 300 ms or more as
+    /// holds.
+    fn default() -> Self {
+        Self {
+            double_tap_window: Duration::from_millis(250),
+            hold_threshold: Duration::from_millis(300),
+        }
+    }
+}
+
+/// Detects gestures on a set of digital inputs.
+///
+/// Call [`update`](Self::update) once per update of the inputs, with the updated inputs.
+#[derive(Debug, Clone)]
+pub struct Gestures<T> {
+    timings: GestureTimings,
+    // When the current press of each input started, indexed by input.
+    press_starts: Vec<Option<Duration>>,
+    // When each input last finished a tap that a second tap could follow, indexed by input.
+    tap_ends: Vec<Option<Duration>>,
+    // Inputs whose current press completed a double tap, so that it cannot start another.
+    double_tap_presses: InputSet<T>,
+    held: InputSet<T>,
+    just_tapped: InputSet<T>,
+    just_double_tapped: InputSet<T>,
+    just_held: InputSet<T>,
+}
+
+impl<T> Gestures<T>
+where
+    T: Input,
+{
+    /// Creates a detector with the given timing windows.
+    pub fn new(timings: GestureTimings) -> Self {
+        Self {
+            timings,
+            press_starts: alloc::vec![None; T::COUNT],
+            tap_ends: alloc::vec![None; T::COUNT],
+            double_tap_presses: InputSet::default(),
+            held: InputSet::default(),
+            just_tapped: InputSet::default(),
+            just_double_tapped: InputSet::default(),
+            just_held: InputSet::default(),
+        }
+    }
+
+    /// Gets the timing windows.
+    pub fn timings(&self) -> GestureTimings {
+        self.timings
+    }
+
+    /// Sets the timing windows.
+    pub fn set_timings(&mut self, timings: GestureTimings) {
+        self.timings = timings;
+    }
+
+    /// Checks if an input was released during the latest update, before it counted as held.
+    pub fn just_tapped(&self, input: T) -> bool {
+        self.just_tapped.contains(input)
+    }
+
+    /// Checks if an input was pressed during the latest update soon enough after a tap to make
+    /// a double tap.
+    ///
+    /// This is reported on the second press, without waiting for its release.
+    pub fn just_double_tapped(&self, input: T) -> bool {
+        self.just_double_tapped.contains(input)
+    }
+
+    /// Checks if an input became held during the latest update, by staying pressed for the hold
+    /// threshold.
+    pub fn just_held(&self, input: T) -> bool {
+        self.just_held.contains(input)
+    }
+
+    /// Checks if an input is pressed and has been for at least the hold threshold.
+    pub fn held(&self, input: T) -> bool {
+        self.held.contains(input)
+    }
+
+    /// Detects the gestures of the latest update of `inputs`.
+    pub fn update(&mut self, inputs: &DigitalInput<T>) {
+        let time = inputs.time();
+        self.just_tapped.clear();
+        self.just_double_tapped.clear();
+        self.just_held.clear();
+
+        for &input in T::ALL {
+            let index = input.index();
+
+            if inputs.just_activated(input) {
+                let start = time.saturating_sub(inputs.held_duration(input).unwrap_or_default());
+                let double_tap = self.tap_ends[index]
+                    .take()
+                    .is_some_and(|end| start.saturating_sub(end) <= self.timings.double_tap_window);
+                if double_tap {
+                    self.just_double_tapped.insert(input);
+                    self.double_tap_presses.insert(input);
+                } else {
+                    self.double_tap_presses.remove(input);
+                }
+                // A press and release within a single update is a tap of no length.
+                self.press_starts[index] = Some(start);
+            }
+
+            match inputs.held_duration(input) {
+                Some(held) => {
+                    if self.press_starts[index].is_none() {
+                        self.press_starts[index] = Some(time.saturating_sub(held));
+                    }
+                    if held >= self.timings.hold_threshold && !self.held.contains(input) {
+                        self.held.insert(input);
+                        self.just_held.insert(input);
+                    }
+                }
+                None => {
+                    self.held.remove(input);
+                    let Some(start) = self.press_starts[index].take() else {
+                        continue;
+                    };
+                    if time.saturating_sub(start) < self.timings.hold_threshold {
+                        self.just_tapped.insert(input);
+                        if !self.double_tap_presses.contains(input) {
+                            self.tap_ends[index] = Some(time);
+                        }
+                    }
+                    self.double_tap_presses.remove(input);
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for Gestures<T>
+where
+    T: Input,
+{
+    fn default() -> Self {
+        Self::new(GestureTimings::default())
+    }
+}
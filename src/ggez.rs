@@ -0,0 +1,206 @@
+//! Integration with the [ggez](https://ggez.rs) game framework.
+//!
+//! ggez owns its own gilrs instance, so rather than opening a second one through a
+//! [`GamepadContext`](crate::GamepadContext), [`GgezGamepads`] tracks gamepad state from the
+//! events ggez already delivers to its `EventHandler`. Forward the gamepad events to it, and call
+//! [`GgezGamepads::update`] at the start of every `EventHandler::update`.
+
+use crate::analog::{AnalogInput, AnalogInputValue, Deadzone, Sample};
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use alloc::vec::Vec;
+use core::time::Duration;
+use ggez::input::gamepad::gilrs::{Axis, Button};
+use ggez::input::gamepad::GamepadId;
+use ggez::Context;
+
+impl Input for Axis {
+    const COUNT: usize = Axis::DPadY as usize + 1;
+
+    const ALL: &'static [Self] = &[
+        Axis::LeftStickX,
+        Axis::LeftStickY,
+        Axis::LeftZ,
+        Axis::RightStickX,
+        Axis::RightStickY,
+        Axis::RightZ,
+        Axis::DPadX,
+        Axis::DPadY,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Input for Button {
+    const COUNT: usize = Button::DPadRight as usize + 1;
+
+    const ALL: &'static [Self] = &[
+        Button::South,
+        Button::East,
+        Button::North,
+        Button::West,
+        Button::C,
+        Button::Z,
+        Button::LeftTrigger,
+        Button::LeftTrigger2,
+        Button::RightTrigger,
+        Button::RightTrigger2,
+        Button::Select,
+        Button::Start,
+        Button::Mode,
+        Button::LeftThumb,
+        Button::RightThumb,
+        Button::DPadUp,
+        Button::DPadDown,
+        Button::DPadLeft,
+        Button::DPadRight,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Holds the state of a gamepad known to ggez.
+#[derive(Debug, Clone)]
+pub struct GgezGamepad {
+    /// Analog inputs, such as thumbsticks.
+    pub analog_inputs: AnalogInput<Axis>,
+    /// Digital inputs, such as buttons.
+    pub digital_inputs: DigitalInput<Button>,
+}
+
+impl GgezGamepad {
+    fn new(deadzone: Deadzone, digital_deadzone: Deadzone) -> Self {
+        let mut analog_inputs = AnalogInput::default();
+        analog_inputs.set_deadzone(deadzone);
+        analog_inputs.set_digital_deadzone(digital_deadzone);
+
+        Self {
+            analog_inputs,
+            digital_inputs: Default::default(),
+        }
+    }
+}
+
+/// Gamepad state tracking fed by ggez's gamepad events.
+#[derive(Debug, Clone)]
+pub struct GgezGamepads {
+    gamepads: Vec<(GamepadId, GgezGamepad)>,
+    deadzone: Deadzone,
+    digital_deadzone: Deadzone,
+    time: Duration,
+}
+
+impl GgezGamepads {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        let defaults = AnalogInput::<Axis>::default();
+
+        Self {
+            gamepads: Vec::new(),
+            deadzone: Deadzone::from(AnalogInputValue::from(defaults.deadzone())),
+            digital_deadzone: Deadzone::from(AnalogInputValue::from(defaults.digital_deadzone())),
+            time: Duration::ZERO,
+        }
+    }
+
+    /// Gets a reference to a specific gamepad.
+    ///
+    /// Returns `None` if the gamepad is not connected.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&GgezGamepad> {
+        self.gamepads
+            .iter()
+            .find(|(gamepad_id, _)| *gamepad_id == id)
+            .map(|(_, gamepad)| gamepad)
+    }
+
+    /// Gets an iterator over all gamepads.
+    pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &GgezGamepad)> {
+        self.gamepads.iter().map(|(id, gamepad)| (*id, gamepad))
+    }
+
+    /// Starts a new update, clearing all `just_*` changes and synchronizing the set of
+    /// connected gamepads with ggez.
+    ///
+    /// Call this at the start of `EventHandler::update`.
+    pub fn update(&mut self, ctx: &Context) {
+        self.time = ctx.time.time_since_start();
+
+        let connected: Vec<GamepadId> = ctx.gamepad.gamepads().map(|(id, _)| id).collect();
+        self.gamepads.retain(|(id, _)| connected.contains(id));
+        for id in connected {
+            self.gamepad_mut(id);
+        }
+
+        for (_, gamepad) in &mut self.gamepads {
+            gamepad.analog_inputs.flush();
+            gamepad.analog_inputs.update(self.time);
+            gamepad.digital_inputs.update(self.time);
+        }
+    }
+
+    /// Forwards `EventHandler::gamepad_button_down_event`.
+    pub fn button_down_event(&mut self, button: Button, id: GamepadId) {
+        if button.index() < Button::COUNT {
+            self.gamepad_mut(id).digital_inputs.activate(button);
+        }
+    }
+
+    /// Forwards `EventHandler::gamepad_button_up_event`.
+    pub fn button_up_event(&mut self, button: Button, id: GamepadId) {
+        if button.index() < Button::COUNT {
+            self.gamepad_mut(id).digital_inputs.deactivate(button);
+        }
+    }
+
+    /// Forwards `EventHandler::gamepad_axis_event`.
+    pub fn axis_event(&mut self, axis: Axis, value: f32, id: GamepadId) {
+        if axis.index() < Axis::COUNT {
+            let sample = Sample::new(AnalogInputValue::from(value), self.time);
+            self.gamepad_mut(id).analog_inputs.set(axis, sample);
+        }
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        for (_, gamepad) in &mut self.gamepads {
+            gamepad.analog_inputs.set_deadzone(self.deadzone);
+        }
+    }
+
+    /// Sets the digital deadzone for all analog inputs.
+    pub fn set_digital_deadzone(&mut self, deadzone: f32) {
+        self.digital_deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        for (_, gamepad) in &mut self.gamepads {
+            gamepad
+                .analog_inputs
+                .set_digital_deadzone(self.digital_deadzone);
+        }
+    }
+
+    fn gamepad_mut(&mut self, id: GamepadId) -> &mut GgezGamepad {
+        let index = match self
+            .gamepads
+            .iter()
+            .position(|(gamepad_id, _)| *gamepad_id == id)
+        {
+            Some(index) => index,
+            None => {
+                let gamepad = GgezGamepad::new(self.deadzone, self.digital_deadzone);
+                self.gamepads.push((id, gamepad));
+                self.gamepads.len() - 1
+            }
+        };
+        &mut self.gamepads[index].1
+    }
+}
+
+impl Default for GgezGamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+//! Integration with [Godot](https://godotengine.org) through [gdext](https://godot-rust.github.io).
+//!
+//! Linking this crate into a GDExtension library registers a `FishsticksGamepads` node. Add it
+//! to the scene tree and it updates the gamepads every frame, before other nodes process.
+//!
+//! As in the JavaScript API, gamepads are identified by the integer form of their
+//! [`GamepadId`](crate::GamepadId), and inputs by their [`Input::index`].
+//!
+//! See [`FrameGamepads`] for how platforms without gamepad support are handled.
+
+// The glue generated by `#[godot_api]` returns gdext's large `CallError`.
+#![allow(clippy::result_large_err)]
+
+use crate::digital::DigitalInput;
+use crate::frame::FrameGamepads;
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad};
+use godot::prelude::*;
+
+/// A node driving the gamepad context.
+#[derive(GodotClass)]
+#[class(base = Node)]
+pub struct FishsticksGamepads {
+    gamepads: FrameGamepads,
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for FishsticksGamepads {
+    fn init(base: Base<Node>) -> Self {
+        let gamepads =
+            FrameGamepads::new(|error| godot_warn!("Gamepad input is unavailable: {}", error));
+
+        Self { gamepads, base }
+    }
+
+    fn ready(&mut self) {
+        self.base_mut().set_process_priority(i32::MIN);
+    }
+
+    fn process(&mut self, _delta: f64) {
+        self.gamepads
+            .update(|error| godot_warn!("Failed to update gamepads: {}", error));
+    }
+}
+
+#[godot_api]
+impl FishsticksGamepads {
+    /// Checks if gamepad input is available on this platform.
+    #[func]
+    fn is_available(&self) -> bool {
+        self.gamepads.is_available()
+    }
+
+    /// Gets the Ids of all connected gamepads.
+    #[func]
+    fn gamepads(&self) -> PackedInt64Array {
+        self.gamepads
+            .gamepads()
+            .map(|(id, _)| usize::from(id) as i64)
+            .collect()
+    }
+
+    /// Checks if a button is activated.
+    #[func]
+    fn button_activated(&self, gamepad: i64, button: i64) -> bool {
+        self.button(gamepad, button, |inputs, button| inputs.activated(button))
+    }
+
+    /// Checks if a button has just been activated.
+    #[func]
+    fn button_just_activated(&self, gamepad: i64, button: i64) -> bool {
+        self.button(gamepad, button, |inputs, button| {
+            inputs.just_activated(button)
+        })
+    }
+
+    /// Checks if a button has just been deactivated.
+    #[func]
+    fn button_just_deactivated(&self, gamepad: i64, button: i64) -> bool {
+        self.button(gamepad, button, |inputs, button| {
+            inputs.just_deactivated(button)
+        })
+    }
+
+    /// Gets the value of an axis, with the analog deadzone applied.
+    #[func]
+    fn axis_value(&self, gamepad: i64, axis: i64) -> f32 {
+        match (
+            self.gamepad(gamepad),
+            index(axis).and_then(Axis::from_index),
+        ) {
+            (Some(gamepad), Some(axis)) => gamepad.analog_inputs.value(axis),
+            _ => 0.0,
+        }
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    #[func]
+    fn set_deadzone(&mut self, deadzone: f32) {
+        if let Some(context) = self.gamepads.context_mut() {
+            context.set_deadzone(deadzone);
+        }
+    }
+}
+
+impl FishsticksGamepads {
+    /// Gets the gamepad context driven by this node.
+    pub fn frame_gamepads(&self) -> &FrameGamepads {
+        &self.gamepads
+    }
+
+    fn gamepad(&self, gamepad: i64) -> Option<&Gamepad> {
+        self.gamepads.context()?.gamepad_by_index(index(gamepad)?)
+    }
+
+    fn button(
+        &self,
+        gamepad: i64,
+        button: i64,
+        f: impl FnOnce(&DigitalInput<Button>, Button) -> bool,
+    ) -> bool {
+        match (
+            self.gamepad(gamepad),
+            index(button).and_then(Button::from_index),
+        ) {
+            (Some(gamepad), Some(button)) => f(&gamepad.digital_inputs, button),
+            _ => false,
+        }
+    }
+}
+
+fn index(value: i64) -> Option<usize> {
+    usize::try_from(value).ok()
+}
@@ -0,0 +1,120 @@
+//! Identifying models of gamepads across sessions.
+
+use crate::error::{Error, Result};
+use alloc::format;
+use core::fmt;
+use core::str::FromStr;
+
+/// The bus type SDL uses in GUIDs for USB devices.
+const BUS_USB: u16 = 0x03;
+
+/// A GUID identifying a model of gamepad, in the layout used by SDL and its gamepad mapping
+/// database.
+///
+/// Every gamepad of the same model and connection has the same GUID, so it suits settings kept
+/// per device. It is written as 32 lowercase hexadecimal digits, as in SDL mappings. A GUID of
+/// all zeroes means the backend could not identify the gamepad.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guid([u8; 16]);
+
+impl Guid {
+    /// Creates a GUID from its raw bytes.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates the GUID SDL gives a USB gamepad with the given vendor and product IDs, for
+    /// backends that only report the IDs.
+    pub fn from_ids(vendor_id: u16, product_id: u16) -> Self {
+        let mut bytes = [0; 16];
+        bytes[0..2].copy_from_slice(&BUS_USB.to_le_bytes());
+        bytes[4..6].copy_from_slice(&vendor_id.to_le_bytes());
+        bytes[8..10].copy_from_slice(&product_id.to_le_bytes());
+        Self(bytes)
+    }
+
+    /// Gets the raw bytes of the GUID.
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Checks if the GUID is all zeroes, which means the gamepad could not be identified.
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 16]
+    }
+
+    /// Gets the USB vendor ID encoded in the GUID, if it has one.
+    pub fn vendor_id(&self) -> Option<u16> {
+        self.ids().map(|(vendor_id, _)| vendor_id)
+    }
+
+    /// Gets the USB product ID encoded in the GUID, if it has one.
+    pub fn product_id(&self) -> Option<u16> {
+        self.ids().map(|(_, product_id)| product_id)
+    }
+
+    /// Reads the vendor and product IDs, which SDL only stores when the words after each of them
+    /// are zero. Other GUIDs hold a hash of the name instead.
+    fn ids(&self) -> Option<(u16, u16)> {
+        let word = |index: usize| u16::from_le_bytes([self.0[index * 2], self.0[index * 2 + 1]]);
+        let (vendor_id, product_id) = (word(2), word(4));
+        if word(3) == 0 && word(5) == 0 && (vendor_id != 0 || product_id != 0) {
+            Some((vendor_id, product_id))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Guid {
+    type Err = Error;
+
+    /// Parses a GUID written as 32 hexadecimal digits.
+    fn from_str(text: &str) -> Result<Self> {
+        let digits = text.as_bytes();
+        if digits.len() != 32 || !text.is_ascii() {
+            return Err(Error::Invalid(format!(
+                "invalid GUID {:?}: expected 32 hex digits",
+                text
+            )));
+        }
+
+        let mut bytes = [0; 16];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks_exact(2)) {
+            let pair = core::str::from_utf8(pair).unwrap_or_default();
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| {
+                Error::Invalid(format!("invalid GUID {:?}: expected 32 hex digits", text))
+            })?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Guid {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Guid {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let text = <alloc::string::String as serde::Deserialize>::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
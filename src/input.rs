@@ -0,0 +1,116 @@
+//! Common support for identifying inputs.
+
+use core::fmt;
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+/// The maximum number of distinct inputs of a single type.
+pub const MAX_INPUTS: usize = u128::BITS as usize;
+
+/// An input that can be mapped to a small, dense index.
+///
+/// This lets input state be tracked in fixed-capacity collections rather than hash sets.
+pub trait Input: Copy + Eq + Hash + 'static {
+    /// The number of distinct inputs of this type.
+    ///
+    /// Must not exceed [`MAX_INPUTS`], which is checked when the crate tracks the inputs, failing
+    /// the build otherwise.
+    const COUNT: usize;
+
+    /// Every input of this type.
+    const ALL: &'static [Self];
+
+    /// Gets the index of this input, in the range `0..COUNT`.
+    fn index(self) -> usize;
+
+    /// Gets the input with the given index.
+    ///
+    /// Returns `None` if no input has that index.
+    fn from_index(index: usize) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|input| input.index() == index)
+    }
+}
+
+/// Fixed-capacity bitset of inputs.
+pub(crate) struct InputSet<T> {
+    bits: u128,
+    marker: PhantomData<T>,
+}
+
+impl<T> InputSet<T> {
+    pub(crate) fn clear(&mut self) {
+        self.bits = 0;
+    }
+
+    pub(crate) fn union(&mut self, other: &Self) {
+        self.bits |= other.bits;
+    }
+}
+
+impl<T> InputSet<T>
+where
+    T: Input,
+{
+    /// Fails to compile for inputs with more than [`MAX_INPUTS`] indices, which would not fit
+    /// in the bits of the set.
+    const FITS: () = assert!(T::COUNT <= MAX_INPUTS, "Input::COUNT exceeds MAX_INPUTS");
+
+    fn mask(input: T) -> u128 {
+        let () = Self::FITS;
+        debug_assert!(input.index() < T::COUNT);
+        1 << input.index()
+    }
+
+    pub(crate) fn contains(&self, input: T) -> bool {
+        self.bits & Self::mask(input) != 0
+    }
+
+    pub(crate) fn insert(&mut self, input: T) {
+        self.bits |= Self::mask(input);
+    }
+
+    pub(crate) fn remove(&mut self, input: T) {
+        self.bits &= !Self::mask(input);
+    }
+
+    /// Iterates over the inputs in the set, in order of their indices.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = T> {
+        let mut bits = self.bits;
+        core::iter::from_fn(move || {
+            while bits != 0 {
+                let index = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                if let Some(input) = T::from_index(index) {
+                    return Some(input);
+                }
+            }
+            None
+        })
+    }
+}
+
+impl<T> Default for InputSet<T> {
+    fn default() -> Self {
+        Self {
+            bits: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for InputSet<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for InputSet<T> {}
+
+impl<T> fmt::Debug for InputSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InputSet({:#b})", self.bits)
+    }
+}
@@ -0,0 +1,265 @@
+//! A history of recent frames of input, for fighting-game style input leniency and motions.
+//!
+//! [`InputBuffer`] keeps the last few frames of digital inputs and of a stick direction, so that
+//! a press can still count a few frames after it happened, and so that motions such as a
+//! quarter circle forward (down, down-forward, forward) followed by a button can be recognized.
+//!
+//! ```ignore
+//! let mut buffer = InputBuffer::new(30);
+//!
+//! // Once per frame:
+//! let direction = Direction::from_stick(
+//!     gamepad.analog_inputs.value(Axis::LeftX),
+//!     gamepad.analog_inputs.value(Axis::LeftY),
+//!     0.5,
+//! );
+//! buffer.update(&gamepad.digital_inputs, direction.mirrored_if(facing_left));
+//!
+//! let fireball = [Direction::Down, Direction::DownRight, Direction::Right];
+//! if buffer.motion_then_pressed(&fireball, Button::West, 15) {
+//!     // ...
+//! }
+//! ```
+
+use crate::digital::DigitalInput;
+use crate::input::{Input, InputSet};
+use alloc::collections::VecDeque;
+
+/// One of eight directions of a stick or d-pad, or neutral.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Centered.
+    #[default]
+    Neutral,
+    /// Towards the top.
+    Up,
+    /// Towards the top right.
+    UpRight,
+    /// Towards the right.
+    Right,
+    /// Towards the bottom right.
+    DownRight,
+    /// Towards the bottom.
+    Down,
+    /// Towards the bottom left.
+    DownLeft,
+    /// Towards the left.
+    Left,
+    /// Towards the top left.
+    UpLeft,
+}
+
+impl Direction {
+    /// Gets the direction of a stick, where `y` is positive downwards, as with every
+    /// [`Axis`](crate::Axis).
+    ///
+    /// Each axis counts towards the direction once its magnitude reaches `threshold`, so a
+    /// diagonal needs both axes past it.
+    pub fn from_stick(x: f32, y: f32, threshold: f32) -> Self {
+        let horizontal = Self::sign(x, threshold);
+        let vertical = Self::sign(y, threshold);
+        Self::from_signs(horizontal, vertical)
+    }
+
+    /// Gets the direction of a d-pad from its four buttons. Opposite buttons held together
+    /// cancel out.
+    pub fn from_dpad(up: bool, down: bool, left: bool, right: bool) -> Self {
+        let horizontal = right as i8 - left as i8;
+        let vertical = down as i8 - up as i8;
+        Self::from_signs(horizontal, vertical)
+    }
+
+    /// Flips the direction horizontally, such as to read directions relative to the way a
+    /// character faces.
+    pub fn mirrored(self) -> Self {
+        match self {
+            Direction::UpRight => Direction::UpLeft,
+            Direction::Right => Direction::Left,
+            Direction::DownRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::DownRight,
+            Direction::Left => Direction::Right,
+            Direction::UpLeft => Direction::UpRight,
+            other => other,
+        }
+    }
+
+    /// Flips the direction horizontally if `mirror` is set.
+    pub fn mirrored_if(self, mirror: bool) -> Self {
+        if mirror {
+            self.mirrored()
+        } else {
+            self
+        }
+    }
+
+    fn sign(value: f32, threshold: f32) -> i8 {
+        if value >= threshold {
+            1
+        } else if value <= -threshold {
+            -1
+        } else {
+            0
+        }
+    }
+
+    fn from_signs(horizontal: i8, vertical: i8) -> Self {
+        match (horizontal, vertical) {
+            (0, -1) => Direction::Up,
+            (1, -1) => Direction::UpRight,
+            (1, 0) => Direction::Right,
+            (1, 1) => Direction::DownRight,
+            (0, 1) => Direction::Down,
+            (-1, 1) => Direction::DownLeft,
+            (-1, 0) => Direction::Left,
+            (-1, -1) => Direction::UpLeft,
+            _ => Direction::Neutral,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame<T> {
+    pressed: InputSet<T>,
+    released: InputSet<T>,
+    held: InputSet<T>,
+    direction: Direction,
+}
+
+/// A ring buffer of the last frames of a set of digital inputs and a direction.
+///
+/// Call [`update`](Self::update) once per frame. Queries take a number of frames to look back
+/// over, counting the latest frame as the first, and are clamped to the capacity of the buffer.
+#[derive(Debug, Clone)]
+pub struct InputBuffer<T> {
+    frames: VecDeque<Frame<T>>,
+    capacity: usize,
+}
+
+impl<T> InputBuffer<T>
+where
+    T: Input,
+{
+    /// Creates an empty buffer that keeps the given number of frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Gets the number of frames the buffer keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Gets the number of frames recorded so far, up to the capacity.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Checks if no frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Forgets every recorded frame, such as after a round ends.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Records a frame from the latest update of `inputs` and the current direction, dropping
+    /// the oldest frame if the buffer is full.
+    pub fn update(&mut self, inputs: &DigitalInput<T>, direction: Direction) {
+        let mut frame = Frame {
+            pressed: InputSet::default(),
+            released: InputSet::default(),
+            held: InputSet::default(),
+            direction,
+        };
+        for &input in T::ALL {
+            if inputs.just_activated(input) {
+                frame.pressed.insert(input);
+            }
+            if inputs.just_deactivated(input) {
+                frame.released.insert(input);
+            }
+            if inputs.activated(input) {
+                frame.held.insert(input);
+            }
+        }
+
+        if self.capacity == 0 {
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.pop_back();
+        }
+        self.frames.push_front(frame);
+    }
+
+    /// Checks if an input was pressed during any of the last `frames` frames.
+    pub fn was_pressed_within(&self, input: T, frames: usize) -> bool {
+        self.recent(frames)
+            .any(|frame| frame.pressed.contains(input))
+    }
+
+    /// Checks if an input was released during any of the last `frames` frames.
+    pub fn was_released_within(&self, input: T, frames: usize) -> bool {
+        self.recent(frames)
+            .any(|frame| frame.released.contains(input))
+    }
+
+    /// Checks if an input was held through each of the last `frames` frames.
+    ///
+    /// This is `false` if fewer frames have been recorded.
+    pub fn held_for(&self, input: T, frames: usize) -> bool {
+        self.frames.len() >= frames && self.recent(frames).all(|frame| frame.held.contains(input))
+    }
+
+    /// Gets the direction of a frame, counting the latest frame as `0`, if it is still kept.
+    pub fn direction(&self, frames_ago: usize) -> Option<Direction> {
+        self.frames.get(frames_ago).map(|frame| frame.direction)
+    }
+
+    /// Checks if the directions of a motion were entered in order within the last `frames`
+    /// frames, ending on or before the latest frame.
+    ///
+    /// Each direction may be held for any number of frames, and other directions may come
+    /// between them, so that a slightly sloppy quarter circle still counts.
+    pub fn motion_within(&self, motion: &[Direction], frames: usize) -> bool {
+        self.motion_end(motion, frames).is_some()
+    }
+
+    /// Checks if the directions of a motion were entered in order, followed by a press of
+    /// `input` on the frame the motion completed or later, all within the last `frames` frames.
+    pub fn motion_then_pressed(&self, motion: &[Direction], input: T, frames: usize) -> bool {
+        self.motion_end(motion, frames).is_some_and(|end| {
+            self.recent(end + 1)
+                .any(|frame| frame.pressed.contains(input))
+        })
+    }
+
+    /// Finds the latest frame, counting back from the latest frame as `0`, by which the motion
+    /// was completed when matched from the oldest frame of the window.
+    fn motion_end(&self, motion: &[Direction], frames: usize) -> Option<usize> {
+        let window = frames.min(self.frames.len());
+        let mut remaining = motion.iter().peekable();
+        // Oldest first, so the motion is matched in the order it was entered.
+        for frames_ago in (0..window).rev() {
+            let Some(&&next) = remaining.peek() else {
+                break;
+            };
+            if self.frames[frames_ago].direction == next {
+                remaining.next();
+                if remaining.peek().is_none() {
+                    return Some(frames_ago);
+                }
+            }
+        }
+        motion.is_empty().then_some(window.saturating_sub(1))
+    }
+
+    fn recent(&self, frames: usize) -> impl Iterator<Item = &Frame<T>> {
+        self.frames.iter().take(frames)
+    }
+}
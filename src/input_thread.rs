@@ -0,0 +1,164 @@
+//! Polling gamepads on a background thread, independently of the game's frame rate.
+//!
+//! At a low frame rate, input read once per frame is up to a whole frame old, and changes that
+//! come and go between frames are timestamped late. [`InputThread`] instead runs a
+//! [`GamepadContext`] on its own thread and updates it at a fixed rate, sending the input and
+//! connection events of every update through channels, and publishing the state of all gamepads
+//! through a [`SharedGamepadState`].
+//!
+//! The context is created on the input thread, since backends such as SDL2 must be polled from
+//! the thread that initialized them. On macOS, SDL2 may need to run on the main thread, in which
+//! case update the context on the main thread and share its state with
+//! [`GamepadContext::shared_state`] instead.
+//!
+//! ```ignore
+//! let input = InputThread::spawn(Duration::from_millis(2), || GamepadContext::new())?;
+//!
+//! // Once per frame:
+//! for event in input.events() {
+//!     // ...
+//! }
+//! let state = input.state().read();
+//! ```
+
+use crate::error::{Error, Result};
+use crate::snapshot::SharedGamepadState;
+use crate::{GamepadContext, GamepadEvent, InputEvent};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryIter};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A [`GamepadContext`] updated at a fixed rate on a background thread.
+///
+/// The thread stops when the handle is dropped or [`stop`](Self::stop)ped, or when an update
+/// fails.
+pub struct InputThread {
+    events: Receiver<InputEvent>,
+    gamepad_events: Receiver<GamepadEvent>,
+    state: SharedGamepadState,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl InputThread {
+    /// Starts a thread that creates a context with `init` and updates it every `interval`.
+    ///
+    /// `init` runs on the new thread, and can configure the context before the first update.
+    /// Returns the error of `init` if it fails.
+    pub fn spawn(
+        interval: Duration,
+        init: impl FnOnce() -> Result<GamepadContext> + Send + 'static,
+    ) -> Result<Self> {
+        let (events_sender, events) = mpsc::channel();
+        let (gamepad_events_sender, gamepad_events) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut context = match init() {
+                    Ok(mut context) => {
+                        let _ = ready_sender.send(Ok(context.shared_state()));
+                        context
+                    }
+                    Err(error) => {
+                        let _ = ready_sender.send(Err(error));
+                        return Ok(());
+                    }
+                };
+
+                let mut next = Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    context.update()?;
+                    for event in context.events() {
+                        let _ = gamepad_events_sender.send(event);
+                    }
+                    for event in context.poll_events() {
+                        let _ = events_sender.send(event);
+                    }
+
+                    next += interval;
+                    let now = Instant::now();
+                    if next > now {
+                        thread::sleep(next - now);
+                    } else {
+                        // Fell behind, so skip the missed updates rather than catching up.
+                        next = now;
+                    }
+                }
+                Ok(())
+            })
+        };
+
+        let state = match ready.recv() {
+            Ok(Ok(state)) => state,
+            Ok(Err(error)) => {
+                let _ = thread.join();
+                return Err(error);
+            }
+            Err(_) => {
+                return Err(Error::Other(
+                    "input thread panicked during initialization".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            events,
+            gamepad_events,
+            state,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Takes the input events of every update since the last call, in order.
+    ///
+    /// See [`GamepadContext::poll_events`].
+    pub fn events(&self) -> TryIter<'_, InputEvent> {
+        self.events.try_iter()
+    }
+
+    /// Takes the gamepads connected and disconnected since the last call, in order.
+    ///
+    /// See [`GamepadContext::events`].
+    pub fn gamepad_events(&self) -> TryIter<'_, GamepadEvent> {
+        self.gamepad_events.try_iter()
+    }
+
+    /// Gets the state of all gamepads, as of the latest update.
+    pub fn state(&self) -> &SharedGamepadState {
+        &self.state
+    }
+
+    /// Checks if the thread is still updating the context.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops the thread and waits for it to finish, returning the error of the update that
+    /// stopped it, if any.
+    pub fn stop(mut self) -> Result<()> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(Error::Other("input thread panicked".into())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
@@ -0,0 +1,155 @@
+//! Analog keyboards as a source of gamepad-like input.
+//!
+//! Analog keyboards, such as those made by Wooting, report how far each key is pressed rather
+//! than only whether it is. An [`AnalogKeyboard`] maps selected keys to analog and digital
+//! inputs, which then go through the same deadzones and state tracking as a gamepad's.
+//!
+//! Reading the keyboard itself is left to the vendor's SDK, which reports the depth of every
+//! pressed key by its HID usage ID. For example, the Wooting Analog SDK's full buffer read
+//! returns exactly such pairs, which can be passed to [`AnalogKeyboard::update`] unchanged.
+
+use crate::analog::{AnalogInput, AnalogInputValue, Deadzone, Sample};
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// The HID usage ID of a key, as reported by analog keyboard SDKs.
+pub type KeyCode = u16;
+
+/// The default depth at which a key bound to a digital input activates it.
+pub const DEFAULT_ACTUATION: f32 = 0.5;
+
+/// Maps the keys of an analog keyboard to analog and digital inputs.
+#[derive(Debug, Clone)]
+pub struct AnalogKeyboard<A, B> {
+    /// Analog inputs, driven by the keys bound with [`bind_axis`](Self::bind_axis).
+    pub analog_inputs: AnalogInput<A>,
+    /// Digital inputs, driven by the keys bound with [`bind_button`](Self::bind_button).
+    pub digital_inputs: DigitalInput<B>,
+    axis_bindings: Vec<(KeyCode, A, f32)>,
+    button_bindings: Vec<(KeyCode, B)>,
+    actuation: f32,
+    depths: Vec<(KeyCode, f32)>,
+    values: Vec<f32>,
+}
+
+impl<A, B> AnalogKeyboard<A, B>
+where
+    A: Input,
+    B: Input,
+{
+    /// Creates a keyboard with no keys bound.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds a key to an analog input, in the positive or negative direction.
+    ///
+    /// The value of an analog input is the sum of the depths of its bound keys, so binding one
+    /// key in each direction makes the pair act like a thumbstick axis.
+    pub fn bind_axis(&mut self, key: KeyCode, axis: A, positive: bool) {
+        let direction = if positive { 1.0 } else { -1.0 };
+        self.axis_bindings.push((key, axis, direction));
+    }
+
+    /// Binds a key to a digital input, activated while the key is pressed past the actuation
+    /// depth.
+    pub fn bind_button(&mut self, key: KeyCode, button: B) {
+        self.button_bindings.push((key, button));
+    }
+
+    /// Removes every binding of a key.
+    pub fn unbind(&mut self, key: KeyCode) {
+        self.axis_bindings.retain(|&(bound, _, _)| bound != key);
+        self.button_bindings.retain(|&(bound, _)| bound != key);
+    }
+
+    /// Sets the depth at which keys bound to digital inputs activate them.
+    ///
+    /// The default is [`DEFAULT_ACTUATION`].
+    pub fn set_actuation(&mut self, depth: f32) {
+        self.actuation = depth;
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    ///
+    /// See [`GamepadContext::set_deadzone`](crate::GamepadContext::set_deadzone).
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        self.analog_inputs.set_deadzone(deadzone);
+    }
+
+    /// Updates the inputs from the depths of the currently pressed keys.
+    ///
+    /// Depths range from 0.0 for a released key to 1.0 for a fully pressed one, and keys not
+    /// listed are treated as released. `time` must be measured from a fixed epoch and never
+    /// decrease between updates.
+    pub fn update(&mut self, depths: impl IntoIterator<Item = (KeyCode, f32)>, time: Duration) {
+        self.analog_inputs.update(time);
+        self.digital_inputs.update(time);
+
+        self.depths.clear();
+        self.depths.extend(depths);
+        let depth = |key| {
+            self.depths
+                .iter()
+                .find(|&&(pressed, _)| pressed == key)
+                .map_or(0.0, |&(_, depth)| depth)
+        };
+
+        for &axis in A::ALL {
+            let value: f32 = self
+                .axis_bindings
+                .iter()
+                .filter(|binding| binding.1 == axis)
+                .map(|&(key, _, direction)| depth(key) * direction)
+                .sum();
+
+            let last = &mut self.values[axis.index()];
+            if *last != value {
+                *last = value;
+                let sample = Sample::new(AnalogInputValue::from(value), time);
+                self.analog_inputs.set(axis, sample);
+            }
+        }
+
+        for &button in B::ALL {
+            let mut bound = false;
+            let mut pressed = false;
+            for &(key, _) in self.button_bindings.iter().filter(|b| b.1 == button) {
+                bound = true;
+                pressed |= depth(key) >= self.actuation;
+            }
+
+            if !bound || pressed == self.digital_inputs.activated(button) {
+                continue;
+            }
+            if pressed {
+                self.digital_inputs.activate_at(button, time);
+            } else {
+                self.digital_inputs.deactivate_at(button, time);
+            }
+        }
+
+        self.analog_inputs.flush();
+    }
+}
+
+impl<A, B> Default for AnalogKeyboard<A, B>
+where
+    A: Input,
+    B: Input,
+{
+    fn default() -> Self {
+        Self {
+            analog_inputs: Default::default(),
+            digital_inputs: Default::default(),
+            axis_bindings: Vec::new(),
+            button_bindings: Vec::new(),
+            actuation: DEFAULT_ACTUATION,
+            depths: Vec::new(),
+            values: alloc::vec![0.0; A::COUNT],
+        }
+    }
+}
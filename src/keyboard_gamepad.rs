@@ -0,0 +1,221 @@
+//! A keyboard as a virtual gamepad, so that a keyboard player goes through the same [`Gamepad`]
+//! API as controller players.
+//!
+//! The host game or engine reads the keyboard and forwards key presses to a [`KeyboardGamepad`],
+//! which is added to the context as a custom [`Backend`]. Keys are identified by scancodes of the
+//! host's choosing, and bound to buttons and axes of the virtual gamepad.
+//!
+//! ```ignore
+//! let keyboard = KeyboardGamepad::new("Keyboard");
+//! keyboard.bind_axis(KEY_A, Axis::LeftX, false);
+//! keyboard.bind_axis(KEY_D, Axis::LeftX, true);
+//! keyboard.bind_button(KEY_SPACE, Button::South);
+//! context.add_backend(keyboard.clone())?;
+//!
+//! // In the window event handler:
+//! keyboard.key_down(scancode);
+//! ```
+//!
+//! [`Gamepad`]: crate::Gamepad
+
+use crate::backend::{Backend, BackendEvent};
+use crate::error::Result;
+use crate::guid::Guid;
+use crate::{Axis, Button};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// A key, in whatever scancodes the host reports, such as those of winit or SDL2.
+pub type Scancode = u32;
+
+/// The device number of the virtual gamepad within its backend.
+const DEVICE: u16 = 0;
+
+/// A virtual gamepad driven by keyboard keys.
+///
+/// Clones share the same gamepad, so one clone can be added to the context while another is
+/// kept to forward key presses, from any thread. The gamepad connects on the next update after
+/// it is added. Key presses are timestamped with the update that reads them.
+#[derive(Debug, Clone)]
+pub struct KeyboardGamepad {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    name: String,
+    connected: bool,
+    buttons: Vec<(Scancode, Button)>,
+    axes: Vec<(Scancode, Axis, f32)>,
+    held: Vec<Scancode>,
+    events: Vec<(Duration, BackendEvent)>,
+}
+
+impl KeyboardGamepad {
+    /// Creates a virtual gamepad with the given name and no keys bound.
+    pub fn new(name: &str) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                name: name.to_owned(),
+                connected: false,
+                buttons: Vec::new(),
+                axes: Vec::new(),
+                held: Vec::new(),
+                events: Vec::new(),
+            })),
+        }
+    }
+
+    /// Binds a key to a button. Several keys may be bound to the same button, which stays
+    /// pressed while any of them is held.
+    pub fn bind_button(&self, key: Scancode, button: Button) {
+        self.lock()
+            .change(|state| state.buttons.push((key, button)));
+    }
+
+    /// Binds a key to an axis, in the positive or negative direction.
+    ///
+    /// The axis moves to the sum of the directions of its held keys, so binding one key in each
+    /// direction makes the pair act like a thumbstick axis, and holding both centers it.
+    pub fn bind_axis(&self, key: Scancode, axis: Axis, positive: bool) {
+        let direction = if positive { 1.0 } else { -1.0 };
+        self.lock()
+            .change(|state| state.axes.push((key, axis, direction)));
+    }
+
+    /// Removes every binding of a key, releasing its inputs if it is held.
+    pub fn unbind(&self, key: Scancode) {
+        self.lock().change(|state| {
+            state.buttons.retain(|&(other, _)| other != key);
+            state.axes.retain(|&(other, _, _)| other != key);
+        });
+    }
+
+    /// Forwards a key press. Repeated presses of a held key are ignored.
+    pub fn key_down(&self, key: Scancode) {
+        let mut state = self.lock();
+        if !state.held.contains(&key) {
+            state.change(|state| state.held.push(key));
+        }
+    }
+
+    /// Forwards a key release.
+    pub fn key_up(&self, key: Scancode) {
+        self.lock()
+            .change(|state| state.held.retain(|&other| other != key));
+    }
+
+    /// Releases every held key, such as when the window loses focus and releases would be
+    /// missed.
+    pub fn release_all(&self) {
+        self.lock().change(|state| state.held.clear());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    /// Applies a change to the held keys or bindings, queuing events for the inputs whose values
+    /// it changes.
+    fn change(&mut self, change: impl FnOnce(&mut Self)) {
+        let before = self.values();
+        change(self);
+        let after = self.values();
+
+        let value_of = |values: &[(BoundInput, f32)], input| {
+            values
+                .iter()
+                .find(|&&(other, _)| other == input)
+                .map_or(0.0, |&(_, value)| value)
+        };
+        let inputs = after.iter().chain(&before).map(|&(input, _)| input);
+        let mut seen = Vec::new();
+        for input in inputs {
+            if seen.contains(&input) {
+                continue;
+            }
+            seen.push(input);
+            let value = value_of(&after, input);
+            if value == value_of(&before, input) {
+                continue;
+            }
+            let event = match input {
+                BoundInput::Button(button) if value > 0.0 => {
+                    BackendEvent::ButtonDown(DEVICE, button)
+                }
+                BoundInput::Button(button) => BackendEvent::ButtonUp(DEVICE, button),
+                BoundInput::Axis(axis) => BackendEvent::AxisMotion(DEVICE, axis, value),
+            };
+            self.events.push((Duration::ZERO, event));
+        }
+    }
+
+    /// Gets the value of every bound input.
+    fn values(&self) -> Vec<(BoundInput, f32)> {
+        let buttons = self
+            .buttons
+            .iter()
+            .map(|&(_, button)| BoundInput::Button(button));
+        let axes = self.axes.iter().map(|&(_, axis, _)| BoundInput::Axis(axis));
+        buttons
+            .chain(axes)
+            .map(|input| (input, self.value(input)))
+            .collect()
+    }
+
+    /// Gets the value of an input from the held keys, `1.0` for a pressed button.
+    fn value(&self, input: BoundInput) -> f32 {
+        match input {
+            BoundInput::Button(button) => {
+                let pressed = self
+                    .buttons
+                    .iter()
+                    .any(|&(key, other)| other == button && self.held.contains(&key));
+                if pressed {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            BoundInput::Axis(axis) => self
+                .axes
+                .iter()
+                .filter(|&&(key, other, _)| other == axis && self.held.contains(&key))
+                .map(|&(_, _, direction)| direction)
+                .sum::<f32>()
+                .clamp(-1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BoundInput {
+    Button(Button),
+    Axis(Axis),
+}
+
+impl Backend for KeyboardGamepad {
+    fn poll(&mut self, events: &mut Vec<(Duration, BackendEvent)>) -> Result<()> {
+        let mut state = self.lock();
+        if !state.connected {
+            state.connected = true;
+            events.push((
+                Duration::ZERO,
+                BackendEvent::Connected {
+                    device: DEVICE,
+                    name: state.name.clone(),
+                    guid: Guid::default(),
+                    supports_rumble: false,
+                },
+            ));
+        }
+        events.append(&mut state.events);
+        Ok(())
+    }
+
+    fn time(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -1,112 +1,1975 @@
 //! Wrapper library for handling gamepad input. API is unstable and may change at any time.
 //!
-//! Uses SDL2 as the backend by default.
-//! To use gilrs instead, disable default features and enable the `gilrs` feature.
+//! Uses SDL2 as the backend by default, with the `sdl2-backend` feature.
+//! To use gilrs, enable the `gilrs-backend` feature.
+//! For web builds, enable the `wasm-backend` feature, which reads gamepads through the browser's
+//! Gamepad API. SDL2 is never built for WebAssembly, so the default features can stay enabled.
+//!
+//! Backend features can be combined, such as to fall back to gilrs where SDL2 fails to
+//! initialize, or to build for desktop and web from the same manifest. Every enabled backend
+//! that supports the target is compiled in, and the context opens the first that initializes,
+//! in the order `sdl2-backend`, `gilrs-backend`, `wasm-backend` and `headless` unless another is
+//! chosen with `GamepadContextBuilder::prefer`. Enabling none of them is a compile error. The
+//! `sdl2` and `gilrs` features are the former names of `sdl2-backend` and `gilrs-backend`.
+//!
+//! The `mock-backend` feature replaces whichever backend is enabled with virtual gamepads that
+//! are driven through `GamepadContext::mock_backend`, for testing input handling. Enable it on
+//! the dev-dependency only, so that it applies to tests alone.
+//!
+//! For dedicated servers and CI runs, disable default features and enable the `headless`
+//! feature, whose backend reports no gamepads and links no platform libraries, so that input
+//! code builds and runs unchanged.
+//!
+//! Gamepads the built-in backend cannot see, such as controllers streamed over the network, can
+//! be added by implementing `Backend` and adding it with `GamepadContext::add_backend`.
+//!
+//! Disabling the default `std` feature builds only the core state tracking
+//! ([`analog`], [`digital`] and [`input`]) on top of `core` and `alloc`,
+//! for targets that provide their own backend.
+//!
+//! Enabling the `tracing` feature emits [`tracing`](https://docs.rs/tracing) events for
+//! device connections and backend errors, and at trace level for input changes.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
-#![cfg_attr(not(any(feature = "sdl2", feature = "gilrs")), allow(dead_code))]
+#![cfg_attr(
+    not(any(
+        all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+        feature = "gilrs-backend",
+        feature = "wasm-backend",
+        feature = "headless",
+        feature = "mock-backend"
+    )),
+    allow(dead_code)
+)]
+
+extern crate alloc;
 
+#[cfg(feature = "std")]
+pub mod action;
 pub mod analog;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod button_axis;
+#[cfg(feature = "std")]
+pub mod calibration;
+#[cfg(feature = "std")]
+pub mod capture;
+#[cfg(feature = "std")]
+pub mod composite;
+pub mod config;
+#[cfg(feature = "std")]
+pub mod debug;
+#[cfg(feature = "std")]
+pub mod diagnostics;
 pub mod digital;
+#[cfg(feature = "egui")]
+pub mod egui;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(
+    feature = "godot",
+    feature = "macroquad",
+    feature = "miniquad",
+    feature = "notan"
+))]
+pub mod frame;
+pub mod gesture;
+#[cfg(feature = "ggez")]
+pub mod ggez;
+#[cfg(feature = "godot")]
+pub mod godot;
+pub mod guid;
+pub mod input;
+pub mod input_buffer;
+#[cfg(feature = "std")]
+pub mod input_thread;
+pub mod keyboard;
+#[cfg(feature = "std")]
+pub mod keyboard_gamepad;
+#[cfg(feature = "std")]
+pub mod log;
+#[cfg(feature = "macroquad")]
+pub mod macroquad;
+#[cfg(feature = "std")]
+pub mod macros;
+#[cfg(feature = "std")]
+pub mod menu;
+#[cfg(feature = "miniquad")]
+pub mod miniquad;
+#[cfg(feature = "std")]
+pub mod motion;
+#[cfg(feature = "notan")]
+pub mod notan;
+#[cfg(feature = "std")]
+pub mod player;
+#[cfg(feature = "std")]
+pub mod power;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod prompt;
+#[cfg(feature = "std")]
+pub mod raw;
+#[cfg(feature = "std")]
+pub mod recorder;
+#[cfg(feature = "std")]
+pub mod rollback;
+pub mod scanning;
+#[cfg(feature = "std")]
+pub mod slots;
+#[cfg(feature = "std")]
+pub mod snapshot;
+pub mod source;
+pub mod state;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod touchpad;
+#[cfg(feature = "std")]
+pub mod trigger;
+#[cfg(feature = "std")]
+pub mod virtual_button;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+#[cfg(feature = "winit")]
+pub mod winit;
 
+#[cfg(feature = "std")]
+mod arena;
+mod axis;
+#[cfg(feature = "std")]
 mod backend;
+mod button;
+#[cfg(feature = "std")]
+mod mapping_db;
+#[cfg(feature = "std")]
+mod mappings;
+#[cfg(feature = "std")]
+mod rebind;
+#[cfg(feature = "std")]
+mod reconnect;
 
-pub use backend::{Axis, Button};
+pub use axis::Axis;
+#[cfg(feature = "mock-backend")]
+pub use backend::MockBackend;
+#[cfg(feature = "std")]
+pub use backend::{Backend, BackendEvent, BackendKind};
+pub use button::Button;
 
+#[cfg(feature = "std")]
+use action::Binding;
+#[cfg(feature = "std")]
 use analog::AnalogInput;
-use analog::{AnalogInputValue, Deadzone};
-use backend::Backend;
+#[cfg(feature = "std")]
+use analog::{AnalogInputValue, Coalescing, Deadzone, ResponseCurve, Sample, Smoothing};
+#[cfg(feature = "std")]
+use arena::GamepadMap;
+#[cfg(feature = "std")]
+use backend::{CustomBackend, Event, NativeBackend, Route};
+#[cfg(feature = "std")]
 use backend::{ImplementationContext, OwnedImplementationGamepad};
-use digital::DigitalInput;
+#[cfg(feature = "std")]
+use builder::GamepadContextBuilder;
+#[cfg(feature = "std")]
+use button_axis::{ButtonAxis, ButtonAxisState};
+#[cfg(feature = "std")]
+use calibration::{CalibrationStep, GamepadCalibration, TimedCalibration};
+#[cfg(feature = "std")]
+use capture::{CaptureEvent, CapturePolicy};
+#[cfg(feature = "std")]
+use config::{Config, Haptics};
+#[cfg(feature = "std")]
+use debug::{DebugState, GamepadDebugState};
+#[cfg(feature = "std")]
+use digital::{DigitalInput, KeyRepeat};
+#[cfg(feature = "std")]
+use guid::Guid;
+#[cfg(feature = "std")]
+use input::{Input, InputSet};
+#[cfg(feature = "std")]
+use log::{EventLog, LogEntry};
+#[cfg(feature = "std")]
+use macros::{Macro, MacroId, Macros};
+#[cfg(feature = "std")]
+use mappings::MappingsWatch;
+#[cfg(feature = "std")]
+use motion::{MotionSensor, MotionState};
+#[cfg(feature = "std")]
+use player::PlayerRegistry;
+#[cfg(feature = "std")]
+use power::PowerInfo;
+#[cfg(feature = "std")]
+use prompt::DeviceKind;
+#[cfg(feature = "std")]
+use raw::{RawInput, RawState};
+#[cfg(feature = "std")]
+use rebind::BindingListener;
+#[cfg(feature = "std")]
+use reconnect::Reconnects;
+#[cfg(feature = "std")]
+use recorder::{InputRecording, Recorder};
+#[cfg(feature = "std")]
+use rollback::InputState;
+#[cfg(feature = "std")]
+use snapshot::{SharedGamepadState, Snapshot, ViewId};
+#[cfg(feature = "std")]
 use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::{RwLock, Weak};
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "std")]
+use touchpad::{Touchpad, TouchpadState};
+#[cfg(feature = "std")]
+use trigger::{Trigger, TriggerEffect, TriggerInput};
+#[cfg(feature = "std")]
+use virtual_button::VirtualButton;
 
-use error::Result;
+#[cfg(feature = "std")]
+use error::{Error, Result};
 
 /// The instance Id of a gamepad.
+///
+/// Ids are unique across the built-in backend and every custom [`Backend`].
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct GamepadId(backend::ImplementationId);
+pub struct GamepadId(backend::RawId);
 
 /// Holds the state of a gamepad.
+#[cfg(feature = "std")]
 pub struct Gamepad {
+    name: String,
+    guid: Guid,
+    device_kind: DeviceKind,
+    supports_rumble: bool,
+    buttons: InputSet<Button>,
+    has_led: bool,
+    supports_trigger_effects: bool,
+    player_index: Option<u8>,
+    reconnected_from: Option<GamepadId>,
+    power_info: PowerInfo,
+    // When the battery was last read, or `None` if it has not been read yet.
+    power_polled: Option<Duration>,
+    motion: MotionState,
+    touchpad: TouchpadState,
+    button_axes: ButtonAxisState,
+    raw: RawState,
     // Backends that produce owned gamepads, such as SDL2, need this to keep the gamepads open.
     // Backends that produce references to gamepads, such as gilrs, do not need this.
-    #[allow(dead_code)]
     owned_internal_gamepad: Option<OwnedImplementationGamepad>,
     /// Analog inputs, such as thumbsticks.
     pub analog_inputs: AnalogInput<Axis>,
     /// Digital inputs, such as buttons.
     pub digital_inputs: DigitalInput<Button>,
+    /// Stick directions and triggers as digital inputs, see [`virtual_button`].
+    pub virtual_buttons: DigitalInput<VirtualButton>,
 }
 
+#[cfg(feature = "std")]
 impl Gamepad {
-    fn new(owned_internal_gamepad: Option<OwnedImplementationGamepad>) -> Self {
+    fn new(
+        name: String,
+        guid: Guid,
+        owned_internal_gamepad: Option<OwnedImplementationGamepad>,
+        supports_rumble: bool,
+        analog_inputs: AnalogInput<Axis>,
+    ) -> Self {
         Self {
+            guid,
+            device_kind: DeviceKind::detect(&name, guid),
+            supports_rumble,
+            buttons: InputSet::default(),
+            has_led: false,
+            supports_trigger_effects: false,
+            player_index: None,
+            reconnected_from: None,
+            power_info: PowerInfo::default(),
+            power_polled: None,
+            motion: MotionState::default(),
+            touchpad: TouchpadState::default(),
+            button_axes: ButtonAxisState::default(),
+            raw: RawState::default(),
+            name,
             owned_internal_gamepad,
-            analog_inputs: Default::default(),
+            analog_inputs,
             digital_inputs: Default::default(),
+            virtual_buttons: Default::default(),
         }
     }
 
-    fn update_inputs(&mut self) {
-        self.analog_inputs.update();
-        self.digital_inputs.update();
+    /// Gets the name of the gamepad, as reported by the backend.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the GUID of the gamepad's model, in the layout used by SDL, for keeping settings per
+    /// device.
+    ///
+    /// The GUID is all zeroes if the backend could not identify the gamepad.
+    pub fn guid(&self) -> Guid {
+        self.guid
+    }
+
+    /// Gets the USB vendor ID of the gamepad, if the backend reports it.
+    pub fn vendor_id(&self) -> Option<u16> {
+        self.guid.vendor_id()
+    }
+
+    /// Gets the USB product ID of the gamepad, if the backend reports it.
+    pub fn product_id(&self) -> Option<u16> {
+        self.guid.product_id()
+    }
+
+    /// Gets the family of the gamepad, for showing matching button prompts.
+    ///
+    /// The family is detected from the USB vendor and product IDs where the backend reports them,
+    /// and from the name of the gamepad otherwise.
+    pub fn device_kind(&self) -> DeviceKind {
+        self.device_kind
+    }
+
+    /// Checks if the gamepad has rumble motors, for [`GamepadContext::rumble`].
+    pub fn supports_rumble(&self) -> bool {
+        self.supports_rumble
+    }
+
+    /// Checks if the gamepad has a button, such as for only offering bindings to the buttons a
+    /// player can press.
+    ///
+    /// Where the backend cannot tell, gamepads have every button it can report.
+    pub fn has_button(&self, button: Button) -> bool {
+        self.buttons.contains(button)
+    }
+
+    /// Checks if the gamepad has rear paddles, such as those of Xbox Elite and DualSense Edge
+    /// controllers, for showing paddle bindings only to players who have them.
+    pub fn has_paddles(&self) -> bool {
+        [
+            Button::Paddle1,
+            Button::Paddle2,
+            Button::Paddle3,
+            Button::Paddle4,
+        ]
+        .into_iter()
+        .any(|paddle| self.has_button(paddle))
+    }
+
+    /// Checks if the gamepad has an LED whose color can be set, such as the light bar of a
+    /// DualShock 4, for [`GamepadContext::set_led`].
+    pub fn has_led(&self) -> bool {
+        self.has_led
+    }
+
+    /// Checks if the gamepad has adaptive triggers, for [`GamepadContext::set_trigger_effect`].
+    pub fn supports_trigger_effects(&self) -> bool {
+        self.supports_trigger_effects
+    }
+
+    /// Gets the player index last set with [`GamepadContext::set_player_index`].
+    pub fn player_index(&self) -> Option<u8> {
+        self.player_index
+    }
+
+    /// Gets the Id the gamepad had before it last disconnected, if it reconnected within the
+    /// grace period set with [`GamepadContext::set_reconnect_grace`].
+    ///
+    /// A reconnected gamepad gets back the settings it had, such as its per-axis deadzones,
+    /// response curves, debounce, key repeat, player index and enabled sensors, and is assigned
+    /// to the same player in the [`PlayerRegistry`].
+    pub fn reconnected_from(&self) -> Option<GamepadId> {
+        self.reconnected_from
+    }
+
+    /// Gets the battery level of the gamepad and whether it is charging, such as for warning the
+    /// player about a dying controller.
+    ///
+    /// The battery is read from the backend about once per second.
+    pub fn power_info(&self) -> PowerInfo {
+        self.power_info
+    }
+
+    /// Checks if the gamepad has a motion sensor, for [`GamepadContext::set_sensor_enabled`].
+    pub fn has_sensor(&self, sensor: MotionSensor) -> bool {
+        self.motion.supports(sensor)
+    }
+
+    /// Checks if a motion sensor of the gamepad is enabled.
+    pub fn sensor_enabled(&self, sensor: MotionSensor) -> bool {
+        self.motion.is_enabled(sensor)
+    }
+
+    /// Gets the latest reading of the gyroscope, in radians per second around each axis, or
+    /// `None` unless it is enabled.
+    ///
+    /// See [`motion`] for the orientation of the axes.
+    pub fn gyro(&self) -> Option<[f32; 3]> {
+        self.motion.reading(MotionSensor::Gyroscope)
+    }
+
+    /// Gets the latest reading of the accelerometer, in meters per second squared along each
+    /// axis, or `None` unless it is enabled.
+    ///
+    /// See [`motion`] for the orientation of the axes.
+    pub fn accel(&self) -> Option<[f32; 3]> {
+        self.motion.reading(MotionSensor::Accelerometer)
+    }
+
+    /// Iterates over the buttons and axes that changed during the most recent update.
+    ///
+    /// Buttons are listed when pressed or released, and axes whenever their raw value moved.
+    /// Only changed inputs are visited, so systems that react to changes need not scan the full
+    /// state of every gamepad each frame.
+    pub fn changed_this_frame(&self) -> impl Iterator<Item = ChangedInput> + '_ {
+        let buttons = self.digital_inputs.changed().map(ChangedInput::Button);
+        let axes = self.analog_inputs.changed().map(ChangedInput::Axis);
+        buttons.chain(axes)
+    }
+
+    /// Gets the direction the d-pad is pressed in, as `(x, y)` with each of `-1`, `0` or `1`, and
+    /// `x` positive to the right and `y` positive downwards, as with [`Direction`].
+    ///
+    /// Backends report d-pads as buttons whether the device has them as buttons, a hat or a pair
+    /// of axes, so this reads the d-pad buttons. Opposite buttons held together cancel out.
+    ///
+    /// [`Direction`]: input_buffer::Direction
+    pub fn dpad(&self) -> (i8, i8) {
+        let pressed = |button| self.digital_inputs.activated(button) as i8;
+        (
+            pressed(Button::DPadRight) - pressed(Button::DPadLeft),
+            pressed(Button::DPadDown) - pressed(Button::DPadUp),
+        )
+    }
+
+    /// Gets the analog triggers of the gamepad, with values from `0.0` to `1.0`.
+    pub fn triggers(&self) -> TriggerInput<'_> {
+        TriggerInput::new(&self.analog_inputs)
+    }
+
+    /// Gets the first touchpad of the gamepad, or `None` if it has none.
+    pub fn touchpad(&self) -> Option<Touchpad<'_>> {
+        self.touchpads().next()
+    }
+
+    /// Gets an iterator over the touchpads of the gamepad, such as both trackpads of the Steam
+    /// Deck.
+    pub fn touchpads(&self) -> impl Iterator<Item = Touchpad<'_>> {
+        self.touchpad
+            .pads
+            .iter()
+            .enumerate()
+            .map(|(index, pad)| Touchpad::new(index, pad, &self.digital_inputs))
+    }
+
+    /// Gets the raw, unmapped buttons and axes of the gamepad, for inputs that do not fit
+    /// [`Button`] and [`Axis`]. See [`raw`].
+    pub fn raw(&self) -> RawInput<'_> {
+        RawInput::new(&self.raw)
+    }
+
+    /// Captures the current input as a compact, `Copy` state, such as for rollback netcode.
+    pub fn snapshot(&self) -> InputState {
+        InputState::new(self)
+    }
+
+    fn update_inputs(&mut self, time: Duration) {
+        self.analog_inputs.update(time);
+        self.digital_inputs.update(time);
+        self.virtual_buttons.update(time);
+    }
+
+    fn flush_inputs(&mut self) {
+        self.analog_inputs.flush();
+        let time = self.virtual_buttons.time();
+        virtual_button::update(&self.analog_inputs, &mut self.virtual_buttons, time);
     }
 }
 
+/// A gamepad being connected or disconnected, as listed by [`GamepadContext::events`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadEvent {
+    /// A gamepad was connected, and can now be read with [`GamepadContext::gamepad`].
+    Connected(GamepadId),
+    /// A gamepad was disconnected. It has already been removed from the context.
+    Disconnected(GamepadId),
+}
+
+/// A change of input on a gamepad, as listed by [`GamepadContext::poll_events`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    /// A button was pressed.
+    ButtonPressed {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The button.
+        button: Button,
+        /// When the button was pressed, on the clock of [`GamepadContext::time`].
+        time: Duration,
+    },
+    /// A button was released.
+    ButtonReleased {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The button.
+        button: Button,
+        /// When the button was released, on the clock of [`GamepadContext::time`].
+        time: Duration,
+    },
+    /// An axis moved.
+    AxisChanged {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The axis.
+        axis: Axis,
+        /// The value the axis moved to, after calibration but before deadzones.
+        value: f32,
+        /// When the axis moved, on the clock of [`GamepadContext::time`].
+        time: Duration,
+    },
+}
+
+/// An input of a gamepad that changed, as listed by [`Gamepad::changed_this_frame`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangedInput {
+    /// A button that was pressed or released.
+    Button(Button),
+    /// An axis whose value moved.
+    Axis(Axis),
+}
+
+/// How a [`GamepadContext`] reads events from its backend.
+///
+/// In both modes, the `just_*` queries reflect every event that arrived
+/// between the two most recent calls to [`GamepadContext::update`].
+#[cfg(feature = "std")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// The backend is only read when [`GamepadContext::update`] is called.
+    #[default]
+    PollOnUpdate,
+    /// The backend is read whenever [`GamepadContext::ingest`] is called, and the events are
+    /// buffered until the next call to [`GamepadContext::update`].
+    ///
+    /// This keeps the backend's own event queue short when updates are infrequent.
+    ContinuousIngest,
+}
+
 /// The gamepad system context.
 ///
 /// Only one `GamepadContext` should be alive at any time.
+#[cfg(feature = "std")]
 pub struct GamepadContext {
     gamepad_system: ImplementationContext,
-    gamepads: HashMap<GamepadId, Gamepad>,
+    custom_backends: Vec<CustomBackend>,
+    gamepads: GamepadMap<Gamepad>,
+    // Carries the analog settings given to newly connected gamepads.
+    default_analog_inputs: AnalogInput<Axis>,
+    // The repeat and debounce given to newly connected gamepads.
+    default_repeat: Option<KeyRepeat>,
+    default_debounce: Option<Duration>,
+    haptics: Haptics,
+    button_axes: Vec<(Axis, ButtonAxis)>,
+    mode: Mode,
+    events: Vec<(Duration, Event)>,
+    tick_front: Snapshot,
+    tick_back: Snapshot,
+    views: HashMap<ViewId, Snapshot>,
+    shared_states: Vec<Weak<RwLock<Snapshot>>>,
+    next_view_id: usize,
+    players: PlayerRegistry,
+    reconnects: Reconnects,
+    capture_policy: CapturePolicy,
+    capture_events: Vec<CaptureEvent>,
+    gamepad_events: Vec<GamepadEvent>,
+    input_events: Vec<InputEvent>,
+    // Calibrations by gamepad name, applied whenever a gamepad of that name connects.
+    calibrations: HashMap<String, GamepadCalibration>,
+    timed_calibrations: Vec<TimedCalibration>,
+    mappings_watch: Option<MappingsWatch>,
+    macros: Macros,
+    recorder: Recorder,
+    event_log: Option<EventLog>,
+    binding_listener: Option<BindingListener>,
+    listened_binding: Option<(GamepadId, Binding)>,
 }
 
+#[cfg(feature = "std")]
 impl GamepadContext {
-    /// Initializes the gamepad context.
+    /// Creates a builder for configuring the gamepad context before initializing it.
+    pub fn builder() -> GamepadContextBuilder {
+        GamepadContextBuilder::default()
+    }
+
+    /// Initializes the gamepad context with the default configuration.
+    ///
+    /// This is a shorthand for `GamepadContext::builder().build()`. Fails with
+    /// [`Error::BackendInit`] if the backend cannot be started, such as when the platform has no
+    /// gamepad support, and the error's message can be shown to players as is.
+    pub fn new() -> Result<Self> {
+        Self::builder().build()
+    }
+
+    /// Initializes the gamepad context with the default configuration.
+    ///
+    /// This is the same as [`new`](Self::new).
     pub fn init() -> Result<Self> {
-        let gamepad_system = ImplementationContext::new()?;
-        let gamepads = HashMap::new();
+        Self::new()
+    }
+
+    /// Initializes the gamepad context in the given mode.
+    pub fn init_with_mode(mode: Mode) -> Result<Self> {
+        Self::builder().mode(mode).build()
+    }
 
-        Ok(Self {
+    fn with_backend(gamepad_system: ImplementationContext, mode: Mode) -> Self {
+        let gamepads = GamepadMap::new();
+
+        Self {
             gamepad_system,
+            custom_backends: Vec::new(),
             gamepads,
-        })
+            default_analog_inputs: AnalogInput::default(),
+            default_repeat: None,
+            default_debounce: None,
+            haptics: Haptics::default(),
+            button_axes: Vec::new(),
+            mode,
+            events: Vec::new(),
+            tick_front: Snapshot::default(),
+            tick_back: Snapshot::default(),
+            views: HashMap::new(),
+            shared_states: Vec::new(),
+            next_view_id: 0,
+            players: PlayerRegistry::default(),
+            reconnects: Reconnects::default(),
+            capture_policy: CapturePolicy::default(),
+            capture_events: Vec::new(),
+            gamepad_events: Vec::new(),
+            input_events: Vec::new(),
+            calibrations: HashMap::new(),
+            timed_calibrations: Vec::new(),
+            mappings_watch: None,
+            macros: Macros::default(),
+            recorder: Recorder::default(),
+            event_log: None,
+            binding_listener: None,
+            listened_binding: None,
+        }
+    }
+
+    /// Initializes the gamepad context from an SDL context owned by the application.
+    ///
+    /// The gamepad context never creates an event pump of its own, so it does not conflict with
+    /// the application's. Instead, every event read from the application's event pump must be
+    /// passed to [`handle_sdl_event`](Self::handle_sdl_event) before calling
+    /// [`update`](Self::update).
+    ///
+    /// This is a shorthand for [`GamepadContextBuilder::sdl_context`].
+    #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+    pub fn init_with_sdl(sdl_context: &sdl2::Sdl, mode: Mode) -> Result<Self> {
+        Self::builder().sdl_context(sdl_context).mode(mode).build()
+    }
+
+    /// Handles an event read from an event pump owned by the application.
+    ///
+    /// Does nothing unless the context was initialized with [`init_with_sdl`](Self::init_with_sdl).
+    #[cfg(all(feature = "sdl2-backend", not(target_arch = "wasm32")))]
+    pub fn handle_sdl_event(&mut self, event: &sdl2::event::Event) {
+        self.gamepad_system.handle_sdl_event(event);
+    }
+
+    /// Gets the built-in backend the context opened, or `None` with the mock backend.
+    pub fn backend_kind(&self) -> Option<BackendKind> {
+        self.gamepad_system.kind()
+    }
+
+    /// Gets the backend of virtual gamepads, to connect gamepads and change their inputs.
+    ///
+    /// Changes take effect on the next [`update`](Self::update).
+    #[cfg(feature = "mock-backend")]
+    pub fn mock_backend(&mut self) -> &mut MockBackend {
+        self.gamepad_system.mock()
+    }
+
+    /// Adds a custom backend, whose gamepads are reported alongside those of the built-in
+    /// backend from the next [`update`](Self::update).
+    ///
+    /// Fails if there are already too many custom backends.
+    pub fn add_backend(&mut self, backend: impl Backend + 'static) -> Result<()> {
+        self.add_boxed_backend(Box::new(backend))
+    }
+
+    fn add_boxed_backend(&mut self, backend: Box<dyn Backend>) -> Result<()> {
+        let index = u16::try_from(self.custom_backends.len())
+            .map_err(|_| Error::Other("too many custom backends".into()))?;
+        self.custom_backends
+            .push(CustomBackend::new(index, backend));
+        Ok(())
+    }
+
+    /// Gets the current time of the backend's clock.
+    ///
+    /// This is the clock that analog input samples are timestamped with,
+    /// for use with [`AnalogInput::interpolated_value`].
+    pub fn time(&self) -> Duration {
+        self.gamepad_system.time()
+    }
+
+    /// Gets the mode the context was initialized in.
+    pub fn mode(&self) -> Mode {
+        self.mode
     }
 
     /// Gets a reference to a specific gamepad.
     ///
     /// Returns `None` if the gamepad is not found.
     pub fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
-        self.gamepads.get(&id)
+        self.gamepads.get(id)
+    }
+
+    /// Gets a reference to the gamepad whose Id converts to the given integer.
+    #[cfg(any(feature = "ffi", feature = "godot", feature = "wasm-bindgen"))]
+    pub(crate) fn gamepad_by_index(&self, index: usize) -> Option<&Gamepad> {
+        self.gamepads()
+            .find(|&(id, _)| usize::from(id) == index)
+            .map(|(_, gamepad)| gamepad)
+    }
+
+    /// Gets a mutable reference to a specific gamepad, such as for changing its input state.
+    ///
+    /// Returns `None` if the gamepad is not found.
+    pub fn gamepad_mut(&mut self, id: GamepadId) -> Option<&mut Gamepad> {
+        self.gamepads.get_mut(id)
     }
 
-    /// Gets an iterator over all gamepads.
+    /// Gets an iterator over all connected gamepads, in the order they connected.
+    ///
+    /// The order is stable: a disconnecting gamepad leaves the others in place, and a gamepad
+    /// that reconnects comes last.
     pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
-        self.gamepads.iter().map(|(&id, gamepad)| (id, gamepad))
+        self.gamepads.iter()
+    }
+
+    /// Gets a mutable iterator over all connected gamepads, in the same order as
+    /// [`gamepads`](Self::gamepads).
+    pub fn gamepads_mut(&mut self) -> impl Iterator<Item = (GamepadId, &mut Gamepad)> {
+        self.gamepads.iter_mut()
+    }
+
+    /// Gets the number of connected gamepads.
+    pub fn len(&self) -> usize {
+        self.gamepads.len()
+    }
+
+    /// Checks if no gamepads are connected.
+    pub fn is_empty(&self) -> bool {
+        self.gamepads.is_empty()
+    }
+
+    /// Gets a structure describing everything the context knows, such as connected gamepads,
+    /// deadzones, and both raw and processed input values.
+    ///
+    /// This is intended for debug overlays and bug reports. Enable the `serde` feature to
+    /// serialize it.
+    pub fn debug_state(&self) -> DebugState {
+        DebugState {
+            mode: self.mode,
+            gamepads: self
+                .gamepads
+                .iter()
+                .map(|(id, gamepad)| GamepadDebugState::new(id, gamepad))
+                .collect(),
+        }
+    }
+
+    /// Reads all pending events from the backend into the context's buffer,
+    /// without changing the state of any gamepad.
+    ///
+    /// Does nothing unless the context is in [`Mode::ContinuousIngest`].
+    pub fn ingest(&mut self) -> Result<()> {
+        match self.mode {
+            Mode::PollOnUpdate => Ok(()),
+            Mode::ContinuousIngest => self.read_backends(),
+        }
     }
 
     /// Updates the state of all gamepads.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self), err)
+    )]
     pub fn update(&mut self) -> Result<()> {
-        self.gamepad_system.update(&mut self.gamepads)
+        if let Err(error) = self.poll_backend() {
+            let time = self.gamepad_system.time();
+            self.log(time, || LogEntry::Error(error.to_string()));
+            return Err(error);
+        }
+
+        let time = self.gamepad_system.time();
+        if let Some(log) = &mut self.event_log {
+            log.record(&self.events);
+        }
+        self.macros.record(&self.events);
+        self.macros.play(time, &mut self.events);
+        self.recorder
+            .process(&self.gamepads, time, &mut self.events);
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.update_inputs(time);
+        }
+        self.capture_events.clear();
+        self.gamepad_events.clear();
+        self.input_events.clear();
+
+        let mut reconnected = Vec::new();
+        for (time, event) in self.events.drain(..) {
+            match event {
+                Event::Connected {
+                    id,
+                    name,
+                    guid,
+                    owned_internal_gamepad,
+                    supports_rumble,
+                } => {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!(?id, %name, %guid, "gamepad connected");
+                    #[cfg(all(debug_assertions, not(feature = "tracing")))]
+                    println!("Added gamepad \"{}\"", name);
+
+                    let mut analog_inputs = self.default_analog_inputs.clone();
+                    if let Some(calibration) = self.calibrations.get(&calibration_key(&name, guid))
+                    {
+                        for &(axis, calibration) in &calibration.axes {
+                            analog_inputs.set_calibration(axis, Some(calibration));
+                        }
+                        if let Some(log) = &mut self.event_log {
+                            let message = format!("applied stored calibration to {:?}", id);
+                            log.push(time, LogEntry::Mapping(message));
+                        }
+                    }
+                    let mut gamepad = Gamepad::new(
+                        name,
+                        guid,
+                        owned_internal_gamepad,
+                        supports_rumble,
+                        analog_inputs,
+                    );
+                    gamepad.digital_inputs.set_repeat(self.default_repeat);
+                    gamepad.digital_inputs.set_debounce(self.default_debounce);
+                    for &sensor in MotionSensor::ALL {
+                        let supported = match CustomBackend::route(
+                            &mut self.custom_backends,
+                            &mut self.gamepad_system,
+                            id,
+                        ) {
+                            Route::Custom(backend, device) => backend.has_sensor(device, sensor),
+                            Route::Native(system) => system.has_sensor(
+                                id,
+                                gamepad.owned_internal_gamepad.as_ref(),
+                                sensor,
+                            ),
+                        };
+                        gamepad.motion.set_supported(sensor, supported);
+                    }
+                    for &button in Button::ALL {
+                        let has_button = match CustomBackend::route(
+                            &mut self.custom_backends,
+                            &mut self.gamepad_system,
+                            id,
+                        ) {
+                            Route::Custom(backend, device) => backend.has_button(device, button),
+                            Route::Native(system) => system.has_button(
+                                id,
+                                gamepad.owned_internal_gamepad.as_ref(),
+                                button,
+                            ),
+                        };
+                        if has_button {
+                            gamepad.buttons.insert(button);
+                        }
+                    }
+                    gamepad.has_led = match CustomBackend::route(
+                        &mut self.custom_backends,
+                        &mut self.gamepad_system,
+                        id,
+                    ) {
+                        Route::Custom(backend, device) => backend.has_led(device),
+                        Route::Native(system) => {
+                            system.has_led(id, gamepad.owned_internal_gamepad.as_ref())
+                        }
+                    };
+                    gamepad.supports_trigger_effects = match CustomBackend::route(
+                        &mut self.custom_backends,
+                        &mut self.gamepad_system,
+                        id,
+                    ) {
+                        Route::Custom(backend, device) => backend.supports_trigger_effects(device),
+                        Route::Native(system) => system
+                            .supports_trigger_effects(id, gamepad.owned_internal_gamepad.as_ref()),
+                    };
+                    let touchpads = match CustomBackend::route(
+                        &mut self.custom_backends,
+                        &mut self.gamepad_system,
+                        id,
+                    ) {
+                        Route::Custom(backend, device) => backend.touchpad_count(device),
+                        Route::Native(system) => {
+                            system.touchpad_count(id, gamepad.owned_internal_gamepad.as_ref())
+                        }
+                    };
+                    gamepad.touchpad.pads = vec![Default::default(); touchpads];
+                    if let Some(log) = &mut self.event_log {
+                        let message = format!("{:?} detected as {:?}", id, gamepad.device_kind);
+                        log.push(time, LogEntry::Mapping(message));
+                    }
+                    if let Some(previous) = self.reconnects.take(&gamepad, time) {
+                        #[cfg(feature = "tracing")]
+                        tracing::info!(?id, previous = ?previous.id, "gamepad reconnected");
+                        let old = &previous.gamepad;
+                        gamepad
+                            .analog_inputs
+                            .copy_input_settings(&old.analog_inputs);
+                        gamepad
+                            .digital_inputs
+                            .set_debounce(old.digital_inputs.debounce());
+                        gamepad
+                            .digital_inputs
+                            .set_repeat(old.digital_inputs.repeat());
+                        gamepad.reconnected_from = Some(previous.id);
+                        if let Some(player) = previous.player {
+                            self.players.assign(player, id);
+                        }
+                        // Settings of the device itself are restored once the gamepad is in
+                        // place, below.
+                        reconnected.push((id, previous.gamepad));
+                    }
+                    self.gamepads.insert(id, gamepad);
+                    self.gamepad_events.push(GamepadEvent::Connected(id));
+                }
+                Event::Disconnected(id) => {
+                    let player = self.players.player(id);
+                    self.players.unassign(id);
+                    if let Some(gamepad) = self.gamepads.remove(id) {
+                        self.gamepad_events.push(GamepadEvent::Disconnected(id));
+                        #[cfg(feature = "tracing")]
+                        tracing::info!(?id, name = %gamepad.name, "gamepad disconnected");
+                        #[cfg(all(debug_assertions, not(feature = "tracing")))]
+                        println!("Removed gamepad \"{}\"", gamepad.name);
+                        self.reconnects.push(id, gamepad, player, time);
+                    }
+                }
+                Event::AxisMotion(id, axis, value) => {
+                    if let Some(gamepad) = self.gamepads.get_mut(id) {
+                        gamepad.analog_inputs.set(axis, Sample::new(value, time));
+                        let value = match gamepad.analog_inputs.calibration(axis) {
+                            Some(calibration) => calibration.apply(value.get()),
+                            None => value.get(),
+                        };
+                        self.input_events.push(InputEvent::AxisChanged {
+                            gamepad: id,
+                            axis,
+                            value,
+                            time,
+                        });
+                    }
+                }
+                Event::ButtonDown(id, button) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(?id, ?button, "button pressed");
+                    if self.gamepad_system.capture_button() == Some(button)
+                        && self.gamepads.contains_key(id)
+                    {
+                        let reserved = match self.capture_policy {
+                            CapturePolicy::Deliver => false,
+                            CapturePolicy::Ignore => continue,
+                            CapturePolicy::Reserved => true,
+                        };
+                        self.capture_events.push(CaptureEvent {
+                            gamepad: id,
+                            time,
+                            reserved,
+                        });
+                        if reserved {
+                            continue;
+                        }
+                    }
+                    if let Some(gamepad) = self.gamepads.get_mut(id) {
+                        let was_pressed = gamepad.digital_inputs.activated(button);
+                        gamepad.digital_inputs.activate_at(button, time);
+                        if !was_pressed && gamepad.digital_inputs.activated(button) {
+                            self.input_events.push(InputEvent::ButtonPressed {
+                                gamepad: id,
+                                button,
+                                time,
+                            });
+                        }
+                    }
+                }
+                Event::ButtonUp(id, button) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(?id, ?button, "button released");
+                    if let Some(gamepad) = self.gamepads.get_mut(id) {
+                        let was_pressed = gamepad.digital_inputs.activated(button);
+                        gamepad.digital_inputs.deactivate_at(button, time);
+                        if was_pressed && !gamepad.digital_inputs.activated(button) {
+                            self.input_events.push(InputEvent::ButtonReleased {
+                                gamepad: id,
+                                button,
+                                time,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        for (id, previous) in reconnected {
+            self.restore_device_settings(id, &previous);
+        }
+
+        for (id, gamepad) in self.gamepads.iter_mut() {
+            gamepad.button_axes.update(
+                &self.button_axes,
+                &gamepad.digital_inputs,
+                &mut gamepad.analog_inputs,
+                time,
+            );
+            gamepad.flush_inputs();
+
+            let due = gamepad
+                .power_polled
+                .is_none_or(|polled| time.saturating_sub(polled) >= power::POLL_INTERVAL);
+            if due {
+                gamepad.power_info = match CustomBackend::route(
+                    &mut self.custom_backends,
+                    &mut self.gamepad_system,
+                    id,
+                ) {
+                    Route::Custom(backend, device) => backend.power_info(device),
+                    Route::Native(system) => {
+                        system.power_info(id, gamepad.owned_internal_gamepad.as_ref())
+                    }
+                };
+                gamepad.power_polled = Some(time);
+            }
+
+            for &sensor in MotionSensor::ALL {
+                if !gamepad.motion.is_enabled(sensor) {
+                    continue;
+                }
+                let data = match CustomBackend::route(
+                    &mut self.custom_backends,
+                    &mut self.gamepad_system,
+                    id,
+                ) {
+                    Route::Custom(backend, device) => backend.sensor_data(device, sensor),
+                    Route::Native(system) => {
+                        system.sensor_data(id, gamepad.owned_internal_gamepad.as_ref(), sensor)
+                    }
+                };
+                if let Some(data) = data {
+                    gamepad.motion.set_reading(sensor, data);
+                }
+            }
+
+            for (index, pad) in gamepad.touchpad.pads.iter_mut().enumerate() {
+                let fingers = &mut pad.fingers;
+                pad.clicked = match CustomBackend::route(
+                    &mut self.custom_backends,
+                    &mut self.gamepad_system,
+                    id,
+                ) {
+                    Route::Custom(backend, device) => {
+                        backend.read_touchpad_at(device, index, fingers)
+                    }
+                    Route::Native(system) => system.read_touchpad(
+                        id,
+                        gamepad.owned_internal_gamepad.as_ref(),
+                        index,
+                        fingers,
+                    ),
+                };
+            }
+
+            let RawState { buttons, axes } = &mut gamepad.raw;
+            match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+                Route::Custom(backend, device) => backend.read_raw(device, buttons, axes),
+                Route::Native(system) => {
+                    system.read_raw(id, gamepad.owned_internal_gamepad.as_ref(), buttons, axes);
+                }
+            }
+        }
+
+        self.update_calibrations(time);
+        self.macros.trigger(&self.gamepads, time);
+        self.players.update(&self.gamepads, time);
+        if let Some(listener) = &mut self.binding_listener {
+            self.listened_binding = listener.poll(&self.gamepads);
+            if self.listened_binding.is_some() {
+                self.binding_listener = None;
+            }
+        }
+
+        self.tick_back.accumulate(&self.gamepads);
+        for view in self.views.values_mut() {
+            view.accumulate(&self.gamepads);
+        }
+        SharedGamepadState::publish(&mut self.shared_states, &self.gamepads);
+
+        Ok(())
+    }
+
+    /// Adds gamepad mappings, in the SDL format of `gamecontrollerdb.txt`, so that gamepads the
+    /// backend does not know get the right layout.
+    ///
+    /// Mappings added later take precedence over earlier ones and over the mappings file, for
+    /// gamepads connected from then on. Every mapping is checked before any is added, so a
+    /// malformed line fails the whole call, naming the line.
+    ///
+    /// SDL2 and gilrs apply mappings themselves; gilrs reconnects every gamepad to do so. The
+    /// browser backend applies them to gamepads the browser has no standard mapping for.
+    pub fn add_mappings(&mut self, mappings: &str) -> Result<()> {
+        let count = mapping_db::parse(mappings)?.len();
+        self.gamepad_system.add_mappings(mappings)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(count, "added mappings");
+        let time = self.gamepad_system.time();
+        self.log(time, || {
+            LogEntry::Mapping(format!("added {} mappings", count))
+        });
+        Ok(())
+    }
+
+    /// Reloads changed mappings, then reads pending events from the backend.
+    fn poll_backend(&mut self) -> Result<()> {
+        if let Some(watch) = &mut self.mappings_watch {
+            let gamepad_system = &mut self.gamepad_system;
+            let time = gamepad_system.time();
+            let result = watch.poll(time, |mappings| {
+                mapping_db::parse(mappings)?;
+                gamepad_system.load_mappings(mappings)
+            });
+            match result {
+                Some(Ok(())) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!("reloaded mappings");
+                    self.log(time, || LogEntry::Mapping("reloaded mappings file".into()));
+                }
+                // The mappings loaded before stay in place until the file loads.
+                Some(Err(error)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(%error, "failed to reload mappings, keeping the previous ones");
+                    self.log(time, || {
+                        LogEntry::Error(format!("failed to reload mappings: {}", error))
+                    });
+                }
+                None => {}
+            }
+        }
+        self.read_backends()
+    }
+
+    /// Reads pending events from the built-in backend and every custom backend.
+    fn read_backends(&mut self) -> Result<()> {
+        self.gamepad_system.poll(&mut self.events)?;
+        let now = self.gamepad_system.time();
+        for backend in &mut self.custom_backends {
+            backend.poll(now, &mut self.events)?;
+        }
+        Ok(())
+    }
+
+    fn log(&mut self, time: Duration, entry: impl FnOnce() -> LogEntry) {
+        if let Some(log) = &mut self.event_log {
+            log.push(time, entry());
+        }
+    }
+
+    /// Starts logging every event read from the backend, along with mapping decisions and errors.
+    ///
+    /// Entries are kept for `retention`, so the log always covers the most recent events. An
+    /// existing log is kept, with the new retention.
+    pub fn enable_event_log(&mut self, retention: Duration) {
+        match &mut self.event_log {
+            Some(log) => log.set_retention(retention),
+            None => self.event_log = Some(EventLog::new(retention)),
+        }
+    }
+
+    /// Stops logging and discards the log.
+    pub fn disable_event_log(&mut self) {
+        self.event_log = None;
+    }
+
+    /// Gets the event log, if enabled, for example to export it into a bug report.
+    pub fn event_log(&self) -> Option<&EventLog> {
+        self.event_log.as_ref()
+    }
+
+    /// Gets the gamepads connected and disconnected during the most recent update, in order.
+    ///
+    /// A game can use this to pause and ask for a controller when one is pulled mid-game.
+    pub fn events(&self) -> impl Iterator<Item = GamepadEvent> + '_ {
+        self.gamepad_events.iter().copied()
+    }
+
+    /// Takes the changes of input read during the most recent update, in the order they
+    /// happened, for consumers that handle input as events rather than by polling state.
+    ///
+    /// Events are only kept until the next update, and each is yielded once. Presses of a
+    /// reserved capture button, and presses ignored by debouncing, are left out.
+    pub fn poll_events(&mut self) -> impl Iterator<Item = InputEvent> + '_ {
+        self.input_events.drain(..)
+    }
+
+    /// Gets the presses of the capture button during the most recent update.
+    pub fn capture_events(&self) -> &[CaptureEvent] {
+        &self.capture_events
+    }
+
+    /// Sets how presses of the capture button are routed.
+    ///
+    /// The default is [`CapturePolicy::Deliver`].
+    pub fn set_capture_policy(&mut self, policy: CapturePolicy) {
+        self.capture_policy = policy;
+    }
+
+    /// Captures the state of all gamepads for a fixed simulation tick.
+    ///
+    /// The `just_*` queries on the returned snapshot reflect every change since the previous
+    /// tick, regardless of how many times [`update`](Self::update) was called in between.
+    /// This lets a fixed-timestep simulation observe each change exactly once, while
+    /// rendering reads the live state through [`gamepad`](Self::gamepad) as usual.
+    pub fn tick(&mut self) -> &Snapshot {
+        self.tick_front.clone_from(&self.tick_back);
+        self.tick_back.clear();
+        &self.tick_front
+    }
+
+    /// Creates a new input view.
+    ///
+    /// The view starts out with the current state of all gamepads and no recorded changes.
+    pub fn create_view(&mut self) -> ViewId {
+        let id = ViewId(self.next_view_id);
+        self.next_view_id += 1;
+
+        let mut view = Snapshot::default();
+        view.accumulate(&self.gamepads);
+        view.clear();
+        self.views.insert(id, view);
+
+        id
     }
 
-    /// Sets the analog deadzone for all analog inputs.
+    /// Gets the state of all gamepads as seen by a view.
+    ///
+    /// The `just_*` queries reflect every change since the view was last consumed.
+    /// Returns `None` if the view has been removed.
+    pub fn view(&self, id: ViewId) -> Option<&Snapshot> {
+        self.views.get(&id)
+    }
+
+    /// Clears all changes recorded by a view, so that each change is only processed once.
+    pub fn consume_view(&mut self, id: ViewId) {
+        if let Some(view) = self.views.get_mut(&id) {
+            view.clear();
+        }
+    }
+
+    /// Removes a view.
+    pub fn remove_view(&mut self, id: ViewId) {
+        self.views.remove(&id);
+    }
+
+    /// Creates a handle through which other threads can read the state of all gamepads, as of
+    /// the latest update.
+    ///
+    /// The state starts out with the current state of all gamepads and no recorded changes, and
+    /// is updated until every clone of the handle is dropped.
+    pub fn shared_state(&mut self) -> SharedGamepadState {
+        let shared = SharedGamepadState::new(&self.gamepads);
+        self.shared_states.push(shared.downgrade());
+        shared
+    }
+
+    /// Starts recording the inputs of a gamepad into a macro, discarding any recording in
+    /// progress.
+    ///
+    /// Inputs replayed by macros are not recorded.
+    pub fn start_recording(&mut self, id: GamepadId) {
+        let time = self.gamepad_system.time();
+        self.macros.start_recording(id, time);
+    }
+
+    /// Checks whether a macro is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.macros.is_recording()
+    }
+
+    /// Stops recording and returns the recorded macro, if any.
+    ///
+    /// Buttons and analog inputs still held when the recording stops are released at the end of
+    /// the macro.
+    pub fn stop_recording(&mut self) -> Option<Macro> {
+        let time = self.gamepad_system.time();
+        self.macros.stop_recording(time)
+    }
+
+    /// Binds a macro to a chord of buttons.
+    ///
+    /// Whenever every button of the chord is pressed on a gamepad, the macro is replayed on that
+    /// gamepad. A `speed` of 2.0 replays the macro twice as fast as it was recorded.
+    pub fn add_macro(&mut self, chord: &[Button], recording: Macro, speed: f32) -> Result<MacroId> {
+        self.macros.add(chord, recording, speed)
+    }
+
+    /// Removes a macro, stopping any playback of it, and returns it.
+    pub fn remove_macro(&mut self, id: MacroId) -> Option<Macro> {
+        self.macros.remove(id)
+    }
+
+    /// Sets the playback speed of a macro.
+    ///
+    /// Playbacks already in progress are affected as well.
+    pub fn set_macro_speed(&mut self, id: MacroId, speed: f32) -> Result<()> {
+        self.macros.set_speed(id, speed)
+    }
+
+    /// Stops every macro being replayed.
+    ///
+    /// Inputs held by a stopped macro stay held until the gamepad itself changes them.
+    pub fn stop_macros(&mut self) {
+        self.macros.stop_playback();
+    }
+
+    /// Starts recording the input of every gamepad, frame by frame, discarding any recording in
+    /// progress.
+    ///
+    /// Unlike macros, which record a single gamepad, this records everything the context sees,
+    /// including the input of macros and of recordings being played back. See the
+    /// [`recorder`] module.
+    pub fn start_input_recording(&mut self) {
+        let time = self.gamepad_system.time();
+        self.recorder.start_recording(&self.gamepads, time);
+    }
+
+    /// Checks whether the input of every gamepad is being recorded.
+    pub fn is_recording_input(&self) -> bool {
+        self.recorder.is_recording()
+    }
+
+    /// Stops recording the input of every gamepad and returns the recording, if any.
+    pub fn stop_input_recording(&mut self) -> Option<InputRecording> {
+        self.recorder.stop_recording()
+    }
+
+    /// Plays back a recording of the input of every gamepad, replacing any playback in progress.
+    ///
+    /// Each slot of the recording is played onto the gamepad connected in the same position,
+    /// starting on the next update, and the live input of those gamepads is ignored until the
+    /// playback ends. Slots without a connected gamepad are skipped.
+    pub fn play_input_recording(&mut self, recording: InputRecording) {
+        self.recorder.start_playback(&self.gamepads, recording);
+    }
+
+    /// Checks whether a recording is being played back.
+    pub fn is_playing_input(&self) -> bool {
+        self.recorder.is_playing()
+    }
+
+    /// Stops playing back a recording.
+    ///
+    /// Inputs held by the recording stay held until the gamepad itself changes them.
+    pub fn stop_input_playback(&mut self) {
+        self.recorder.stop_playback();
+    }
+
+    /// Starts listening for the next input on any gamepad, such as to assign it to an action in
+    /// a controls menu.
+    ///
+    /// The first button pressed, or axis pushed past `threshold`, after this call is returned by
+    /// [`listened_binding`](Self::listened_binding) once the update that read it has finished.
+    /// Buttons and axes already held when listening starts are ignored, and an axis must move
+    /// `threshold` away from where it rested, so that a drifting stick is not picked up.
+    ///
+    /// `threshold` must be in `(0.0, 1.0]`.
+    pub fn listen_for_binding(&mut self, threshold: f32) -> Result<()> {
+        self.binding_listener = Some(BindingListener::new(&self.gamepads, threshold)?);
+        self.listened_binding = None;
+        Ok(())
+    }
+
+    /// Checks if the context is listening for an input to bind.
+    pub fn is_listening_for_binding(&self) -> bool {
+        self.binding_listener.is_some()
+    }
+
+    /// Stops listening for an input to bind.
+    pub fn stop_listening_for_binding(&mut self) {
+        self.binding_listener = None;
+    }
+
+    /// Takes the input read since [`listen_for_binding`](Self::listen_for_binding), along with
+    /// the gamepad it came from.
+    ///
+    /// Returns `None` until an input is read, and again after the input has been taken.
+    pub fn listened_binding(&mut self) -> Option<(GamepadId, Binding)> {
+        self.listened_binding.take()
+    }
+
+    /// Gets the registry of players.
+    pub fn players(&self) -> &PlayerRegistry {
+        &self.players
+    }
+
+    /// Gets the registry of players mutably, to create players and assign gamepads to them.
+    pub fn players_mut(&mut self) -> &mut PlayerRegistry {
+        &mut self.players
+    }
+
+    /// Sets the debounce interval of all gamepads' buttons, including those of gamepads
+    /// connected later, or disables debouncing if `None`.
+    ///
+    /// See [`set_debounce`](Self::set_debounce).
+    pub fn set_default_debounce(&mut self, debounce: Option<Duration>) {
+        self.default_debounce = debounce;
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.digital_inputs.set_debounce(debounce);
+        }
+    }
+
+    /// Sets how all gamepads' held buttons repeat, including those of gamepads connected later,
+    /// or disables repeating if `None`.
+    ///
+    /// See [`set_repeat`](Self::set_repeat).
+    pub fn set_default_repeat(&mut self, repeat: Option<KeyRepeat>) {
+        self.default_repeat = repeat;
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.digital_inputs.set_repeat(repeat);
+        }
+    }
+
+    /// Sets the debounce interval of a gamepad's buttons, or disables debouncing if `None`.
+    ///
+    /// While debouncing, a button that is activated again within the interval after being
+    /// deactivated is ignored. This filters out the rapid chatter of worn switches.
+    /// Debouncing is disabled by default.
+    pub fn set_debounce(&mut self, id: GamepadId, debounce: Option<Duration>) {
+        if let Some(gamepad) = self.gamepads.get_mut(id) {
+            gamepad.digital_inputs.set_debounce(debounce);
+        }
+    }
+
+    /// Sets how a gamepad's held buttons repeat, or disables repeating if `None`.
+    ///
+    /// Repeats are reported by [`DigitalInput::just_activated_repeat`]. Repeating is disabled by
+    /// default.
+    pub fn set_repeat(&mut self, id: GamepadId, repeat: Option<KeyRepeat>) {
+        if let Some(gamepad) = self.gamepads.get_mut(id) {
+            gamepad.digital_inputs.set_repeat(repeat);
+        }
+    }
+
+    /// Rumbles a gamepad for `duration`, replacing any rumble still playing on it.
+    ///
+    /// `strong` and `weak` are the intensities of the low and high frequency motors, from `0.0`
+    /// to `1.0`, and are scaled by the [`Haptics::rumble_intensity`] of the haptics policy.
+    /// Fails if the gamepad is not connected or has no rumble motors, which can be checked
+    /// beforehand with [`Gamepad::supports_rumble`].
+    pub fn rumble(
+        &mut self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        let gamepad = Self::rumble_gamepad(&mut self.gamepads, id)?;
+        let intensity = self.haptics.rumble_intensity;
+        let (strong, weak) = (strong * intensity, weak * intensity);
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => backend.rumble(device, strong, weak, duration),
+            Route::Native(system) => system.rumble(id, gamepad, strong, weak, duration),
+        }
+    }
+
+    /// Stops any rumble playing on a gamepad.
+    pub fn stop_rumble(&mut self, id: GamepadId) -> Result<()> {
+        let gamepad = Self::rumble_gamepad(&mut self.gamepads, id)?;
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => backend.stop_rumble(device),
+            Route::Native(system) => system.stop_rumble(id, gamepad),
+        }
+    }
+
+    /// Sets the color of the LED of a gamepad, such as to tint each player's controller with
+    /// their color in the game.
+    ///
+    /// Fails if the gamepad is not connected or has no LED, which can be checked beforehand with
+    /// [`Gamepad::has_led`].
+    pub fn set_led(&mut self, id: GamepadId, red: u8, green: u8, blue: u8) -> Result<()> {
+        let gamepad = match self.gamepads.get_mut(id) {
+            Some(gamepad) if gamepad.has_led => gamepad,
+            Some(gamepad) => {
+                return Err(Error::Unsupported(format!(
+                    "gamepad \"{}\" has no LED",
+                    gamepad.name
+                )))
+            }
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => backend.set_led(device, red, green, blue),
+            Route::Native(system) => system.set_led(
+                id,
+                gamepad.owned_internal_gamepad.as_mut(),
+                (red, green, blue),
+            ),
+        }
+    }
+
+    /// Shows a player number on the LEDs of a gamepad, such as the quadrants of the Xbox 360
+    /// ring or the lights of Switch controllers, or clears it if `None`. Players are numbered
+    /// from 0.
+    ///
+    /// Gamepads and backends without player LEDs ignore the index. [`PlayerSlots`] sets it
+    /// automatically as gamepads take their slots.
+    ///
+    /// [`PlayerSlots`]: crate::slots::PlayerSlots
+    pub fn set_player_index(&mut self, id: GamepadId, index: Option<u8>) -> Result<()> {
+        let gamepad = self.gamepads.get_mut(id).ok_or(Error::DeviceNotFound(id))?;
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => backend.set_player_index(device, index)?,
+            Route::Native(system) => {
+                system.set_player_index(id, gamepad.owned_internal_gamepad.as_mut(), index)?
+            }
+        }
+        gamepad.player_index = index;
+        Ok(())
+    }
+
+    /// Sets how long the settings of a disconnected gamepad are kept, so that they are restored
+    /// if it reconnects in that time, such as after a battery swap or a wireless dropout. See
+    /// [`Gamepad::reconnected_from`].
+    ///
+    /// Gamepads are recognized by their GUID, or by their name if the backend could not identify
+    /// them. The default is 10 seconds, and zero disables restoring.
+    pub fn set_reconnect_grace(&mut self, grace: Duration) {
+        self.reconnects.grace = grace;
+    }
+
+    /// Restores the player index and sensors a reconnected gamepad had before it disconnected.
+    fn restore_device_settings(&mut self, id: GamepadId, previous: &Gamepad) {
+        if previous.player_index.is_some() {
+            if let Err(_error) = self.set_player_index(id, previous.player_index) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?id, error = %_error, "failed to restore player index");
+            }
+        }
+        for &sensor in MotionSensor::ALL {
+            if previous.motion.is_enabled(sensor) {
+                if let Err(_error) = self.set_sensor_enabled(id, sensor, true) {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(?id, ?sensor, error = %_error, "failed to restore sensor");
+                }
+            }
+        }
+    }
+
+    /// Sets the force feedback effect of an adaptive trigger, replacing the effect it had.
+    ///
+    /// Fails if the gamepad is not connected or has no adaptive triggers, which can be checked
+    /// beforehand with [`Gamepad::supports_trigger_effects`], so that games can fall back to
+    /// rumble. Effects other than [`TriggerEffect::Off`] are ignored while the haptics policy
+    /// turns off [`trigger_effects`](Haptics::trigger_effects).
+    pub fn set_trigger_effect(
+        &mut self,
+        id: GamepadId,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Result<()> {
+        let gamepad = match self.gamepads.get_mut(id) {
+            Some(gamepad) if gamepad.supports_trigger_effects => gamepad,
+            Some(gamepad) => {
+                return Err(Error::Unsupported(format!(
+                    "gamepad \"{}\" has no adaptive triggers",
+                    gamepad.name
+                )))
+            }
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        if !self.haptics.trigger_effects && effect != TriggerEffect::Off {
+            return Ok(());
+        }
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => backend.set_trigger_effect(device, trigger, effect),
+            Route::Native(system) => system.set_trigger_effect(
+                id,
+                gamepad.owned_internal_gamepad.as_mut(),
+                trigger,
+                effect,
+            ),
+        }
+    }
+
+    /// Turns a motion sensor of a gamepad on or off. Sensors start out off, since they draw
+    /// power.
+    ///
+    /// Fails if the gamepad is not connected or lacks the sensor, which can be checked beforehand
+    /// with [`Gamepad::has_sensor`].
+    pub fn set_sensor_enabled(
+        &mut self,
+        id: GamepadId,
+        sensor: MotionSensor,
+        enabled: bool,
+    ) -> Result<()> {
+        let gamepad = match self.gamepads.get_mut(id) {
+            Some(gamepad) if gamepad.motion.supports(sensor) => gamepad,
+            Some(gamepad) => {
+                return Err(Error::Unsupported(format!(
+                    "gamepad \"{}\" has no {:?}",
+                    gamepad.name, sensor
+                )))
+            }
+            None => return Err(Error::DeviceNotFound(id)),
+        };
+        match CustomBackend::route(&mut self.custom_backends, &mut self.gamepad_system, id) {
+            Route::Custom(backend, device) => {
+                backend.set_sensor_enabled(device, sensor, enabled)?
+            }
+            Route::Native(system) => system.set_sensor_enabled(
+                id,
+                gamepad.owned_internal_gamepad.as_mut(),
+                sensor,
+                enabled,
+            )?,
+        }
+        gamepad.motion.set_enabled(sensor, enabled);
+        Ok(())
+    }
+
+    fn rumble_gamepad(
+        gamepads: &mut GamepadMap<Gamepad>,
+        id: GamepadId,
+    ) -> Result<Option<&mut OwnedImplementationGamepad>> {
+        match gamepads.get_mut(id) {
+            Some(gamepad) if gamepad.supports_rumble => Ok(gamepad.owned_internal_gamepad.as_mut()),
+            Some(gamepad) => Err(Error::Unsupported(format!(
+                "gamepad \"{}\" does not support rumble",
+                gamepad.name
+            ))),
+            None => Err(Error::DeviceNotFound(id)),
+        }
+    }
+
+    /// Calibrates a gamepad, or removes its calibration if `None`.
+    ///
+    /// The calibration is remembered for the gamepad's model, by its [`GUID`](Gamepad::guid) or
+    /// by its name if the backend could not identify it, and applied again whenever a gamepad
+    /// of that model connects. It affects the samples read from then on.
+    pub fn set_calibration(&mut self, id: GamepadId, calibration: Option<GamepadCalibration>) {
+        let gamepad = match self.gamepads.get_mut(id) {
+            Some(gamepad) => gamepad,
+            None => return,
+        };
+
+        for &axis in Axis::ALL {
+            let axis_calibration = calibration.as_ref().and_then(|c| c.axis(axis));
+            gamepad
+                .analog_inputs
+                .set_calibration(axis, axis_calibration);
+        }
+        let key = calibration_key(&gamepad.name, gamepad.guid);
+        match calibration {
+            Some(calibration) => self.calibrations.insert(key, calibration),
+            None => self.calibrations.remove(&key),
+        };
+    }
+
+    /// Calibrates a gamepad on a timer, without a calibration screen of the game's own.
+    ///
+    /// The gamepad's calibration is removed, and its resting positions are measured for
+    /// `step_duration` while the player leaves the sticks and triggers untouched. Their extents
+    /// are then measured for another `step_duration` while the player rotates the sticks and
+    /// presses the triggers fully. The result is applied as with
+    /// [`set_calibration`](Self::set_calibration). A few seconds per step is usually enough.
+    ///
+    /// Restarts any calibration of the gamepad already running.
+    pub fn begin_calibration(&mut self, id: GamepadId, step_duration: Duration) {
+        if !self.gamepads.contains_key(id) {
+            return;
+        }
+        self.cancel_calibration(id);
+        self.set_calibration(id, None);
+        let time = self.gamepad_system.time();
+        let calibration = TimedCalibration::new(id, step_duration, time);
+        self.timed_calibrations.push(calibration);
+    }
+
+    /// Gets the step that a calibration started with
+    /// [`begin_calibration`](Self::begin_calibration) is in, for telling the player what to do,
+    /// or `None` once it has finished.
+    pub fn calibration_step(&self, id: GamepadId) -> Option<CalibrationStep> {
+        self.timed_calibrations
+            .iter()
+            .find(|calibration| calibration.gamepad == id)
+            .map(TimedCalibration::step)
+    }
+
+    /// Stops a calibration started with [`begin_calibration`](Self::begin_calibration), leaving
+    /// the gamepad uncalibrated.
+    pub fn cancel_calibration(&mut self, id: GamepadId) {
+        self.timed_calibrations
+            .retain(|calibration| calibration.gamepad != id);
+    }
+
+    fn update_calibrations(&mut self, time: Duration) {
+        let mut finished = Vec::new();
+        self.timed_calibrations.retain_mut(|calibration| {
+            match self.gamepads.get(calibration.gamepad) {
+                Some(gamepad) => match calibration.update(gamepad, time) {
+                    Some(result) => {
+                        finished.push((calibration.gamepad, result));
+                        false
+                    }
+                    None => true,
+                },
+                None => false,
+            }
+        });
+        for (id, calibration) in finished {
+            self.set_calibration(id, Some(calibration));
+        }
+    }
+
+    /// Gets the stored calibrations, for saving them between sessions.
+    ///
+    /// They are keyed by the [`GUID`](Gamepad::guid) of each gamepad model, written as in SDL
+    /// mappings, or by the gamepad's name if the backend could not identify it.
+    pub fn calibrations(&self) -> &HashMap<String, GamepadCalibration> {
+        &self.calibrations
+    }
+
+    /// Replaces the stored calibrations, such as with those saved in a previous session.
+    ///
+    /// The calibrations are applied to gamepads as they connect.
+    pub fn set_calibrations(&mut self, calibrations: HashMap<String, GamepadCalibration>) {
+        self.calibrations = calibrations;
+    }
+
+    /// Gets the current configuration.
+    pub fn config(&self) -> Config {
+        let analog_inputs = &self.default_analog_inputs;
+        Config {
+            deadzone: analog_inputs.deadzone(),
+            deadzone_rescaling: analog_inputs.deadzone_rescaling(),
+            digital_deadzone: analog_inputs.digital_deadzone(),
+            extrapolation: analog_inputs.extrapolation(),
+            coalescing: analog_inputs.coalescing(),
+            smoothing: analog_inputs.smoothing(),
+            repeat: self.default_repeat,
+            debounce: self.default_debounce,
+            haptics: self.haptics,
+        }
+    }
+
+    /// Validates a configuration and applies it to all gamepads, including those connected later.
+    ///
+    /// Nothing is changed if the configuration is invalid.
+    pub fn apply_config(&mut self, config: &Config) -> Result<()> {
+        config.validate()?;
+
+        self.set_deadzone(config.deadzone);
+        self.set_deadzone_rescaling(config.deadzone_rescaling);
+        self.set_digital_deadzone(config.digital_deadzone);
+        self.set_extrapolation(config.extrapolation);
+        self.set_coalescing(config.coalescing);
+        self.set_smoothing(config.smoothing);
+        self.set_default_repeat(config.repeat);
+        self.set_default_debounce(config.debounce);
+        self.set_haptics(config.haptics);
+        Ok(())
+    }
+
+    /// Gets the policy for rumble and adaptive trigger effects.
+    pub fn haptics(&self) -> Haptics {
+        self.haptics
+    }
+
+    /// Sets the policy for rumble and adaptive trigger effects, which applies to effects started
+    /// from then on.
+    pub fn set_haptics(&mut self, haptics: Haptics) {
+        self.haptics = haptics;
+    }
+
+    /// Sets the analog deadzone for all analog inputs, including those of gamepads connected later.
     ///
     /// Positive values outside the deadzone get remapped to the range `[0.0, ANALOG_MAX]`.\
-    /// Negative values get remapped to `[ANALOG_MIN, 0.0]`.
+    /// Negative values get remapped to `[ANALOG_MIN, 0.0]`.\
+    /// This can be turned off with [`set_deadzone_rescaling`](Self::set_deadzone_rescaling).
+    ///
+    /// Axes with a deadzone of their own, set with [`set_axis_deadzone`](Self::set_axis_deadzone),
+    /// keep it.
     pub fn set_deadzone(&mut self, deadzone: f32) {
+        let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        self.default_analog_inputs.set_deadzone(deadzone);
         for (_, gamepad) in self.gamepads.iter_mut() {
-            let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
             gamepad.analog_inputs.set_deadzone(deadzone);
         }
     }
 
+    /// Sets whether values outside the analog deadzone are rescaled, for all analog inputs
+    /// including those of gamepads connected later.
+    ///
+    /// Rescaling is on by default, so values grow from `0.0` at the edge of the deadzone. With it
+    /// off, values outside the deadzone are left as they are and jump from `0.0` to the size of
+    /// the deadzone, which some games prefer so that the deadzone only cuts off small values.
+    pub fn set_deadzone_rescaling(&mut self, rescale: bool) {
+        self.default_analog_inputs.set_deadzone_rescaling(rescale);
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.analog_inputs.set_deadzone_rescaling(rescale);
+        }
+    }
+
     /// Sets the digital deadzone for all analog inputs.
     pub fn set_digital_deadzone(&mut self, deadzone: f32) {
+        let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        self.default_analog_inputs.set_digital_deadzone(deadzone);
         for (_, gamepad) in self.gamepads.iter_mut() {
-            let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
             gamepad.analog_inputs.set_digital_deadzone(deadzone);
         }
     }
+
+    /// Overrides the analog deadzone of a single axis of a gamepad, or restores the deadzone set
+    /// with [`set_deadzone`](Self::set_deadzone) if `None`.
+    ///
+    /// If the axis enters or leaves the deadzone because of the change, that counts as the axis
+    /// being just activated or deactivated.
+    pub fn set_axis_deadzone(&mut self, id: GamepadId, axis: Axis, deadzone: Option<f32>) {
+        if let Some(gamepad) = self.gamepads.get_mut(id) {
+            let deadzone =
+                deadzone.map(|deadzone| Deadzone::from(AnalogInputValue::from(deadzone)));
+            gamepad.analog_inputs.set_input_deadzone(axis, deadzone);
+        }
+    }
+
+    /// Overrides the digital deadzone of a single axis of a gamepad, or restores the deadzone set
+    /// with [`set_digital_deadzone`](Self::set_digital_deadzone) if `None`.
+    ///
+    /// If the axis enters or leaves the deadzone because of the change, that counts as the axis
+    /// being just activated or deactivated.
+    pub fn set_axis_digital_deadzone(&mut self, id: GamepadId, axis: Axis, deadzone: Option<f32>) {
+        if let Some(gamepad) = self.gamepads.get_mut(id) {
+            let deadzone =
+                deadzone.map(|deadzone| Deadzone::from(AnalogInputValue::from(deadzone)));
+            gamepad
+                .analog_inputs
+                .set_input_digital_deadzone(axis, deadzone);
+        }
+    }
+
+    /// Sets the response curve of a single axis of a gamepad, applied after the analog deadzone.
+    ///
+    /// Every axis has a [`ResponseCurve::Linear`] curve by default.
+    pub fn set_response_curve(&mut self, id: GamepadId, axis: Axis, curve: ResponseCurve) {
+        if let Some(gamepad) = self.gamepads.get_mut(id) {
+            gamepad.analog_inputs.set_response_curve(axis, curve);
+        }
+    }
+
+    /// Sets the analog and digital deadzones of the triggers of every gamepad, including those
+    /// connected later, apart from those of the sticks.
+    ///
+    /// This overrides the deadzones of the trigger axes, as with
+    /// [`set_axis_deadzone`](Self::set_axis_deadzone) and
+    /// [`set_axis_digital_deadzone`](Self::set_axis_digital_deadzone), so a trigger counts as
+    /// pressed once it moves past `digital_deadzone`.
+    pub fn set_trigger_deadzones(&mut self, deadzone: f32, digital_deadzone: f32) {
+        let deadzone = Deadzone::from(AnalogInputValue::from(deadzone));
+        let digital_deadzone = Deadzone::from(AnalogInputValue::from(digital_deadzone));
+        let gamepads = self
+            .gamepads
+            .iter_mut()
+            .map(|(_, gamepad)| &mut gamepad.analog_inputs);
+        for analog_inputs in gamepads.chain([&mut self.default_analog_inputs]) {
+            for axis in Trigger::ALL.into_iter().filter_map(Trigger::axis) {
+                analog_inputs.set_input_deadzone(axis, Some(deadzone));
+                analog_inputs.set_input_digital_deadzone(axis, Some(digital_deadzone));
+            }
+        }
+    }
+
+    /// Enables extrapolation of analog inputs up to the given horizon, or disables it if `None`.
+    ///
+    /// The horizon is capped at [`MAX_EXTRAPOLATION`](analog::MAX_EXTRAPOLATION).
+    /// Extrapolated values are read with [`AnalogInput::predicted_value`].
+    /// Extrapolation is disabled by default.
+    pub fn set_extrapolation(&mut self, horizon: Option<Duration>) {
+        self.default_analog_inputs.set_extrapolation(horizon);
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.analog_inputs.set_extrapolation(horizon);
+        }
+    }
+
+    /// Sets how consecutive motion events for the same analog input are handled within an update.
+    ///
+    /// Coalescing is disabled by default.
+    pub fn set_coalescing(&mut self, coalescing: Coalescing) {
+        self.default_analog_inputs.set_coalescing(coalescing);
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.analog_inputs.set_coalescing(coalescing);
+        }
+    }
+
+    /// Sets how analog inputs are smoothed against noise, for all gamepads including those
+    /// connected later.
+    ///
+    /// Smoothing is applied after calibration and before the deadzone. It is disabled by
+    /// default.
+    pub fn set_smoothing(&mut self, smoothing: Smoothing) {
+        self.default_analog_inputs.set_smoothing(smoothing);
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.analog_inputs.set_smoothing(smoothing);
+        }
+    }
+
+    /// Drives an axis of every gamepad from a pair of its buttons, replacing any button axis
+    /// already driving it, or stops driving it if `None`. See [`button_axis`].
+    ///
+    /// The axis takes the value of the buttons whenever that value changes, so on a gamepad that
+    /// also has the axis, it reads whichever of the two moved last.
+    pub fn set_button_axis(&mut self, axis: Axis, button_axis: Option<ButtonAxis>) {
+        self.button_axes.retain(|(driven, _)| *driven != axis);
+        if let Some(button_axis) = button_axis {
+            self.button_axes.push((axis, button_axis));
+        }
+        for (_, gamepad) in self.gamepads.iter_mut() {
+            gamepad.button_axes.reset();
+        }
+    }
+
+    /// Gets the axes driven by pairs of buttons, set with
+    /// [`set_button_axis`](Self::set_button_axis).
+    pub fn button_axes(&self) -> &[(Axis, ButtonAxis)] {
+        &self.button_axes
+    }
+}
+
+/// Gets the key a calibration is stored by: the GUID of the gamepad's model, or its name if the
+/// backend could not identify it.
+#[cfg(feature = "std")]
+fn calibration_key(name: &str, guid: Guid) -> String {
+    if guid.is_zero() {
+        name.to_owned()
+    } else {
+        guid.to_string()
+    }
 }
@@ -0,0 +1,179 @@
+//! A rolling log of recent input events, for attaching to bug reports.
+//!
+//! The log is disabled by default, and enabled with [`GamepadContext::enable_event_log`] or
+//! [`GamepadContextBuilder::event_log`]. It keeps every event read from the backend, the
+//! decisions made about each gamepad and any errors for a fixed period, and can be exported as
+//! plain text.
+//!
+//! [`GamepadContext::enable_event_log`]: crate::GamepadContext::enable_event_log
+//! [`GamepadContextBuilder::event_log`]: crate::builder::GamepadContextBuilder::event_log
+
+use crate::backend::Event;
+use crate::error::{Error, Result};
+use crate::{Axis, Button, GamepadId};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::time::Duration;
+
+/// A single entry of an [`EventLog`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogEntry {
+    /// A gamepad was connected.
+    Connected {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The name reported by the backend.
+        name: String,
+    },
+    /// A gamepad was disconnected.
+    Disconnected(GamepadId),
+    /// A button was pressed or released.
+    Button {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The button.
+        button: Button,
+        /// Whether the button was pressed, rather than released.
+        pressed: bool,
+    },
+    /// An axis moved, before calibration and deadzones.
+    Axis {
+        /// The gamepad.
+        gamepad: GamepadId,
+        /// The axis.
+        axis: Axis,
+        /// The raw value.
+        value: f32,
+    },
+    /// A decision made about how inputs are mapped, such as applying a stored calibration.
+    Mapping(String),
+    /// An error while reading input.
+    Error(String),
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogEntry::Connected { gamepad, name } => {
+                write!(f, "{:?} connected: {:?}", gamepad, name)
+            }
+            LogEntry::Disconnected(gamepad) => write!(f, "{:?} disconnected", gamepad),
+            LogEntry::Button {
+                gamepad,
+                button,
+                pressed,
+            } => {
+                let action = if *pressed { "pressed" } else { "released" };
+                write!(f, "{:?} {:?} {}", gamepad, button, action)
+            }
+            LogEntry::Axis {
+                gamepad,
+                axis,
+                value,
+            } => write!(f, "{:?} {:?} = {:.4}", gamepad, axis, value),
+            LogEntry::Mapping(message) => write!(f, "mapping: {}", message),
+            LogEntry::Error(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// A log of the entries of the last few seconds.
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    entries: VecDeque<(Duration, LogEntry)>,
+    retention: Duration,
+}
+
+impl EventLog {
+    /// Creates an empty log, which keeps entries for `retention`.
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            retention,
+        }
+    }
+
+    /// Gets how long entries are kept.
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Iterates over the entries, oldest first, along with the time of each.
+    pub fn entries(&self) -> impl Iterator<Item = &(Duration, LogEntry)> {
+        self.entries.iter()
+    }
+
+    /// Removes every entry.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Formats the log as text, with one entry per line.
+    pub fn export(&self) -> String {
+        let mut text = String::new();
+        for (time, entry) in &self.entries {
+            // Writing to a string never fails.
+            let _ = writeln!(text, "{:>10.3}s  {}", time.as_secs_f64(), entry);
+        }
+        text
+    }
+
+    /// Writes the log to a file, as formatted by [`export`](Self::export).
+    pub fn export_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.export()).map_err(|error| {
+            Error::Io(format!(
+                "failed to write event log to {}: {}",
+                path.display(),
+                error
+            ))
+        })
+    }
+
+    pub(crate) fn set_retention(&mut self, retention: Duration) {
+        self.retention = retention;
+    }
+
+    /// Adds an entry, dropping those that have expired by `time`.
+    pub(crate) fn push(&mut self, time: Duration, entry: LogEntry) {
+        let cutoff = time.saturating_sub(self.retention);
+        while self
+            .entries
+            .front()
+            .is_some_and(|&(entry_time, _)| entry_time < cutoff)
+        {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((time, entry));
+    }
+
+    /// Adds the events read from the backend.
+    pub(crate) fn record(&mut self, events: &[(Duration, Event)]) {
+        for (time, event) in events {
+            let entry = match *event {
+                Event::Connected { id, ref name, .. } => LogEntry::Connected {
+                    gamepad: id,
+                    name: name.clone(),
+                },
+                Event::Disconnected(id) => LogEntry::Disconnected(id),
+                Event::AxisMotion(id, axis, value) => LogEntry::Axis {
+                    gamepad: id,
+                    axis,
+                    value: value.get(),
+                },
+                Event::ButtonDown(id, button) => LogEntry::Button {
+                    gamepad: id,
+                    button,
+                    pressed: true,
+                },
+                Event::ButtonUp(id, button) => LogEntry::Button {
+                    gamepad: id,
+                    button,
+                    pressed: false,
+                },
+            };
+            self.push(*time, entry);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Integration with the [macroquad](https://macroquad.rs) game engine.
+//!
+//! [`Gamepads`] replaces the usual `next_frame().await` at the end of the game loop, and keeps
+//! the gamepad context updated once per frame. See [`FrameGamepads`] for how platforms without
+//! gamepad support are handled.
+//!
+//! Small games can skip owning a [`Gamepads`] and use the one [`gamepads`] creates on first use
+//! instead, updated by this module's [`next_frame`]:
+//!
+//! ```ignore
+//! use fishsticks::macroquad::{gamepads, next_frame};
+//!
+//! loop {
+//!     for (_id, gamepad) in gamepads().gamepads() {
+//!         // Read inputs.
+//!     }
+//!     next_frame().await;
+//! }
+//! ```
+//!
+//! For web builds, enable the `wasm-backend` feature to read gamepads through the browser.
+
+use crate::frame::FrameGamepads;
+use macroquad::logging::warn;
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+
+thread_local! {
+    // Leaked so that borrows of it can outlive the calls that make them. Macroquad runs the game
+    // on a single thread, so there is only ever one.
+    static GAMEPADS: &'static RefCell<Gamepads> = Box::leak(Box::default());
+}
+
+/// Per-frame driver for a [`GamepadContext`](crate::GamepadContext).
+pub struct Gamepads(FrameGamepads);
+
+impl Gamepads {
+    /// Initializes the gamepad context.
+    pub fn new() -> Self {
+        let mut gamepads = Self(FrameGamepads::new(|error| {
+            warn!("Gamepad input is unavailable: {}", error)
+        }));
+        gamepads.update();
+        gamepads
+    }
+
+    /// Waits for the next frame, then updates the state of all gamepads.
+    ///
+    /// Use this in place of [`macroquad::window::next_frame`].
+    pub async fn next_frame(&mut self) {
+        macroquad::window::next_frame().await;
+        self.update();
+    }
+
+    fn update(&mut self) {
+        self.0
+            .update(|error| warn!("Failed to update gamepads: {}", error));
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Gamepads {
+    type Target = FrameGamepads;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Gamepads {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Gets the gamepads of the game, initializing the gamepad context on first use.
+///
+/// # Panics
+///
+/// Panics if the gamepads are borrowed mutably, such as by a [`gamepads_mut`] still held.
+/// Borrows must not be held across [`next_frame`], which updates the gamepads.
+pub fn gamepads() -> Ref<'static, Gamepads> {
+    GAMEPADS.with(|gamepads| gamepads.borrow())
+}
+
+/// Gets the gamepads of the game mutably, such as to change settings of the context.
+///
+/// # Panics
+///
+/// Panics if the gamepads are already borrowed. See [`gamepads`].
+pub fn gamepads_mut() -> RefMut<'static, Gamepads> {
+    GAMEPADS.with(|gamepads| gamepads.borrow_mut())
+}
+
+/// Waits for the next frame, then updates the gamepads returned by [`gamepads`].
+///
+/// Use this in place of [`macroquad::window::next_frame`].
+pub async fn next_frame() {
+    macroquad::window::next_frame().await;
+    gamepads_mut().update();
+}
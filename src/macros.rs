@@ -0,0 +1,321 @@
+//! Recording of input sequences, replayed when a chord of buttons is pressed.
+//!
+//! A macro is recorded from a single gamepad with [`GamepadContext::start_recording`] and
+//! [`GamepadContext::stop_recording`], then bound to a chord with
+//! [`GamepadContext::add_macro`]. Pressing the chord on any gamepad replays the macro on that
+//! gamepad, as if its inputs were performed again.
+//!
+//! [`GamepadContext::start_recording`]: crate::GamepadContext::start_recording
+//! [`GamepadContext::stop_recording`]: crate::GamepadContext::stop_recording
+//! [`GamepadContext::add_macro`]: crate::GamepadContext::add_macro
+
+use crate::analog::AnalogInputValue;
+use crate::arena::GamepadMap;
+use crate::backend::Event;
+use crate::error::{Error, Result};
+use crate::input::{Input, InputSet};
+use crate::{Axis, Button, Gamepad, GamepadId};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A single input performed during a macro.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MacroInput {
+    /// A button was pressed or released.
+    Button {
+        /// The button.
+        button: Button,
+        /// Whether the button was pressed, rather than released.
+        pressed: bool,
+    },
+    /// An analog input moved to a new value.
+    Axis {
+        /// The analog input.
+        axis: Axis,
+        /// The raw value the input moved to, before any deadzone.
+        value: f32,
+    },
+}
+
+/// A recorded sequence of inputs, each at an offset from the start of the macro.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Macro {
+    inputs: Vec<(Duration, MacroInput)>,
+}
+
+impl Macro {
+    /// Creates an empty macro.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an input, performed `offset` after the start of the macro.
+    ///
+    /// Inputs must be appended in order of their offsets.
+    pub fn push(&mut self, offset: Duration, input: MacroInput) {
+        debug_assert!(self.duration() <= offset, "macro inputs out of order");
+        self.inputs.push((offset, input));
+    }
+
+    /// Gets the inputs of the macro, in order.
+    pub fn inputs(&self) -> &[(Duration, MacroInput)] {
+        &self.inputs
+    }
+
+    /// Gets the offset of the last input of the macro.
+    pub fn duration(&self) -> Duration {
+        self.inputs
+            .last()
+            .map_or(Duration::ZERO, |&(offset, _)| offset)
+    }
+}
+
+/// Identifies a macro bound with [`GamepadContext::add_macro`].
+///
+/// [`GamepadContext::add_macro`]: crate::GamepadContext::add_macro
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MacroId(usize);
+
+struct Binding {
+    chord: InputSet<Button>,
+    recording: Macro,
+    speed: f32,
+}
+
+struct Recorder {
+    gamepad: GamepadId,
+    start: Duration,
+    recording: Macro,
+    pressed: InputSet<Button>,
+    moved: InputSet<Axis>,
+}
+
+struct Playback {
+    gamepad: GamepadId,
+    binding: MacroId,
+    start: Duration,
+    next: usize,
+}
+
+/// The macros of a [`GamepadContext`](crate::GamepadContext), with the recording and playbacks
+/// in progress.
+#[derive(Default)]
+pub(crate) struct Macros {
+    bindings: HashMap<MacroId, Binding>,
+    next_binding: usize,
+    recorder: Option<Recorder>,
+    playbacks: Vec<Playback>,
+}
+
+impl Macros {
+    pub(crate) fn add(
+        &mut self,
+        chord: &[Button],
+        recording: Macro,
+        speed: f32,
+    ) -> Result<MacroId> {
+        validate_speed(speed)?;
+        if chord.is_empty() {
+            return Err(Error::Invalid(
+                "macro chord must contain at least one button".into(),
+            ));
+        }
+
+        let mut chord_set = InputSet::default();
+        for &button in chord {
+            chord_set.insert(button);
+        }
+
+        let id = MacroId(self.next_binding);
+        self.next_binding += 1;
+        self.bindings.insert(
+            id,
+            Binding {
+                chord: chord_set,
+                recording,
+                speed,
+            },
+        );
+
+        Ok(id)
+    }
+
+    pub(crate) fn remove(&mut self, id: MacroId) -> Option<Macro> {
+        self.playbacks.retain(|playback| playback.binding != id);
+        self.bindings.remove(&id).map(|binding| binding.recording)
+    }
+
+    pub(crate) fn set_speed(&mut self, id: MacroId, speed: f32) -> Result<()> {
+        validate_speed(speed)?;
+        let binding = self
+            .bindings
+            .get_mut(&id)
+            .ok_or_else(|| Error::Invalid(format!("no macro with id {:?}", id)))?;
+        binding.speed = speed;
+        Ok(())
+    }
+
+    pub(crate) fn stop_playback(&mut self) {
+        self.playbacks.clear();
+    }
+
+    pub(crate) fn start_recording(&mut self, gamepad: GamepadId, time: Duration) {
+        self.recorder = Some(Recorder {
+            gamepad,
+            start: time,
+            recording: Macro::new(),
+            pressed: InputSet::default(),
+            moved: InputSet::default(),
+        });
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Ends the recording, releasing every button and analog input left held at `time`.
+    pub(crate) fn stop_recording(&mut self, time: Duration) -> Option<Macro> {
+        let mut recorder = self.recorder.take()?;
+        let offset = time
+            .saturating_sub(recorder.start)
+            .max(recorder.recording.duration());
+
+        for &button in Button::ALL {
+            if recorder.pressed.contains(button) {
+                let input = MacroInput::Button {
+                    button,
+                    pressed: false,
+                };
+                recorder.recording.push(offset, input);
+            }
+        }
+        for &axis in Axis::ALL {
+            if recorder.moved.contains(axis) {
+                let input = MacroInput::Axis { axis, value: 0.0 };
+                recorder.recording.push(offset, input);
+            }
+        }
+
+        Some(recorder.recording)
+    }
+
+    /// Records the events read from the backend for the gamepad being recorded.
+    pub(crate) fn record(&mut self, events: &[(Duration, Event)]) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+
+        for (time, event) in events {
+            let input = match *event {
+                Event::AxisMotion(id, axis, value) if id == recorder.gamepad => {
+                    let value = value.get();
+                    if value == 0.0 {
+                        recorder.moved.remove(axis);
+                    } else {
+                        recorder.moved.insert(axis);
+                    }
+                    MacroInput::Axis { axis, value }
+                }
+                Event::ButtonDown(id, button) if id == recorder.gamepad => {
+                    recorder.pressed.insert(button);
+                    MacroInput::Button {
+                        button,
+                        pressed: true,
+                    }
+                }
+                Event::ButtonUp(id, button) if id == recorder.gamepad => {
+                    recorder.pressed.remove(button);
+                    MacroInput::Button {
+                        button,
+                        pressed: false,
+                    }
+                }
+                _ => continue,
+            };
+
+            // Events may be read slightly out of order; keep the offsets monotonic.
+            let offset = time
+                .saturating_sub(recorder.start)
+                .max(recorder.recording.duration());
+            recorder.recording.push(offset, input);
+        }
+    }
+
+    /// Queues the inputs of every playback that are due at `time` as backend events.
+    pub(crate) fn play(&mut self, time: Duration, events: &mut Vec<(Duration, Event)>) {
+        let bindings = &self.bindings;
+        self.playbacks.retain_mut(|playback| {
+            let Some(binding) = bindings.get(&playback.binding) else {
+                return false;
+            };
+            let inputs = binding.recording.inputs();
+
+            while let Some(&(offset, input)) = inputs.get(playback.next) {
+                let input_time = playback.start + offset.div_f32(binding.speed);
+                if input_time > time {
+                    return true;
+                }
+
+                let gamepad = playback.gamepad;
+                let event = match input {
+                    MacroInput::Button {
+                        button,
+                        pressed: true,
+                    } => Event::ButtonDown(gamepad, button),
+                    MacroInput::Button {
+                        button,
+                        pressed: false,
+                    } => Event::ButtonUp(gamepad, button),
+                    MacroInput::Axis { axis, value } => {
+                        Event::AxisMotion(gamepad, axis, AnalogInputValue::from(value))
+                    }
+                };
+                events.push((input_time, event));
+                playback.next += 1;
+            }
+
+            false
+        });
+    }
+
+    /// Starts playing every macro whose chord was completed during the latest update.
+    pub(crate) fn trigger(&mut self, gamepads: &GamepadMap<Gamepad>, time: Duration) {
+        for (&binding_id, binding) in &self.bindings {
+            for (gamepad_id, gamepad) in gamepads {
+                let digital_inputs = &gamepad.digital_inputs;
+                let completed = Button::ALL
+                    .iter()
+                    .filter(|&&button| binding.chord.contains(button))
+                    .all(|&button| digital_inputs.activated(button))
+                    && Button::ALL.iter().any(|&button| {
+                        binding.chord.contains(button) && digital_inputs.just_activated(button)
+                    });
+                let playing = self.playbacks.iter().any(|playback| {
+                    playback.gamepad == gamepad_id && playback.binding == binding_id
+                });
+
+                if completed && !playing {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(?gamepad_id, ?binding_id, "playing macro");
+                    self.playbacks.push(Playback {
+                        gamepad: gamepad_id,
+                        binding: binding_id,
+                        start: time,
+                        next: 0,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn validate_speed(speed: f32) -> Result<()> {
+    if speed.is_finite() && speed > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::Invalid(format!(
+            "macro speed must be positive, got {}",
+            speed
+        )))
+    }
+}
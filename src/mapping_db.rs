@@ -0,0 +1,282 @@
+//! Parsing of SDL gamepad mappings, in the format of `gamecontrollerdb.txt`.
+//!
+//! SDL2 and gilrs read mappings themselves; these are parsed to check them before they reach
+//! either, and to apply them on backends without mapping support of their own, such as browsers.
+//! Both are also given the [`DEFAULT_MAPPINGS`] before any others.
+
+// Only the browser backend applies mappings itself.
+#![cfg_attr(
+    not(all(feature = "wasm-backend", not(feature = "mock-backend"))),
+    allow(dead_code)
+)]
+
+use crate::error::{Error, Result};
+use crate::guid::Guid;
+use crate::Button;
+
+/// Mappings loaded before any others, for devices that the built-in databases of the backends
+/// lack or map poorly.
+///
+/// The Steam Deck's built-in controls, as the Linux kernel reports them without Steam running,
+/// map the back grip buttons to the paddles and the quick access button to
+/// [`Button::Misc`]. Its trackpads are read as touchpads rather than mapped.
+#[cfg_attr(
+    not(any(
+        all(feature = "sdl2-backend", not(target_arch = "wasm32")),
+        feature = "gilrs-backend"
+    )),
+    allow(dead_code)
+)]
+pub(crate) const DEFAULT_MAPPINGS: &str = "\
+03000000de2800000512000011010000,Steam Deck,a:b3,b:b4,back:b11,dpdown:b17,dpleft:b18,\
+dpright:b19,dpup:b16,guide:b13,leftshoulder:b7,leftstick:b14,lefttrigger:a9,leftx:a0,lefty:a1,\
+misc1:b2,paddle1:b21,paddle2:b20,paddle3:b23,paddle4:b22,rightshoulder:b8,rightstick:b15,\
+righttrigger:a8,rightx:a2,righty:a3,start:b12,x:b5,y:b6,platform:Linux,
+";
+
+/// A physical input of a gamepad, by the raw index the device reports it at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Source {
+    Button(u32),
+    Axis {
+        index: u32,
+        // Only the positive or negative half of the axis, if set.
+        half: Option<bool>,
+        inverted: bool,
+    },
+    Hat {
+        index: u32,
+        mask: u8,
+    },
+}
+
+impl Source {
+    fn parse(text: &str) -> Option<Self> {
+        let (half, text) = match text.as_bytes().first()? {
+            b'+' => (Some(true), &text[1..]),
+            b'-' => (Some(false), &text[1..]),
+            _ => (None, text),
+        };
+        let (inverted, text) = match text.strip_suffix('~') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+
+        let (kind, rest) = text.split_at_checked(1)?;
+        match kind {
+            "b" if half.is_none() => Some(Source::Button(rest.parse().ok()?)),
+            "a" => Some(Source::Axis {
+                index: rest.parse().ok()?,
+                half,
+                inverted,
+            }),
+            "h" if half.is_none() => {
+                let (index, mask) = rest.split_once('.')?;
+                Some(Source::Hat {
+                    index: index.parse().ok()?,
+                    mask: mask.parse().ok()?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the input from the raw buttons and axes of a device, as a value from `0.0` to
+    /// `1.0` for buttons and half axes, or from `-1.0` to `1.0` for full axes.
+    ///
+    /// Hats read as released, as backends without mapping support do not report them.
+    pub(crate) fn read(&self, button: impl Fn(u32) -> f32, axis: impl Fn(u32) -> f32) -> f32 {
+        match *self {
+            Source::Button(index) => button(index),
+            Source::Axis {
+                index,
+                half,
+                inverted,
+            } => {
+                let value = if inverted { -axis(index) } else { axis(index) };
+                match half {
+                    Some(true) => value.max(0.0),
+                    Some(false) => (-value).max(0.0),
+                    None => value,
+                }
+            }
+            Source::Hat { .. } => 0.0,
+        }
+    }
+
+    /// Checks if the input reads from `-1.0` to `1.0`.
+    pub(crate) fn is_full_axis(&self) -> bool {
+        matches!(self, Source::Axis { half: None, .. })
+    }
+}
+
+/// An axis of the SDL layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MappedAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// An input of the SDL layout that a [`Source`] is mapped to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Target {
+    Button(Button),
+    Axis {
+        axis: MappedAxis,
+        // Only the positive or negative half of the axis, if set.
+        half: Option<bool>,
+    },
+}
+
+impl Target {
+    fn parse(text: &str) -> Option<Self> {
+        let (half, name) = match text.as_bytes().first()? {
+            b'+' => (Some(true), &text[1..]),
+            b'-' => (Some(false), &text[1..]),
+            _ => (None, text),
+        };
+
+        let axis = match name {
+            "leftx" => Some(MappedAxis::LeftX),
+            "lefty" => Some(MappedAxis::LeftY),
+            "rightx" => Some(MappedAxis::RightX),
+            "righty" => Some(MappedAxis::RightY),
+            "lefttrigger" => Some(MappedAxis::LeftTrigger),
+            "righttrigger" => Some(MappedAxis::RightTrigger),
+            _ => None,
+        };
+        if let Some(axis) = axis {
+            return Some(Target::Axis { axis, half });
+        }
+
+        let button = match name {
+            "a" => Button::South,
+            "b" => Button::East,
+            "x" => Button::West,
+            "y" => Button::North,
+            "back" => Button::Select,
+            "guide" => Button::Guide,
+            "start" => Button::Start,
+            "leftstick" => Button::LeftStick,
+            "rightstick" => Button::RightStick,
+            "leftshoulder" => Button::LeftShoulder,
+            "rightshoulder" => Button::RightShoulder,
+            "dpup" => Button::DPadUp,
+            "dpdown" => Button::DPadDown,
+            "dpleft" => Button::DPadLeft,
+            "dpright" => Button::DPadRight,
+            "misc1" => Button::Misc,
+            "paddle1" => Button::Paddle1,
+            "paddle2" => Button::Paddle2,
+            "paddle3" => Button::Paddle3,
+            "paddle4" => Button::Paddle4,
+            "touchpad" => Button::Touchpad,
+            _ => return None,
+        };
+        half.is_none().then_some(Target::Button(button))
+    }
+}
+
+/// The mapping of one model of gamepad onto the SDL layout.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Mapping {
+    pub(crate) guid: Guid,
+    pub(crate) name: String,
+    pub(crate) platform: Option<String>,
+    pub(crate) bindings: Vec<(Target, Source)>,
+}
+
+impl Mapping {
+    /// Parses a single mapping, such as
+    /// `030000005e0400008e02000014010000,Xbox 360 Controller,a:b0,b:b1,leftx:a0,platform:Linux,`.
+    fn parse(line: &str) -> Result<Self> {
+        let mut fields = line.split(',');
+        let guid = fields.next().unwrap_or_default().trim().parse()?;
+        let name = fields
+            .next()
+            .ok_or_else(|| Error::Invalid(format!("mapping for {} has no name", guid)))?
+            .to_owned();
+
+        let mut mapping = Self {
+            guid,
+            name,
+            platform: None,
+            bindings: Vec::new(),
+        };
+        for field in fields.map(str::trim).filter(|field| !field.is_empty()) {
+            let invalid =
+                || Error::Invalid(format!("invalid field {:?} in mapping for {}", field, guid));
+            let (key, value) = field.split_once(':').ok_or_else(invalid)?;
+            if key == "platform" {
+                mapping.platform = Some(value.to_owned());
+                continue;
+            }
+            // Unknown keys, such as the `crc` and `hint` of newer databases, are skipped.
+            let Some(target) = Target::parse(key) else {
+                continue;
+            };
+            let source = Source::parse(value).ok_or_else(invalid)?;
+            mapping.bindings.push((target, source));
+        }
+        Ok(mapping)
+    }
+}
+
+/// Parses every mapping of a database, skipping blank lines and `#` comments.
+///
+/// The mapping SDL applies to XInput devices on Windows, which is named `xinput` instead of
+/// having a GUID, is skipped too, since no other backend reports such devices.
+pub(crate) fn parse(mappings: &str) -> Result<Vec<Mapping>> {
+    mappings
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number, line.trim()))
+        .filter(|(_, line)| {
+            !line.is_empty() && !line.starts_with('#') && !line.starts_with("xinput,")
+        })
+        .map(|(number, line)| {
+            Mapping::parse(line)
+                .map_err(|error| Error::Invalid(format!("line {}: {}", number + 1, error)))
+        })
+        .collect()
+}
+
+/// Mappings added at runtime, looked up by GUID.
+#[derive(Debug, Default)]
+pub(crate) struct MappingDatabase {
+    mappings: Vec<Mapping>,
+}
+
+impl MappingDatabase {
+    /// Adds mappings, which take precedence over any added earlier for the same GUID.
+    pub(crate) fn add(&mut self, mappings: Vec<Mapping>) {
+        for mapping in mappings {
+            self.mappings
+                .retain(|other| other.guid != mapping.guid || other.platform != mapping.platform);
+            self.mappings.push(mapping);
+        }
+    }
+
+    /// Finds the mapping for a GUID, preferring one for the given platform, as named in SDL
+    /// mappings.
+    pub(crate) fn find(&self, guid: Guid, platform: Option<&str>) -> Option<&Mapping> {
+        let mut fallback = None;
+        for mapping in self
+            .mappings
+            .iter()
+            .rev()
+            .filter(|mapping| mapping.guid == guid)
+        {
+            match (&mapping.platform, platform) {
+                (Some(own), Some(platform)) if own == platform => return Some(mapping),
+                (None, _) | (_, None) => fallback = fallback.or(Some(mapping)),
+                _ => {}
+            }
+        }
+        fallback
+    }
+}
@@ -0,0 +1,79 @@
+//! Reloading of a mapping database when it changes on disk.
+
+use crate::error::{Error, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How often the mapping database is checked for changes.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches a mapping database file for changes.
+///
+/// Changes are found by comparing the contents of the file rather than its modification time,
+/// which some filesystems only keep to the second or coarser.
+pub(crate) struct MappingsWatch {
+    path: PathBuf,
+    // A hash of the contents last loaded.
+    loaded: u64,
+    last_check: Duration,
+}
+
+impl MappingsWatch {
+    /// Watches the file at `path`, whose `contents` are loaded, starting at `now` on the clock of
+    /// the context.
+    pub(crate) fn new(path: PathBuf, contents: &str, now: Duration) -> Self {
+        Self {
+            path,
+            loaded: hash(contents),
+            last_check: now,
+        }
+    }
+
+    /// Reads the file again if it differs from the contents last loaded, and passes it to `load`.
+    ///
+    /// The file is checked at most once per [`CHECK_INTERVAL`] of `now`, which is the time on
+    /// the clock of the context. Returns `None` if the file is unchanged or missing. A file that
+    /// fails to read or load is tried again on the next check, even if it has not changed since,
+    /// so that a half-written file is picked up once complete.
+    pub(crate) fn poll(
+        &mut self,
+        now: Duration,
+        load: impl FnOnce(&str) -> Result<()>,
+    ) -> Option<Result<()>> {
+        if now.saturating_sub(self.last_check) < CHECK_INTERVAL {
+            return None;
+        }
+        self.last_check = now;
+
+        let mappings = match std::fs::read_to_string(&self.path) {
+            Ok(mappings) => mappings,
+            // Editors may remove the file for a moment while saving it.
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                return Some(Err(Error::Io(format!(
+                    "failed to read mappings from {}: {}",
+                    self.path.display(),
+                    error
+                ))))
+            }
+        };
+        let contents = hash(&mappings);
+        if contents == self.loaded {
+            return None;
+        }
+
+        let result = load(&mappings);
+        if result.is_ok() {
+            self.loaded = contents;
+        }
+        Some(result)
+    }
+}
+
+fn hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
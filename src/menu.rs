@@ -0,0 +1,557 @@
+//! Helpers for menus operated with a gamepad.
+
+use crate::{Axis, Button, Gamepad};
+use std::time::Duration;
+
+/// The buttons that operate menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuButtons {
+    /// Accepts the selected item, such as the bottom face button.
+    pub confirm: Button,
+    /// Goes back, such as the right face button.
+    pub cancel: Button,
+    /// Moves the selection up.
+    pub up: Button,
+    /// Moves the selection down.
+    pub down: Button,
+    /// Moves the selection left.
+    pub left: Button,
+    /// Moves the selection right.
+    pub right: Button,
+}
+
+impl Default for MenuButtons {
+    /// Uses the d-pad to move, and the bottom and right face buttons to confirm and cancel.
+    fn default() -> Self {
+        Self {
+            confirm: Button::South,
+            cancel: Button::East,
+            up: Button::DPadUp,
+            down: Button::DPadDown,
+            left: Button::DPadLeft,
+            right: Button::DPadRight,
+        }
+    }
+}
+
+/// The thumbstick that moves the selection in menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuStick {
+    /// The horizontal axis, which is positive to the right.
+    pub x: Axis,
+    /// The vertical axis.
+    pub y: Axis,
+    /// Whether the vertical axis is positive downwards, as with every [`Axis`], rather than
+    /// upwards.
+    pub y_points_down: bool,
+}
+
+impl Default for MenuStick {
+    /// Uses the left thumbstick.
+    fn default() -> Self {
+        MenuStick {
+            x: Axis::LeftX,
+            y: Axis::LeftY,
+            y_points_down: true,
+        }
+    }
+}
+
+/// A direction to move the selection in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Towards the top.
+    Up,
+    /// Towards the bottom.
+    Down,
+    /// Towards the left.
+    Left,
+    /// Towards the right.
+    Right,
+}
+
+/// Converts the d-pad and a thumbstick into discrete navigation steps.
+///
+/// A step is produced as soon as a direction is held, then again after an initial delay, and
+/// then repeatedly at a fixed interval for as long as the direction stays held. Diagonals are
+/// filtered out: the thumbstick moves along its dominant axis, and when two d-pad directions
+/// are held, the one held first wins.
+#[derive(Debug, Clone)]
+pub struct Navigator {
+    buttons: MenuButtons,
+    stick: Option<MenuStick>,
+    threshold: f32,
+    initial_delay: Duration,
+    repeat_interval: Duration,
+    // The held direction, and when it next repeats.
+    held: Option<(Direction, Duration)>,
+}
+
+impl Navigator {
+    /// The default delay before a held direction starts repeating.
+    pub const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(400);
+    /// The default interval between repeats of a held direction.
+    pub const DEFAULT_REPEAT_INTERVAL: Duration = Duration::from_millis(100);
+    /// The default distance the thumbstick must be pushed to count as a direction.
+    pub const DEFAULT_THRESHOLD: f32 = 0.5;
+
+    /// Creates a navigator with the default buttons, the left thumbstick and default timings.
+    pub fn new() -> Self {
+        Self {
+            buttons: MenuButtons::default(),
+            stick: Some(MenuStick::default()),
+            threshold: Self::DEFAULT_THRESHOLD,
+            initial_delay: Self::DEFAULT_INITIAL_DELAY,
+            repeat_interval: Self::DEFAULT_REPEAT_INTERVAL,
+            held: None,
+        }
+    }
+
+    /// Uses different buttons for the d-pad directions.
+    pub fn with_buttons(mut self, buttons: MenuButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// Uses a different thumbstick, or none at all.
+    pub fn with_stick(mut self, stick: Option<MenuStick>) -> Self {
+        self.stick = stick;
+        self
+    }
+
+    /// Sets the distance the thumbstick must be pushed to count as a direction.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the delay before a held direction starts repeating, and the interval between
+    /// repeats.
+    pub fn with_repeat(mut self, initial_delay: Duration, repeat_interval: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self.repeat_interval = repeat_interval;
+        self
+    }
+
+    /// Gets the direction currently held, if any.
+    pub fn held(&self) -> Option<Direction> {
+        self.held.map(|(direction, _)| direction)
+    }
+
+    /// Reads the state of a gamepad, returning a step if one is due.
+    ///
+    /// Call this once per update with the time of the update, such as
+    /// [`GamepadContext::time`](crate::GamepadContext::time).
+    pub fn update(&mut self, gamepad: &Gamepad, time: Duration) -> Option<Direction> {
+        let direction = self.direction(gamepad);
+
+        match (direction, self.held) {
+            (Some(direction), Some((held, repeat_time))) if direction == held => {
+                if time >= repeat_time {
+                    let repeat_time = (repeat_time + self.repeat_interval).max(time);
+                    self.held = Some((direction, repeat_time));
+                    Some(direction)
+                } else {
+                    None
+                }
+            }
+            (Some(direction), _) => {
+                self.held = Some((direction, time + self.initial_delay));
+                Some(direction)
+            }
+            (None, _) => {
+                self.held = None;
+                None
+            }
+        }
+    }
+
+    fn direction(&self, gamepad: &Gamepad) -> Option<Direction> {
+        let activated = |button| gamepad.digital_inputs.activated(button);
+        let dpad = [
+            (Direction::Up, activated(self.buttons.up)),
+            (Direction::Down, activated(self.buttons.down)),
+            (Direction::Left, activated(self.buttons.left)),
+            (Direction::Right, activated(self.buttons.right)),
+        ];
+
+        let held = self.held();
+        if let Some((direction, _)) = dpad
+            .iter()
+            .find(|&&(direction, pressed)| pressed && Some(direction) == held)
+        {
+            return Some(*direction);
+        }
+        if let Some(&(direction, _)) = dpad.iter().find(|&&(_, pressed)| pressed) {
+            return Some(direction);
+        }
+
+        let stick = self.stick?;
+        let x = gamepad.analog_inputs.value(stick.x);
+        let mut y = gamepad.analog_inputs.value(stick.y);
+        if stick.y_points_down {
+            y = -y;
+        }
+
+        let vertical = if y > 0.0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+        let horizontal = if x > 0.0 {
+            Direction::Right
+        } else {
+            Direction::Left
+        };
+        let (direction, magnitude) = if y.abs() > x.abs() {
+            (vertical, y.abs())
+        } else {
+            (horizontal, x.abs())
+        };
+
+        if magnitude >= self.threshold {
+            Some(direction)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Navigator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A key of an on-screen keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Types a character.
+    Char(char),
+    /// Deletes the character before the caret.
+    Backspace,
+    /// Finishes text entry.
+    Submit,
+}
+
+/// An edit produced by a [`TextEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEvent {
+    /// The selection moved to the key at this row and column.
+    Moved {
+        /// The row of the selected key.
+        row: usize,
+        /// The column of the selected key.
+        column: usize,
+    },
+    /// A character was typed.
+    Insert(char),
+    /// The character before the caret should be deleted.
+    Backspace,
+    /// Text entry is finished.
+    Submit,
+    /// The cancel button was pressed. Games commonly treat this as [`Backspace`](Self::Backspace),
+    /// or leave text entry once the text is empty.
+    Cancel,
+}
+
+/// An on-screen keyboard, laid out as a grid of keys.
+///
+/// The selection moves between keys with a [`Navigator`] and wraps around at the edges.
+/// Rows may have different lengths; moving to a shorter row selects its last key.
+#[derive(Debug, Clone)]
+pub struct TextEntry {
+    layout: Vec<Vec<Key>>,
+    row: usize,
+    column: usize,
+    buttons: MenuButtons,
+    navigator: Navigator,
+}
+
+impl TextEntry {
+    /// Creates an on-screen keyboard with the given rows of keys, selecting the first key.
+    ///
+    /// Empty rows are ignored.
+    pub fn new(layout: Vec<Vec<Key>>) -> Self {
+        let layout = layout.into_iter().filter(|row| !row.is_empty()).collect();
+
+        Self {
+            layout,
+            row: 0,
+            column: 0,
+            buttons: MenuButtons::default(),
+            navigator: Navigator::default(),
+        }
+    }
+
+    /// Creates an on-screen keyboard with one character key per character of each row, followed
+    /// by a row with the backspace and submit keys.
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut layout: Vec<Vec<Key>> = rows
+            .into_iter()
+            .map(|row| row.chars().map(Key::Char).collect())
+            .collect();
+        layout.push(vec![Key::Backspace, Key::Submit]);
+        Self::new(layout)
+    }
+
+    /// Uses different buttons to operate the keyboard.
+    pub fn with_buttons(mut self, buttons: MenuButtons) -> Self {
+        self.buttons = buttons;
+        self.navigator = self.navigator.with_buttons(buttons);
+        self
+    }
+
+    /// Uses a differently configured navigator to move the selection.
+    ///
+    /// The navigator keeps using the buttons of the keyboard.
+    pub fn with_navigator(mut self, navigator: Navigator) -> Self {
+        self.navigator = navigator.with_buttons(self.buttons);
+        self
+    }
+
+    /// Gets the rows of keys.
+    pub fn layout(&self) -> &[Vec<Key>] {
+        &self.layout
+    }
+
+    /// Gets the row and column of the selected key.
+    pub fn selection(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    /// Gets the selected key, or `None` if the layout is empty.
+    pub fn selected_key(&self) -> Option<Key> {
+        self.layout.get(self.row)?.get(self.column).copied()
+    }
+
+    /// Converts the input of a gamepad since the last update into edits.
+    ///
+    /// Call this once per update with the time of the update, as with [`Navigator::update`].
+    pub fn update(&mut self, gamepad: &Gamepad, time: Duration) -> Vec<TextEvent> {
+        let pressed = |button| gamepad.digital_inputs.just_activated(button);
+        let mut events = Vec::new();
+        if self.layout.is_empty() {
+            return events;
+        }
+
+        if let Some(direction) = self.navigator.update(gamepad, time) {
+            let (rows, columns) = match direction {
+                Direction::Up => (-1, 0),
+                Direction::Down => (1, 0),
+                Direction::Left => (0, -1),
+                Direction::Right => (0, 1),
+            };
+            self.move_selection(rows, columns);
+            events.push(TextEvent::Moved {
+                row: self.row,
+                column: self.column,
+            });
+        }
+
+        if pressed(self.buttons.confirm) {
+            events.extend(self.selected_key().map(|key| match key {
+                Key::Char(character) => TextEvent::Insert(character),
+                Key::Backspace => TextEvent::Backspace,
+                Key::Submit => TextEvent::Submit,
+            }));
+        }
+        if pressed(self.buttons.cancel) {
+            events.push(TextEvent::Cancel);
+        }
+
+        events
+    }
+
+    fn move_selection(&mut self, rows: isize, columns: isize) {
+        let row_count = self.layout.len() as isize;
+        self.row = (self.row as isize + rows).rem_euclid(row_count) as usize;
+
+        let column_count = self.layout[self.row].len() as isize;
+        let column = (self.column as isize).min(column_count - 1);
+        self.column = (column + columns).rem_euclid(column_count) as usize;
+    }
+}
+
+/// A change produced by a [`VirtualCursor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorEvent {
+    /// The cursor moved to this position.
+    Moved {
+        /// The horizontal position.
+        x: f32,
+        /// The vertical position.
+        y: f32,
+    },
+    /// The click button was pressed at this position.
+    Pressed {
+        /// The horizontal position.
+        x: f32,
+        /// The vertical position.
+        y: f32,
+    },
+    /// The click button was released at this position.
+    Released {
+        /// The horizontal position.
+        x: f32,
+        /// The vertical position.
+        y: f32,
+    },
+}
+
+/// A mouse pointer emulated with a thumbstick, for screens designed around a mouse.
+///
+/// Positions are measured from the top left corner of the screen, with `y` pointing down, and
+/// are clamped to the screen.
+///
+/// The speed follows a response curve: the stick value is raised to the power of the curve
+/// exponent, so small deflections give fine control. While the stick stays pushed, the speed
+/// also accelerates up to a multiple of the base speed.
+#[derive(Debug, Clone)]
+pub struct VirtualCursor {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    stick: MenuStick,
+    click: Button,
+    speed: f32,
+    exponent: f32,
+    max_acceleration: f32,
+    acceleration_time: Duration,
+    // When the stick was first pushed, and when the cursor was last updated.
+    moving_since: Option<Duration>,
+    last_update: Option<Duration>,
+}
+
+impl VirtualCursor {
+    /// The default speed, in screen units per second at full deflection.
+    pub const DEFAULT_SPEED: f32 = 800.0;
+    /// The default response curve exponent.
+    pub const DEFAULT_EXPONENT: f32 = 2.0;
+
+    /// Creates a cursor at the center of a screen of the given size, moved by the left
+    /// thumbstick and clicked with the confirm button.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            x: width / 2.0,
+            y: height / 2.0,
+            width,
+            height,
+            stick: MenuStick::default(),
+            click: MenuButtons::default().confirm,
+            speed: Self::DEFAULT_SPEED,
+            exponent: Self::DEFAULT_EXPONENT,
+            max_acceleration: 1.0,
+            acceleration_time: Duration::ZERO,
+            moving_since: None,
+            last_update: None,
+        }
+    }
+
+    /// Uses a different thumbstick to move the cursor.
+    pub fn with_stick(mut self, stick: MenuStick) -> Self {
+        self.stick = stick;
+        self
+    }
+
+    /// Uses a different button to click.
+    pub fn with_click(mut self, click: Button) -> Self {
+        self.click = click;
+        self
+    }
+
+    /// Sets the speed at full deflection, in screen units per second.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets the response curve exponent, where `1.0` is linear.
+    pub fn with_curve(mut self, exponent: f32) -> Self {
+        self.exponent = exponent;
+        self
+    }
+
+    /// Makes the speed grow to `max_multiplier` times the base speed over `time` of continuous
+    /// movement.
+    pub fn with_acceleration(mut self, max_multiplier: f32, time: Duration) -> Self {
+        self.max_acceleration = max_multiplier;
+        self.acceleration_time = time;
+        self
+    }
+
+    /// Gets the position of the cursor.
+    pub fn position(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    /// Moves the cursor, such as when the real mouse was used.
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x.clamp(0.0, self.width);
+        self.y = y.clamp(0.0, self.height);
+    }
+
+    /// Changes the size of the screen, keeping the cursor on it.
+    pub fn set_bounds(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        self.set_position(self.x, self.y);
+    }
+
+    /// Moves the cursor from the state of a gamepad and reports clicks.
+    ///
+    /// Call this once per update with the time of the update, as with [`Navigator::update`].
+    pub fn update(&mut self, gamepad: &Gamepad, time: Duration) -> Vec<CursorEvent> {
+        let mut events = Vec::new();
+        let elapsed = match self.last_update.replace(time) {
+            Some(last_update) => time.saturating_sub(last_update).as_secs_f32(),
+            None => 0.0,
+        };
+
+        let curve = |value: f32| value.signum() * value.abs().powf(self.exponent);
+        let dx = curve(gamepad.analog_inputs.value(self.stick.x));
+        let mut dy = curve(gamepad.analog_inputs.value(self.stick.y));
+        if !self.stick.y_points_down {
+            dy = -dy;
+        }
+
+        if dx == 0.0 && dy == 0.0 {
+            self.moving_since = None;
+        } else {
+            let moving_since = *self.moving_since.get_or_insert(time);
+            let acceleration = if self.acceleration_time.is_zero() {
+                self.max_acceleration
+            } else {
+                let t = time.saturating_sub(moving_since).as_secs_f32()
+                    / self.acceleration_time.as_secs_f32();
+                1.0 + (self.max_acceleration - 1.0) * t.min(1.0)
+            };
+
+            let distance = self.speed * acceleration * elapsed;
+            let (x, y) = (self.x, self.y);
+            self.set_position(x + dx * distance, y + dy * distance);
+            if (self.x, self.y) != (x, y) {
+                events.push(CursorEvent::Moved {
+                    x: self.x,
+                    y: self.y,
+                });
+            }
+        }
+
+        if gamepad.digital_inputs.just_activated(self.click) {
+            events.push(CursorEvent::Pressed {
+                x: self.x,
+                y: self.y,
+            });
+        }
+        if gamepad.digital_inputs.just_deactivated(self.click) {
+            events.push(CursorEvent::Released {
+                x: self.x,
+                y: self.y,
+            });
+        }
+
+        events
+    }
+}
@@ -0,0 +1,57 @@
+//! Integration with the [miniquad](https://docs.rs/miniquad) windowing library.
+//!
+//! Call [`Gamepads::update`] first thing in `EventHandler::update`. See [`FrameGamepads`] for
+//! how platforms without gamepad support are handled.
+
+use crate::frame::FrameGamepads;
+use miniquad::warn;
+use std::ops::{Deref, DerefMut};
+
+/// Per-frame driver for a [`GamepadContext`](crate::GamepadContext).
+pub struct Gamepads(FrameGamepads);
+
+impl Gamepads {
+    /// Initializes the gamepad context.
+    pub fn new() -> Self {
+        Self(FrameGamepads::new(|error| {
+            warn!("Gamepad input is unavailable: {}", error)
+        }))
+    }
+
+    /// Updates the state of all gamepads.
+    pub fn update(&mut self) {
+        self.0
+            .update(|error| warn!("Failed to update gamepads: {}", error));
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Gamepads {
+    type Target = FrameGamepads;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Gamepads {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Starts miniquad with an event handler that owns a [`Gamepads`].
+///
+/// This is [`miniquad::start`], except that the gamepad context is initialized after the window,
+/// and passed to the function that creates the event handler.
+pub fn start<H>(conf: miniquad::conf::Conf, f: impl FnOnce(Gamepads) -> H + 'static)
+where
+    H: miniquad::EventHandler + 'static,
+{
+    miniquad::start(conf, move || Box::new(f(Gamepads::new())));
+}
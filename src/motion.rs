@@ -0,0 +1,67 @@
+//! Motion sensors of gamepads, such as the gyroscopes of DualShock 4, DualSense and Switch Pro
+//! controllers.
+//!
+//! Sensors draw power, so backends leave them off until enabled with
+//! [`GamepadContext::set_sensor_enabled`]. Enabled sensors are read on every update, and their
+//! latest readings are available through [`Gamepad::gyro`] and [`Gamepad::accel`].
+//!
+//! Readings follow the conventions of SDL2, with the gamepad held flat and facing away from the
+//! player: the X axis points to the right, the Y axis up and the Z axis towards the player.
+//!
+//! [`GamepadContext::set_sensor_enabled`]: crate::GamepadContext::set_sensor_enabled
+//! [`Gamepad::gyro`]: crate::Gamepad::gyro
+//! [`Gamepad::accel`]: crate::Gamepad::accel
+
+/// A motion sensor of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MotionSensor {
+    /// The gyroscope, reading the rate of rotation around each axis, in radians per second,
+    /// counter-clockwise when looking down the axis.
+    Gyroscope,
+    /// The accelerometer, reading acceleration along each axis in meters per second squared,
+    /// including gravity.
+    Accelerometer,
+}
+
+impl MotionSensor {
+    /// Every sensor.
+    pub const ALL: &'static [Self] = &[MotionSensor::Gyroscope, MotionSensor::Accelerometer];
+}
+
+/// The sensors of a gamepad, and the latest readings of those enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MotionState {
+    supported: [bool; 2],
+    readings: [Option<[f32; 3]>; 2],
+}
+
+impl MotionState {
+    pub(crate) fn supports(&self, sensor: MotionSensor) -> bool {
+        self.supported[sensor as usize]
+    }
+
+    pub(crate) fn set_supported(&mut self, sensor: MotionSensor, supported: bool) {
+        self.supported[sensor as usize] = supported;
+    }
+
+    pub(crate) fn is_enabled(&self, sensor: MotionSensor) -> bool {
+        self.readings[sensor as usize].is_some()
+    }
+
+    /// Enables or disables a sensor. An enabled sensor reads zero until its first reading.
+    pub(crate) fn set_enabled(&mut self, sensor: MotionSensor, enabled: bool) {
+        let reading = &mut self.readings[sensor as usize];
+        *reading = enabled.then(|| reading.unwrap_or_default());
+    }
+
+    pub(crate) fn reading(&self, sensor: MotionSensor) -> Option<[f32; 3]> {
+        self.readings[sensor as usize]
+    }
+
+    /// Stores a reading of an enabled sensor.
+    pub(crate) fn set_reading(&mut self, sensor: MotionSensor, values: [f32; 3]) {
+        if let Some(reading) = &mut self.readings[sensor as usize] {
+            *reading = values;
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Integration with the [notan](https://docs.rs/notan) framework.
+//!
+//! Store a [`Gamepads`] in the app state and call [`Gamepads::update`] from the app's update
+//! callback. Games whose only state is their input can use it as the app state directly:
+//!
+//! ```ignore
+//! notan::init_with(Gamepads::new)
+//!     .update(|_app: &mut App, gamepads: &mut Gamepads| gamepads.update())
+//!     .build()
+//! ```
+//!
+//! See [`FrameGamepads`] for how platforms without gamepad support are handled.
+
+use crate::frame::FrameGamepads;
+use log::warn;
+use notan_app::AppState;
+use std::ops::{Deref, DerefMut};
+
+/// Per-frame driver for a [`GamepadContext`](crate::GamepadContext).
+pub struct Gamepads(FrameGamepads);
+
+impl Gamepads {
+    /// Initializes the gamepad context.
+    pub fn new() -> Self {
+        Self(FrameGamepads::new(|error| {
+            warn!("Gamepad input is unavailable: {}", error)
+        }))
+    }
+
+    /// Updates the state of all gamepads.
+    pub fn update(&mut self) {
+        self.0
+            .update(|error| warn!("Failed to update gamepads: {}", error));
+    }
+}
+
+impl AppState for Gamepads {}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Gamepads {
+    type Target = FrameGamepads;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Gamepads {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,381 @@
+//! Players and the gamepads that back them.
+
+use crate::analog::{remap, ANALOG_MAX, ANALOG_MIN};
+use crate::arena::GamepadMap;
+use crate::digital::DigitalInput;
+use crate::input::{Input, InputSet};
+use crate::prompt::{prompt_key, DeviceKind};
+use crate::{Axis, Button, Gamepad, GamepadContext, GamepadId, Result};
+use std::time::Duration;
+
+/// A handle to a player, created with [`PlayerRegistry::create_player`].
+///
+/// Queries on a handle are answered by whichever gamepads are assigned to the player at the
+/// time, so game code can keep a handle for the whole session while players swap, disconnect
+/// and reconnect their gamepads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlayerHandle(usize);
+
+impl PlayerHandle {
+    /// Checks if a button is activated for the player.
+    ///
+    /// See [`PlayerRegistry::digital_inputs`].
+    pub fn pressed(self, context: &GamepadContext, button: Button) -> bool {
+        self.digital_inputs(context)
+            .is_some_and(|inputs| inputs.activated(button))
+    }
+
+    /// Checks if a button has just been activated for the player.
+    pub fn just_pressed(self, context: &GamepadContext, button: Button) -> bool {
+        self.digital_inputs(context)
+            .is_some_and(|inputs| inputs.just_activated(button))
+    }
+
+    /// Checks if a button has just been deactivated for the player.
+    pub fn just_released(self, context: &GamepadContext, button: Button) -> bool {
+        self.digital_inputs(context)
+            .is_some_and(|inputs| inputs.just_deactivated(button))
+    }
+
+    /// Gets the value of an axis, adjusted by the player's settings.
+    ///
+    /// The player's deadzone, if set, replaces that of the gamepad, and the gamepad's response
+    /// curve applies either way. The value is then inverted if requested and scaled by the
+    /// sensitivity, staying within `[ANALOG_MIN, ANALOG_MAX]`.
+    /// If several gamepads back the player, the value furthest from the center wins.
+    pub fn value(self, context: &GamepadContext, axis: Axis) -> f32 {
+        let settings = context.players().settings(self);
+        let deadzone = settings.and_then(|settings| settings.deadzone);
+        let value = self
+            .gamepads(context)
+            .map(|gamepad| match deadzone {
+                Some(deadzone) => {
+                    let inputs = &gamepad.analog_inputs;
+                    let value = remap(
+                        inputs.raw_value(axis),
+                        deadzone,
+                        inputs.deadzone_rescaling(),
+                    );
+                    gamepad.analog_inputs.response_curve(axis).apply(value)
+                }
+                None => gamepad.analog_inputs.value(axis),
+            })
+            .fold(0.0f32, |a, b| if b.abs() > a.abs() { b } else { a });
+
+        match settings {
+            Some(settings) => {
+                let sign = if settings.inverted_axes.contains(&axis) {
+                    -1.0
+                } else {
+                    1.0
+                };
+                (sign * value * settings.sensitivity).clamp(ANALOG_MIN, ANALOG_MAX)
+            }
+            None => value,
+        }
+    }
+
+    /// Gets an iterator over the connected gamepads assigned to the player.
+    pub fn gamepads(self, context: &GamepadContext) -> impl Iterator<Item = &Gamepad> {
+        context
+            .players()
+            .gamepads(self)
+            .iter()
+            .filter_map(|&id| context.gamepad(id))
+    }
+
+    /// Rumbles every gamepad assigned to the player for `duration`.
+    ///
+    /// Gamepads without rumble motors are skipped, so a player holding one of each still feels
+    /// it. See [`GamepadContext::rumble`] for the intensities. Fails if a backend fails to
+    /// rumble a gamepad, after trying the others.
+    pub fn rumble(
+        self,
+        context: &mut GamepadContext,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<()> {
+        self.for_each_rumble_gamepad(context, |context, id| {
+            context.rumble(id, strong, weak, duration)
+        })
+    }
+
+    /// Stops any rumble playing on the gamepads assigned to the player.
+    pub fn stop_rumble(self, context: &mut GamepadContext) -> Result<()> {
+        self.for_each_rumble_gamepad(context, |context, id| context.stop_rumble(id))
+    }
+
+    /// Gets the asset key of a button's prompt, matching the gamepad the player last used.
+    pub fn prompt_key(self, context: &GamepadContext, button: Button) -> &'static str {
+        let kind = context.players().device_kind(self).unwrap_or_default();
+        prompt_key(button, kind)
+    }
+
+    fn digital_inputs(self, context: &GamepadContext) -> Option<&DigitalInput<Button>> {
+        context.players().digital_inputs(self)
+    }
+
+    fn for_each_rumble_gamepad(
+        self,
+        context: &mut GamepadContext,
+        mut f: impl FnMut(&mut GamepadContext, GamepadId) -> Result<()>,
+    ) -> Result<()> {
+        let ids: Vec<_> = context
+            .players()
+            .gamepads(self)
+            .iter()
+            .copied()
+            .filter(|&id| context.gamepad(id).is_some_and(Gamepad::supports_rumble))
+            .collect();
+        let mut result = Ok(());
+        for id in ids {
+            let attempt = f(context, id);
+            if result.is_ok() {
+                result = attempt;
+            }
+        }
+        result
+    }
+}
+
+/// Options of a single player, such as accessibility options and stick adjustments.
+///
+/// With the `serde` feature, these can be stored in [`Profiles`](crate::profile::Profiles) so
+/// that they follow the player rather than the gamepad.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PlayerSettings {
+    /// How long a button must be held before it registers, or `None` to register presses
+    /// immediately.
+    ///
+    /// Shorter presses are ignored entirely, which filters out accidental presses caused by
+    /// tremors, similar to the "slow keys" option of desktop operating systems.
+    pub slow_input: Option<Duration>,
+    /// The analog deadzone for the player's axes, or `None` to keep that of the gamepad.
+    pub deadzone: Option<f32>,
+    /// Axes whose direction is reversed, such as the vertical axis for inverted look.
+    pub inverted_axes: Vec<Axis>,
+    /// The factor axis values are multiplied by.
+    pub sensitivity: f32,
+}
+
+impl Default for PlayerSettings {
+    fn default() -> Self {
+        Self {
+            slow_input: None,
+            deadzone: None,
+            inverted_axes: Vec::new(),
+            sensitivity: 1.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Player {
+    handle: PlayerHandle,
+    gamepads: Vec<GamepadId>,
+    settings: PlayerSettings,
+    digital_inputs: DigitalInput<Button>,
+    // Buttons activated on the player's gamepads as of the last update, before any filtering.
+    raw: InputSet<Button>,
+    // When each button in `raw` was first seen activated.
+    press_times: Vec<Duration>,
+    // The gamepad the player pressed a button on most recently.
+    device_kind: DeviceKind,
+    device_kind_changed: bool,
+}
+
+impl Player {
+    fn new(handle: PlayerHandle) -> Self {
+        Self {
+            handle,
+            gamepads: Vec::new(),
+            settings: PlayerSettings::default(),
+            digital_inputs: DigitalInput::default(),
+            raw: InputSet::default(),
+            press_times: vec![Duration::ZERO; Button::COUNT],
+            device_kind: DeviceKind::default(),
+            device_kind_changed: false,
+        }
+    }
+
+    fn update(&mut self, gamepads: &GamepadMap<Gamepad>, time: Duration) {
+        self.digital_inputs.update(time);
+
+        let active_gamepad = self
+            .gamepads
+            .iter()
+            .filter_map(|id| gamepads.get(*id))
+            .find(|gamepad| {
+                Button::ALL
+                    .iter()
+                    .any(|&button| gamepad.digital_inputs.just_activated(button))
+            });
+        let device_kind = active_gamepad
+            .or_else(|| self.gamepads.iter().find_map(|id| gamepads.get(*id)))
+            .map(Gamepad::device_kind);
+        self.device_kind_changed = false;
+        if let Some(device_kind) = device_kind {
+            if active_gamepad.is_some() || self.device_kind == DeviceKind::Generic {
+                self.device_kind_changed = device_kind != self.device_kind;
+                self.device_kind = device_kind;
+            }
+        }
+
+        for &button in Button::ALL {
+            let raw = self
+                .gamepads
+                .iter()
+                .filter_map(|id| gamepads.get(*id))
+                .any(|gamepad| gamepad.digital_inputs.activated(button));
+            if raw && !self.raw.contains(button) {
+                self.press_times[button.index()] = time;
+            }
+            set(&mut self.raw, button, raw);
+
+            let pressed = raw
+                && match self.settings.slow_input {
+                    Some(delay) => time.saturating_sub(self.press_times[button.index()]) >= delay,
+                    None => true,
+                };
+            if pressed {
+                self.digital_inputs.activate(button);
+            } else {
+                self.digital_inputs.deactivate(button);
+            }
+        }
+    }
+}
+
+fn set(inputs: &mut InputSet<Button>, button: Button, value: bool) {
+    if value {
+        inputs.insert(button);
+    } else {
+        inputs.remove(button);
+    }
+}
+
+/// Keeps track of players and which gamepads are assigned to them.
+///
+/// Each gamepad is assigned to at most one player. Gamepads are unassigned automatically when
+/// they disconnect.
+#[derive(Debug, Default)]
+pub struct PlayerRegistry {
+    players: Vec<Player>,
+    next_player: usize,
+}
+
+impl PlayerRegistry {
+    /// Creates a new player without any gamepads.
+    pub fn create_player(&mut self) -> PlayerHandle {
+        let player = PlayerHandle(self.next_player);
+        self.next_player += 1;
+        self.players.push(Player::new(player));
+        player
+    }
+
+    /// Removes a player, unassigning their gamepads.
+    pub fn remove_player(&mut self, player: PlayerHandle) {
+        self.players.retain(|entry| entry.handle != player);
+    }
+
+    /// Gets an iterator over all players, in the order they were created.
+    pub fn players(&self) -> impl Iterator<Item = PlayerHandle> + '_ {
+        self.players.iter().map(|player| player.handle)
+    }
+
+    /// Assigns a gamepad to a player, taking it away from any other player.
+    ///
+    /// Does nothing if the player has been removed.
+    pub fn assign(&mut self, player: PlayerHandle, gamepad: GamepadId) {
+        if self.player_entry(player).is_none() {
+            return;
+        }
+
+        self.unassign(gamepad);
+        if let Some(entry) = self.player_entry_mut(player) {
+            entry.gamepads.push(gamepad);
+        }
+    }
+
+    /// Unassigns a gamepad from its player.
+    pub fn unassign(&mut self, gamepad: GamepadId) {
+        for player in self.players.iter_mut() {
+            player.gamepads.retain(|&id| id != gamepad);
+        }
+    }
+
+    /// Gets the gamepads assigned to a player.
+    ///
+    /// Returns an empty slice if the player has been removed.
+    pub fn gamepads(&self, player: PlayerHandle) -> &[GamepadId] {
+        self.player_entry(player)
+            .map_or(&[], |player| player.gamepads.as_slice())
+    }
+
+    /// Gets the player a gamepad is assigned to.
+    pub fn player(&self, gamepad: GamepadId) -> Option<PlayerHandle> {
+        self.players
+            .iter()
+            .find(|player| player.gamepads.contains(&gamepad))
+            .map(|player| player.handle)
+    }
+
+    /// Gets the buttons of a player, combined from all of their gamepads and filtered through
+    /// their [settings](PlayerSettings).
+    ///
+    /// The `just_*` queries reflect changes between the two most recent updates of the context.
+    /// Returns `None` if the player has been removed.
+    pub fn digital_inputs(&self, player: PlayerHandle) -> Option<&DigitalInput<Button>> {
+        self.player_entry(player)
+            .map(|player| &player.digital_inputs)
+    }
+
+    /// Gets the family of the gamepad the player last pressed a button on.
+    ///
+    /// Until the player presses a button, this is the family of their first gamepad.
+    /// Returns `None` if the player has been removed.
+    pub fn device_kind(&self, player: PlayerHandle) -> Option<DeviceKind> {
+        self.player_entry(player).map(|player| player.device_kind)
+    }
+
+    /// Checks if the family of the player's gamepad changed in the most recent update, returning
+    /// the new family, so that button prompts can be refreshed.
+    pub fn device_kind_changed(&self, player: PlayerHandle) -> Option<DeviceKind> {
+        self.player_entry(player)
+            .filter(|player| player.device_kind_changed)
+            .map(|player| player.device_kind)
+    }
+
+    /// Gets the settings of a player.
+    ///
+    /// Returns `None` if the player has been removed.
+    pub fn settings(&self, player: PlayerHandle) -> Option<&PlayerSettings> {
+        self.player_entry(player).map(|player| &player.settings)
+    }
+
+    /// Sets the settings of a player.
+    ///
+    /// Does nothing if the player has been removed.
+    pub fn set_settings(&mut self, player: PlayerHandle, settings: PlayerSettings) {
+        if let Some(entry) = self.player_entry_mut(player) {
+            entry.settings = settings;
+        }
+    }
+
+    pub(crate) fn update(&mut self, gamepads: &GamepadMap<Gamepad>, time: Duration) {
+        for player in self.players.iter_mut() {
+            player.update(gamepads, time);
+        }
+    }
+
+    fn player_entry(&self, player: PlayerHandle) -> Option<&Player> {
+        self.players.iter().find(|entry| entry.handle == player)
+    }
+
+    fn player_entry_mut(&mut self, player: PlayerHandle) -> Option<&mut Player> {
+        self.players.iter_mut().find(|entry| entry.handle == player)
+    }
+}
@@ -0,0 +1,43 @@
+//! The battery of wireless gamepads.
+
+use std::time::Duration;
+
+/// How often the battery of every gamepad is read from the backend, since some backends read it
+/// from the operating system each time.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How full the battery of a gamepad is.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerLevel {
+    /// The backend could not read the battery.
+    #[default]
+    Unknown,
+    /// The gamepad is powered by its cable and has no battery to report.
+    Wired,
+    /// The fraction of the battery left, from `0.0` to `1.0`.
+    ///
+    /// Backends that only report a rough level, such as SDL2, give the upper bound of that
+    /// level, such as `0.2` for a battery between 5 and 20 percent.
+    Battery(f32),
+}
+
+/// The power supply of a gamepad, as read with [`Gamepad::power_info`](crate::Gamepad::power_info).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerInfo {
+    /// How full the battery is.
+    pub level: PowerLevel,
+    /// Whether the gamepad is plugged in and charging, or fully charged.
+    ///
+    /// Backends that cannot tell, such as SDL2, always report `false`.
+    pub charging: bool,
+}
+
+impl PowerInfo {
+    /// Checks if the gamepad runs on a battery with at most `fraction` left and is not charging,
+    /// such as for warning the player to plug it in.
+    pub fn is_low(&self, fraction: f32) -> bool {
+        matches!(self.level, PowerLevel::Battery(level) if level <= fraction) && !self.charging
+    }
+}
@@ -0,0 +1,38 @@
+//! Convenient re-exports of the most commonly used types.
+//!
+//! ```ignore
+//! use fishsticks::prelude::*;
+//! ```
+
+pub use crate::action::{ActionMap, Binding, HoldMode};
+pub use crate::analog::{AnalogInput, Coalescing, ResponseCurve, Smoothing};
+pub use crate::builder::GamepadContextBuilder;
+pub use crate::button_axis::ButtonAxis;
+pub use crate::composite::{CompositeGamepad, SourceMapping};
+pub use crate::config::{Config, Haptics};
+pub use crate::digital::DigitalInput;
+pub use crate::error::{Error, Result};
+pub use crate::gesture::{GestureTimings, Gestures};
+pub use crate::guid::Guid;
+pub use crate::input::Input;
+pub use crate::input_buffer::{Direction, InputBuffer};
+pub use crate::input_thread::InputThread;
+pub use crate::keyboard_gamepad::KeyboardGamepad;
+pub use crate::motion::MotionSensor;
+pub use crate::player::{PlayerHandle, PlayerRegistry, PlayerSettings};
+pub use crate::power::{PowerInfo, PowerLevel};
+pub use crate::prompt::DeviceKind;
+pub use crate::raw::RawInput;
+pub use crate::recorder::InputRecording;
+pub use crate::rollback::InputState;
+pub use crate::slots::{JoinMode, PlayerSlots};
+pub use crate::snapshot::{GamepadSnapshot, SharedGamepadState, Snapshot, ViewId};
+pub use crate::source::InputSource;
+pub use crate::state::{AnyInput, GamepadState};
+pub use crate::touchpad::{TouchFinger, Touchpad};
+pub use crate::trigger::{Trigger, TriggerEffect, TriggerInput};
+pub use crate::virtual_button::{Stick, VirtualButton};
+pub use crate::{
+    Axis, BackendKind, Button, ChangedInput, Gamepad, GamepadContext, GamepadEvent, GamepadId,
+    InputEvent, Mode,
+};
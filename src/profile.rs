@@ -0,0 +1,68 @@
+//! Storage of player settings by profile, so a player keeps their setup regardless of which
+//! gamepad they pick up.
+
+use crate::player::{PlayerHandle, PlayerRegistry, PlayerSettings};
+use std::collections::BTreeMap;
+
+/// Player settings stored by profile name.
+///
+/// Enable the `serde` feature to save and load profiles in any format supported by `serde`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Profiles {
+    profiles: BTreeMap<String, PlayerSettings>,
+}
+
+impl Profiles {
+    /// Creates an empty set of profiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the settings stored in a profile.
+    pub fn get(&self, profile: &str) -> Option<&PlayerSettings> {
+        self.profiles.get(profile)
+    }
+
+    /// Stores settings in a profile, replacing any stored before.
+    pub fn insert(&mut self, profile: impl Into<String>, settings: PlayerSettings) {
+        self.profiles.insert(profile.into(), settings);
+    }
+
+    /// Removes a profile, returning its settings.
+    pub fn remove(&mut self, profile: &str) -> Option<PlayerSettings> {
+        self.profiles.remove(profile)
+    }
+
+    /// Gets an iterator over the names of all profiles, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Stores the current settings of a player in a profile.
+    ///
+    /// Does nothing if the player has been removed.
+    pub fn save(
+        &mut self,
+        profile: impl Into<String>,
+        players: &PlayerRegistry,
+        player: PlayerHandle,
+    ) {
+        if let Some(settings) = players.settings(player) {
+            self.insert(profile, settings.clone());
+        }
+    }
+
+    /// Applies the settings stored in a profile to a player, returning `false` if there is no
+    /// such profile.
+    pub fn load(&self, profile: &str, players: &mut PlayerRegistry, player: PlayerHandle) -> bool {
+        match self.get(profile) {
+            Some(settings) => {
+                players.set_settings(player, settings.clone());
+                true
+            }
+            None => false,
+        }
+    }
+}
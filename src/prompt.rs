@@ -0,0 +1,179 @@
+//! Button prompts that match the gamepad the player is holding.
+//!
+//! Prompts are identified by stable asset keys, such as `"xbox_a"`, `"ps_cross"` or
+//! `"switch_b"`, which a game maps to its own glyphs. Keys describe buttons by their position,
+//! so the bottom face button is `"switch_b"` on a Switch controller. For text prompts,
+//! [`Button::prompt_name`] gives the label printed on the button instead.
+
+use crate::guid::Guid;
+use crate::Button;
+
+/// The USB vendor ID of Microsoft.
+const VENDOR_MICROSOFT: u16 = 0x045e;
+/// The USB vendor ID of Sony.
+const VENDOR_SONY: u16 = 0x054c;
+/// The USB vendor ID of Nintendo.
+const VENDOR_NINTENDO: u16 = 0x057e;
+/// The USB vendor ID of Valve.
+const VENDOR_VALVE: u16 = 0x28de;
+/// The USB product ID of the built-in controls of the Steam Deck.
+const PRODUCT_STEAM_DECK: u16 = 0x1205;
+
+/// The family of a gamepad, which determines the labels printed on its buttons.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceKind {
+    /// Xbox and compatible controllers.
+    Xbox,
+    /// PlayStation controllers, such as the DualShock and DualSense.
+    PlayStation,
+    /// Nintendo Switch controllers, such as the Pro Controller and Joy-Cons.
+    Switch,
+    /// The built-in controls of the Steam Deck, whose back grip buttons L4, R4, L5 and R5 are
+    /// reported as the paddle buttons.
+    ///
+    /// Its left and right trackpads are the first and second of
+    /// [`Gamepad::touchpads`](crate::Gamepad::touchpads), so clicking the left one is
+    /// [`Button::Touchpad`], and its gyroscope and accelerometer are read as
+    /// [`MotionSensor`](crate::motion::MotionSensor)s. Without Steam running, the backends map
+    /// the controls with a mapping of their own.
+    SteamDeck,
+    /// Any other controller.
+    #[default]
+    Generic,
+}
+
+impl DeviceKind {
+    /// Guesses the family of a gamepad from its name.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let matches = |patterns: &[&str]| patterns.iter().any(|pattern| name.contains(pattern));
+
+        if matches(&["steam deck"]) {
+            Self::SteamDeck
+        } else if matches(&["xbox", "x-box", "xinput"]) {
+            Self::Xbox
+        } else if matches(&["playstation", "ps3", "ps4", "ps5", "dualshock", "dualsense"]) {
+            Self::PlayStation
+        } else if matches(&["nintendo", "switch", "joy-con"]) {
+            Self::Switch
+        } else {
+            Self::Generic
+        }
+    }
+
+    /// Identifies the family of a gamepad from its USB vendor and product IDs.
+    ///
+    /// Returns `None` for vendors that make no first-party controllers, or that make several
+    /// kinds, such as Valve, whose Steam Input reports virtual gamepads for any controller.
+    pub fn from_ids(vendor_id: u16, product_id: u16) -> Option<Self> {
+        match (vendor_id, product_id) {
+            (VENDOR_MICROSOFT, _) => Some(Self::Xbox),
+            (VENDOR_SONY, _) => Some(Self::PlayStation),
+            (VENDOR_NINTENDO, _) => Some(Self::Switch),
+            (VENDOR_VALVE, PRODUCT_STEAM_DECK) => Some(Self::SteamDeck),
+            _ => None,
+        }
+    }
+
+    /// Identifies the family of a gamepad from the IDs in its GUID, or failing that, from its
+    /// name.
+    pub fn detect(name: &str, guid: Guid) -> Self {
+        guid.vendor_id()
+            .zip(guid.product_id())
+            .and_then(|(vendor_id, product_id)| Self::from_ids(vendor_id, product_id))
+            .unwrap_or_else(|| Self::from_name(name))
+    }
+
+    fn column(self) -> usize {
+        match self {
+            Self::Xbox => 0,
+            Self::PlayStation => 1,
+            Self::Switch => 2,
+            Self::SteamDeck => 3,
+            Self::Generic => 4,
+        }
+    }
+}
+
+// Asset keys of each button, in the order of `DeviceKind::column`.
+#[rustfmt::skip]
+const PROMPTS: &[(&str, [&str; 5])] = &[
+    ("south", ["xbox_a", "ps_cross", "switch_b", "deck_a", "generic_south"]),
+    ("east", ["xbox_b", "ps_circle", "switch_a", "deck_b", "generic_east"]),
+    ("west", ["xbox_x", "ps_square", "switch_y", "deck_x", "generic_west"]),
+    ("north", ["xbox_y", "ps_triangle", "switch_x", "deck_y", "generic_north"]),
+    ("back", ["xbox_view", "ps_share", "switch_minus", "deck_view", "generic_back"]),
+    ("start", ["xbox_menu", "ps_options", "switch_plus", "deck_menu", "generic_start"]),
+    ("guide", ["xbox_guide", "ps_home", "switch_home", "deck_steam", "generic_guide"]),
+    ("left_stick", ["xbox_ls", "ps_l3", "switch_ls", "deck_l3", "generic_left_stick"]),
+    ("right_stick", ["xbox_rs", "ps_r3", "switch_rs", "deck_r3", "generic_right_stick"]),
+    ("left_shoulder", ["xbox_lb", "ps_l1", "switch_l", "deck_l1", "generic_left_shoulder"]),
+    ("right_shoulder", ["xbox_rb", "ps_r1", "switch_r", "deck_r1", "generic_right_shoulder"]),
+    ("left_trigger", ["xbox_lt", "ps_l2", "switch_zl", "deck_l2", "generic_left_trigger"]),
+    ("right_trigger", ["xbox_rt", "ps_r2", "switch_zr", "deck_r2", "generic_right_trigger"]),
+    ("dpad_up", ["xbox_dpad_up", "ps_dpad_up", "switch_dpad_up", "deck_dpad_up", "generic_dpad_up"]),
+    ("dpad_down", ["xbox_dpad_down", "ps_dpad_down", "switch_dpad_down", "deck_dpad_down", "generic_dpad_down"]),
+    ("dpad_left", ["xbox_dpad_left", "ps_dpad_left", "switch_dpad_left", "deck_dpad_left", "generic_dpad_left"]),
+    ("dpad_right", ["xbox_dpad_right", "ps_dpad_right", "switch_dpad_right", "deck_dpad_right", "generic_dpad_right"]),
+    ("misc", ["xbox_share", "ps_mute", "switch_capture", "deck_quick_access", "generic_misc"]),
+    ("touchpad", ["generic_touchpad", "ps_touchpad", "generic_touchpad", "deck_left_trackpad", "generic_touchpad"]),
+    ("paddle1", ["xbox_p1", "ps_paddle1", "generic_paddle1", "deck_r4", "generic_paddle1"]),
+    ("paddle2", ["xbox_p2", "ps_paddle2", "generic_paddle2", "deck_l4", "generic_paddle2"]),
+    ("paddle3", ["xbox_p3", "ps_paddle3", "generic_paddle3", "deck_r5", "generic_paddle3"]),
+    ("paddle4", ["xbox_p4", "ps_paddle4", "generic_paddle4", "deck_l5", "generic_paddle4"]),
+    ("c", ["generic_c", "generic_c", "generic_c", "generic_c", "generic_c"]),
+    ("z", ["generic_z", "generic_z", "generic_z", "generic_z", "generic_z"]),
+];
+
+/// Gets the asset key of a button's prompt on the given kind of gamepad.
+pub fn prompt_key(button: Button, kind: DeviceKind) -> &'static str {
+    let name = button.name();
+    PROMPTS
+        .iter()
+        .find(|&&(other, _)| other == name)
+        .map_or("generic_unknown", |(_, keys)| keys[kind.column()])
+}
+
+// Labels printed on each button, in the order of `DeviceKind::column`. Generic controllers use
+// the Xbox labels, which the gamepad mappings of every backend follow.
+#[rustfmt::skip]
+const LABELS: &[(&str, [&str; 5])] = &[
+    ("south", ["A", "Cross", "B", "A", "A"]),
+    ("east", ["B", "Circle", "A", "B", "B"]),
+    ("west", ["X", "Square", "Y", "X", "X"]),
+    ("north", ["Y", "Triangle", "X", "Y", "Y"]),
+    ("back", ["View", "Share", "-", "View", "Back"]),
+    ("start", ["Menu", "Options", "+", "Menu", "Start"]),
+    ("guide", ["Xbox", "PS", "Home", "Steam", "Guide"]),
+    ("left_stick", ["LS", "L3", "LS", "L3", "LS"]),
+    ("right_stick", ["RS", "R3", "RS", "R3", "RS"]),
+    ("left_shoulder", ["LB", "L1", "L", "L1", "LB"]),
+    ("right_shoulder", ["RB", "R1", "R", "R1", "RB"]),
+    ("left_trigger", ["LT", "L2", "ZL", "L2", "LT"]),
+    ("right_trigger", ["RT", "R2", "ZR", "R2", "RT"]),
+    ("dpad_up", ["D-Pad Up", "D-Pad Up", "D-Pad Up", "D-Pad Up", "D-Pad Up"]),
+    ("dpad_down", ["D-Pad Down", "D-Pad Down", "D-Pad Down", "D-Pad Down", "D-Pad Down"]),
+    ("dpad_left", ["D-Pad Left", "D-Pad Left", "D-Pad Left", "D-Pad Left", "D-Pad Left"]),
+    ("dpad_right", ["D-Pad Right", "D-Pad Right", "D-Pad Right", "D-Pad Right", "D-Pad Right"]),
+    ("misc", ["Share", "Mute", "Capture", "Quick Access", "Misc"]),
+    ("touchpad", ["Touchpad", "Touchpad", "Touchpad", "Left Trackpad", "Touchpad"]),
+    ("paddle1", ["P1", "Paddle 1", "Paddle 1", "R4", "Paddle 1"]),
+    ("paddle2", ["P2", "Paddle 2", "Paddle 2", "L4", "Paddle 2"]),
+    ("paddle3", ["P3", "Paddle 3", "Paddle 3", "R5", "Paddle 3"]),
+    ("paddle4", ["P4", "Paddle 4", "Paddle 4", "L5", "Paddle 4"]),
+    ("c", ["C", "C", "C", "C", "C"]),
+    ("z", ["Z", "Z", "Z", "Z", "Z"]),
+];
+
+impl Button {
+    /// Gets the label printed on the button on the given kind of gamepad, such as `"A"`,
+    /// `"Cross"` or `"B"` for [`Button::South`], for text prompts.
+    pub fn prompt_name(self, kind: DeviceKind) -> &'static str {
+        let name = self.name();
+        LABELS
+            .iter()
+            .find(|&&(other, _)| other == name)
+            .map_or("?", |(_, labels)| labels[kind.column()])
+    }
+}
@@ -0,0 +1,61 @@
+//! The raw, unmapped buttons and axes of gamepads.
+//!
+//! Flight sticks, wheels and arcade encoders have inputs that do not fit [`Button`](crate::Button)
+//! and [`Axis`](crate::Axis), which the mappings drop. Every input the backend can see is instead
+//! read by index on every update, and is available through
+//! [`Gamepad::raw`](crate::Gamepad::raw). Indices are those of the device, so they differ between
+//! models, and are best bound by letting the player press the input.
+
+/// The raw inputs of a gamepad, as last read.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RawState {
+    pub(crate) buttons: Vec<bool>,
+    pub(crate) axes: Vec<f32>,
+}
+
+/// The raw, unmapped inputs of a gamepad.
+///
+/// Created with [`Gamepad::raw`](crate::Gamepad::raw).
+#[derive(Debug, Clone, Copy)]
+pub struct RawInput<'a> {
+    state: &'a RawState,
+}
+
+impl<'a> RawInput<'a> {
+    pub(crate) fn new(state: &'a RawState) -> Self {
+        Self { state }
+    }
+
+    /// Gets the number of raw buttons.
+    pub fn button_count(&self) -> usize {
+        self.state.buttons.len()
+    }
+
+    /// Gets the number of raw axes.
+    pub fn axis_count(&self) -> usize {
+        self.state.axes.len()
+    }
+
+    /// Checks if the raw button at an index is pressed, or `false` if there is none.
+    pub fn button(&self, index: usize) -> bool {
+        self.state.buttons.get(index).copied().unwrap_or_default()
+    }
+
+    /// Gets the value of the raw axis at an index, from `-1.0` to `1.0`, or `0.0` if there is
+    /// none.
+    ///
+    /// Raw values have no deadzone or calibration applied.
+    pub fn axis(&self, index: usize) -> f32 {
+        self.state.axes.get(index).copied().unwrap_or_default()
+    }
+
+    /// Gets whether each raw button is pressed, by index.
+    pub fn buttons(&self) -> &'a [bool] {
+        &self.state.buttons
+    }
+
+    /// Gets the value of each raw axis, by index.
+    pub fn axes(&self) -> &'a [f32] {
+        &self.state.axes
+    }
+}
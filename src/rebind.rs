@@ -0,0 +1,75 @@
+//! Listening for the next input, for assigning it to an action in a controls menu.
+
+use crate::action::Binding;
+use crate::arena::GamepadMap;
+use crate::error::{Error, Result};
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad, GamepadId};
+
+/// Waits for a button to be pressed or an axis to be moved on any gamepad.
+pub(crate) struct BindingListener {
+    threshold: f32,
+    // The raw value of every axis of every gamepad when it was first seen, in the order of
+    // `Axis::ALL`. Sticks that drift rest away from the center, so an axis only counts once it
+    // moves away from where it rested.
+    resting: GamepadMap<Vec<f32>>,
+}
+
+impl BindingListener {
+    pub(crate) fn new(gamepads: &GamepadMap<Gamepad>, threshold: f32) -> Result<Self> {
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            return Err(Error::Invalid(format!(
+                "binding threshold must be in (0.0, 1.0], got {}",
+                threshold
+            )));
+        }
+
+        let mut listener = Self {
+            threshold,
+            resting: GamepadMap::new(),
+        };
+        for (id, gamepad) in gamepads {
+            listener.resting.insert(id, resting_values(gamepad));
+        }
+        Ok(listener)
+    }
+
+    /// Looks for an input activated during the latest update. Buttons take precedence over axes.
+    pub(crate) fn poll(&mut self, gamepads: &GamepadMap<Gamepad>) -> Option<(GamepadId, Binding)> {
+        self.resting.retain(|id, _| gamepads.contains_key(id));
+
+        for (id, gamepad) in gamepads {
+            if let Some(&button) = Button::ALL
+                .iter()
+                .find(|&&button| gamepad.digital_inputs.just_activated(button))
+            {
+                return Some((id, Binding::button(button)));
+            }
+        }
+
+        for (id, gamepad) in gamepads {
+            let Some(resting) = self.resting.get(id) else {
+                self.resting.insert(id, resting_values(gamepad));
+                continue;
+            };
+
+            for (&axis, &rest) in Axis::ALL.iter().zip(resting) {
+                let value = gamepad.analog_inputs.value(axis);
+                let moved = (gamepad.analog_inputs.raw_value(axis) - rest).abs();
+                if value.abs() >= self.threshold && moved >= self.threshold {
+                    let binding = Binding::axis_direction(axis, value > 0.0, self.threshold);
+                    return Some((id, binding));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn resting_values(gamepad: &Gamepad) -> Vec<f32> {
+    Axis::ALL
+        .iter()
+        .map(|&axis| gamepad.analog_inputs.raw_value(axis))
+        .collect()
+}
@@ -0,0 +1,71 @@
+//! Recognizing gamepads that reconnect, so that they get their settings back.
+
+use crate::player::PlayerHandle;
+use crate::slots::DEFAULT_RECONNECT_GRACE;
+use crate::{Gamepad, GamepadId};
+use std::time::Duration;
+
+/// A gamepad that disconnected, kept in case it reconnects.
+pub(crate) struct Disconnected {
+    pub(crate) id: GamepadId,
+    // The gamepad as it disconnected, without its backend handle.
+    pub(crate) gamepad: Gamepad,
+    pub(crate) player: Option<PlayerHandle>,
+    since: Duration,
+}
+
+/// The gamepads that disconnected within the grace period.
+pub(crate) struct Reconnects {
+    pub(crate) grace: Duration,
+    disconnected: Vec<Disconnected>,
+}
+
+impl Default for Reconnects {
+    fn default() -> Self {
+        Self {
+            grace: DEFAULT_RECONNECT_GRACE,
+            disconnected: Vec::new(),
+        }
+    }
+}
+
+impl Reconnects {
+    /// Keeps a gamepad that disconnected at `time`.
+    pub(crate) fn push(
+        &mut self,
+        id: GamepadId,
+        mut gamepad: Gamepad,
+        player: Option<PlayerHandle>,
+        time: Duration,
+    ) {
+        if self.grace.is_zero() {
+            return;
+        }
+        gamepad.owned_internal_gamepad = None;
+        self.disconnected.push(Disconnected {
+            id,
+            gamepad,
+            player,
+            since: time,
+        });
+    }
+
+    /// Takes the gamepad that a gamepad connecting at `time` is a reconnection of, if any.
+    ///
+    /// Gamepads are matched by GUID, or by name if the backend could not identify them. Of
+    /// several identical gamepads, the one that disconnected first is taken.
+    pub(crate) fn take(&mut self, gamepad: &Gamepad, time: Duration) -> Option<Disconnected> {
+        let grace = self.grace;
+        self.disconnected
+            .retain(|disconnected| time.saturating_sub(disconnected.since) < grace);
+        let position = self.disconnected.iter().position(|disconnected| {
+            let previous = &disconnected.gamepad;
+            if gamepad.guid.is_zero() {
+                previous.guid.is_zero() && previous.name == gamepad.name
+            } else {
+                previous.guid == gamepad.guid
+            }
+        })?;
+        Some(self.disconnected.remove(position))
+    }
+}
@@ -0,0 +1,473 @@
+//! Recording of the input of every gamepad, frame by frame, for replays and regression tests.
+//!
+//! A recording is made with [`GamepadContext::start_input_recording`] and
+//! [`GamepadContext::stop_input_recording`], and saved as a compact binary stream with
+//! [`InputRecording::to_bytes`]. Playing it back with [`GamepadContext::play_input_recording`]
+//! feeds one recorded frame into each update, as if the input was performed live, so a game
+//! sees the same input on the same frames every time.
+//!
+//! Gamepads are recorded by slot, numbered in the order they connected, and played back onto
+//! the gamepads connected at the start of playback in the same order. Recordings are specific to
+//! the backend they were made with, since axes differ between backends.
+//!
+//! [`GamepadContext::start_input_recording`]: crate::GamepadContext::start_input_recording
+//! [`GamepadContext::stop_input_recording`]: crate::GamepadContext::stop_input_recording
+//! [`GamepadContext::play_input_recording`]: crate::GamepadContext::play_input_recording
+
+use crate::analog::AnalogInputValue;
+use crate::arena::GamepadMap;
+use crate::backend::Event;
+use crate::error::{Error, Result};
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad, GamepadId};
+use std::time::Duration;
+
+/// Identifies the binary format of recordings.
+const MAGIC: &[u8; 4] = b"FSIR";
+/// The version of the binary format.
+const VERSION: u8 = 1;
+
+/// A single change of input in a recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecordedInput {
+    Press(Button),
+    Release(Button),
+    /// The raw value of an axis, quantized to 16 bits.
+    Axis(Axis, i16),
+}
+
+impl RecordedInput {
+    fn from_event(event: &Event) -> Option<(GamepadId, Self)> {
+        Some(match *event {
+            Event::ButtonDown(id, button) => (id, RecordedInput::Press(button)),
+            Event::ButtonUp(id, button) => (id, RecordedInput::Release(button)),
+            Event::AxisMotion(id, axis, value) => (id, RecordedInput::Axis(axis, quantize(value))),
+            _ => return None,
+        })
+    }
+
+    fn to_event(self, id: GamepadId) -> Event {
+        match self {
+            RecordedInput::Press(button) => Event::ButtonDown(id, button),
+            RecordedInput::Release(button) => Event::ButtonUp(id, button),
+            RecordedInput::Axis(axis, value) => {
+                Event::AxisMotion(id, axis, AnalogInputValue::from(value))
+            }
+        }
+    }
+}
+
+/// A change of input, at an offset from the start of the recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RecordedEvent {
+    offset: Duration,
+    slot: u8,
+    input: RecordedInput,
+}
+
+/// The input of every gamepad over a number of frames.
+///
+/// Besides the changes made during each frame, a recording holds the state of every gamepad at
+/// its start, so that buttons held when recording started are held during playback as well.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    // The inputs held by each slot at the start of the recording.
+    initial: Vec<Vec<RecordedInput>>,
+    frames: Vec<Vec<RecordedEvent>>,
+}
+
+impl InputRecording {
+    /// Gets the number of frames in the recording.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Gets the number of gamepads in the recording.
+    pub fn slot_count(&self) -> usize {
+        self.initial.len()
+    }
+
+    /// Gets the offset of the last input of the recording.
+    pub fn duration(&self) -> Duration {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.last())
+            .map_or(Duration::ZERO, |event| event.offset)
+    }
+
+    /// Encodes the recording as a compact binary stream, such as for a replay file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+
+        write_varint(&mut bytes, self.initial.len() as u64);
+        for inputs in &self.initial {
+            write_varint(&mut bytes, inputs.len() as u64);
+            for &input in inputs {
+                write_input(&mut bytes, input);
+            }
+        }
+
+        // Offsets are stored as the microseconds since the previous event.
+        let mut previous = Duration::ZERO;
+        write_varint(&mut bytes, self.frames.len() as u64);
+        for frame in &self.frames {
+            write_varint(&mut bytes, frame.len() as u64);
+            for event in frame {
+                let delta = event.offset.saturating_sub(previous);
+                write_varint(&mut bytes, delta.as_micros() as u64);
+                previous = previous.max(event.offset);
+                bytes.push(event.slot);
+                write_input(&mut bytes, event.input);
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a recording encoded with [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::Invalid("not an input recording".into()));
+        }
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(Error::Invalid(format!(
+                "unsupported input recording version {}",
+                version
+            )));
+        }
+
+        let mut recording = Self::default();
+        for _ in 0..reader.varint()? {
+            let mut inputs = Vec::new();
+            for _ in 0..reader.varint()? {
+                inputs.push(reader.input()?);
+            }
+            recording.initial.push(inputs);
+        }
+
+        let mut offset = Duration::ZERO;
+        for _ in 0..reader.varint()? {
+            let mut frame = Vec::new();
+            for _ in 0..reader.varint()? {
+                offset += Duration::from_micros(reader.varint()?);
+                let slot = reader.byte()?;
+                if usize::from(slot) >= recording.initial.len() {
+                    return Err(Error::Invalid(format!(
+                        "input recording refers to unknown slot {}",
+                        slot
+                    )));
+                }
+                let input = reader.input()?;
+                frame.push(RecordedEvent {
+                    offset,
+                    slot,
+                    input,
+                });
+            }
+            recording.frames.push(frame);
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(Error::Invalid(
+                "unexpected data after input recording".into(),
+            ));
+        }
+        Ok(recording)
+    }
+}
+
+fn quantize(value: AnalogInputValue) -> i16 {
+    (value.get() * f32::from(i16::MAX)).round() as i16
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_input(bytes: &mut Vec<u8>, input: RecordedInput) {
+    match input {
+        RecordedInput::Press(button) => bytes.extend_from_slice(&[0, button.index() as u8]),
+        RecordedInput::Release(button) => bytes.extend_from_slice(&[1, button.index() as u8]),
+        RecordedInput::Axis(axis, value) => {
+            bytes.extend_from_slice(&[2, axis.index() as u8]);
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(Error::Invalid("input recording is truncated".into()));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::Invalid(
+            "input recording has an overlong number".into(),
+        ))
+    }
+
+    fn input(&mut self) -> Result<RecordedInput> {
+        let kind = self.byte()?;
+        let index = usize::from(self.byte()?);
+        let button = || {
+            Button::from_index(index).ok_or_else(|| {
+                Error::Invalid(format!("input recording has unknown button {}", index))
+            })
+        };
+        Ok(match kind {
+            0 => RecordedInput::Press(button()?),
+            1 => RecordedInput::Release(button()?),
+            2 => {
+                let axis = Axis::from_index(index).ok_or_else(|| {
+                    Error::Invalid(format!("input recording has unknown axis {}", index))
+                })?;
+                let value = self.take(2)?;
+                RecordedInput::Axis(axis, i16::from_le_bytes([value[0], value[1]]))
+            }
+            _ => {
+                return Err(Error::Invalid(format!(
+                    "input recording has unknown input kind {}",
+                    kind
+                )))
+            }
+        })
+    }
+}
+
+/// A recording in progress.
+struct Recording {
+    start: Duration,
+    // The gamepad of each slot.
+    slots: Vec<GamepadId>,
+    recording: InputRecording,
+}
+
+/// A playback in progress.
+struct Playback {
+    // Set on the first update of the playback.
+    start: Option<Duration>,
+    // The gamepad each slot is played back onto, if there are enough gamepads.
+    slots: Vec<Option<GamepadId>>,
+    recording: InputRecording,
+    next_frame: usize,
+}
+
+/// The input recording and playback of a [`GamepadContext`](crate::GamepadContext).
+#[derive(Default)]
+pub(crate) struct Recorder {
+    recording: Option<Recording>,
+    playback: Option<Playback>,
+}
+
+impl Recorder {
+    pub(crate) fn start_recording(&mut self, gamepads: &GamepadMap<Gamepad>, time: Duration) {
+        let mut recording = Recording {
+            start: time,
+            slots: Vec::new(),
+            recording: InputRecording::default(),
+        };
+        for (id, gamepad) in gamepads.iter() {
+            recording.slots.push(id);
+            recording.recording.initial.push(held_inputs(gamepad));
+        }
+        self.recording = Some(recording);
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub(crate) fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take().map(|recording| recording.recording)
+    }
+
+    pub(crate) fn start_playback(
+        &mut self,
+        gamepads: &GamepadMap<Gamepad>,
+        recording: InputRecording,
+    ) {
+        let mut ids = gamepads.iter().map(|(id, _)| id);
+        let slots = (0..recording.slot_count()).map(|_| ids.next()).collect();
+        self.playback = Some(Playback {
+            start: None,
+            slots,
+            recording,
+            next_frame: 0,
+        });
+    }
+
+    pub(crate) fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    pub(crate) fn stop_playback(&mut self) {
+        self.playback = None;
+    }
+
+    /// Replaces the input of the gamepads being played back onto with the next frame of the
+    /// playback, then records the events of the update.
+    pub(crate) fn process(
+        &mut self,
+        gamepads: &GamepadMap<Gamepad>,
+        time: Duration,
+        events: &mut Vec<(Duration, Event)>,
+    ) {
+        if let Some(playback) = &mut self.playback {
+            if playback.play(gamepads, time, events) {
+                self.playback = None;
+            }
+        }
+
+        if let Some(recording) = &mut self.recording {
+            recording.record(events);
+        }
+    }
+}
+
+impl Recording {
+    fn record(&mut self, events: &[(Duration, Event)]) {
+        let mut frame = Vec::new();
+        for (time, event) in events {
+            if let Event::Connected { id, .. } = *event {
+                if self.slots.len() < usize::from(u8::MAX) {
+                    self.slots.push(id);
+                    self.recording.initial.push(Vec::new());
+                }
+                continue;
+            }
+
+            let Some((id, input)) = RecordedInput::from_event(event) else {
+                continue;
+            };
+            // A gamepad that reconnects gets a new slot, so search from the most recent.
+            let Some(slot) = self.slots.iter().rposition(|&other| other == id) else {
+                continue;
+            };
+            // Events may be read slightly out of order; keep the offsets monotonic.
+            let offset = time
+                .saturating_sub(self.start)
+                .max(self.recording.duration());
+            frame.push(RecordedEvent {
+                offset,
+                slot: slot as u8,
+                input,
+            });
+        }
+        self.recording.frames.push(frame);
+    }
+}
+
+impl Playback {
+    /// Plays the next frame. Returns whether the playback is over.
+    fn play(
+        &mut self,
+        gamepads: &GamepadMap<Gamepad>,
+        time: Duration,
+        events: &mut Vec<(Duration, Event)>,
+    ) -> bool {
+        let slots = &self.slots;
+        events.retain(|(_, event)| match RecordedInput::from_event(event) {
+            Some((id, _)) => !slots.contains(&Some(id)),
+            None => true,
+        });
+
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                self.start = Some(time);
+                // Bring every gamepad to the state it had at the start of the recording.
+                for (&id, initial) in self.slots.iter().zip(&self.recording.initial) {
+                    let Some(gamepad) = id.and_then(|id| gamepads.get(id).map(|g| (id, g))) else {
+                        continue;
+                    };
+                    for input in reset_inputs(gamepad.1, initial) {
+                        events.push((time, input.to_event(gamepad.0)));
+                    }
+                }
+                time
+            }
+        };
+
+        let Some(frame) = self.recording.frames.get(self.next_frame) else {
+            return true;
+        };
+        for event in frame {
+            if let Some(Some(id)) = self.slots.get(usize::from(event.slot)) {
+                let event_time = (start + event.offset).min(time);
+                events.push((event_time, event.input.to_event(*id)));
+            }
+        }
+        self.next_frame += 1;
+        self.next_frame >= self.recording.frames.len()
+    }
+}
+
+/// Lists the buttons held and axes moved on a gamepad.
+fn held_inputs(gamepad: &Gamepad) -> Vec<RecordedInput> {
+    let buttons = Button::ALL
+        .iter()
+        .filter(|&&button| gamepad.digital_inputs.activated(button))
+        .map(|&button| RecordedInput::Press(button));
+    let axes = Axis::ALL.iter().filter_map(|&axis| {
+        let value = AnalogInputValue::from(gamepad.analog_inputs.raw_value(axis));
+        let value = quantize(value);
+        (value != 0).then_some(RecordedInput::Axis(axis, value))
+    });
+    buttons.chain(axes).collect()
+}
+
+/// Lists the changes that bring a gamepad to the given held inputs.
+fn reset_inputs(gamepad: &Gamepad, held: &[RecordedInput]) -> Vec<RecordedInput> {
+    let mut inputs = Vec::new();
+    for &button in Button::ALL {
+        let pressed = held.contains(&RecordedInput::Press(button));
+        if pressed != gamepad.digital_inputs.activated(button) {
+            inputs.push(if pressed {
+                RecordedInput::Press(button)
+            } else {
+                RecordedInput::Release(button)
+            });
+        }
+    }
+    for &axis in Axis::ALL {
+        let value = held
+            .iter()
+            .find_map(|&input| match input {
+                RecordedInput::Axis(other, value) if other == axis => Some(value),
+                _ => None,
+            })
+            .unwrap_or(0);
+        inputs.push(RecordedInput::Axis(axis, value));
+    }
+    inputs
+}
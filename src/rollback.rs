@@ -0,0 +1,168 @@
+//! Compact per-frame input state, for rollback netcode.
+//!
+//! Rollback libraries such as GGRS exchange the input of every player each frame, and replay
+//! older frames when a late input arrives. An [`InputState`] captures everything a gamepad
+//! reports in a few bytes, with analog values quantized so that every peer simulates exactly the
+//! same input.
+//!
+//! States also convert to and from a fixed [`ENCODED_LEN`]-byte array, which can be used directly
+//! as the input type of GGRS or backroll. See [`InputState`] for the layout.
+
+use crate::input::Input;
+use crate::{Axis, Button, Gamepad};
+
+/// The number of analog inputs an [`InputState`] has room for.
+pub const MAX_AXES: usize = 8;
+
+/// The number of bytes an [`InputState`] is encoded in.
+pub const ENCODED_LEN: usize = 4 + MAX_AXES;
+
+const _: () = assert!(Button::COUNT <= u32::BITS as usize);
+const _: () = assert!(Axis::ALL.len() <= MAX_AXES);
+
+/// The input of a gamepad during a single frame, created with [`Gamepad::snapshot`].
+///
+/// Buttons are stored as a bitmask, and analog inputs are quantized to `i8`, so a state is small
+/// and cheap to copy, compare and send over the network. The default state has nothing pressed
+/// and every analog input at rest.
+///
+/// # Encoding
+///
+/// A state converts to and from `[u8; ENCODED_LEN]` with [`From`]. The bytes are laid out the
+/// same on every platform, and the layout only changes with a breaking release:
+///
+/// - Bytes 0 to 3 are the pressed buttons, as a little-endian bitmask with the bit of each button
+///   at its [`Input::index`].
+/// - Bytes 4 to 11 are the analog inputs in the order of [`Input::ALL`], each quantized to a
+///   two's complement `i8` from `-127` to `127`. Slots past the last axis are zero.
+///
+/// Every backend reports the same axes, so peers can read gamepads with different backends.
+/// Decoding clears the bits and slots of inputs that do not exist, so every array decodes to a
+/// valid state, and decoding an encoded state gives it back unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputState {
+    buttons: u32,
+    axes: [i8; MAX_AXES],
+}
+
+impl InputState {
+    /// Captures the current input of a gamepad, with the analog deadzone applied.
+    pub fn new(gamepad: &Gamepad) -> Self {
+        let mut state = Self::default();
+        for &button in Button::ALL {
+            if gamepad.digital_inputs.activated(button) {
+                state.buttons |= 1 << button.index();
+            }
+        }
+        for (slot, &axis) in state.axes.iter_mut().zip(Axis::ALL) {
+            *slot = quantize(gamepad.analog_inputs.value(axis));
+        }
+        state
+    }
+
+    /// Checks if a button is pressed.
+    pub fn pressed(&self, button: Button) -> bool {
+        self.buttons & (1 << button.index()) != 0
+    }
+
+    /// Gets the value of an axis, in steps of 1/127.
+    pub fn axis(&self, axis: Axis) -> f32 {
+        f32::from(self.axes[axis_slot(axis)]) / 127.0
+    }
+
+    /// Iterates over the pressed buttons.
+    pub fn pressed_buttons(&self) -> impl Iterator<Item = Button> + '_ {
+        Button::ALL
+            .iter()
+            .copied()
+            .filter(|&button| self.pressed(button))
+    }
+
+    /// Compares this state with the state of the previous frame.
+    pub fn diff(&self, previous: &Self) -> InputDiff {
+        let mut axes = 0;
+        for (index, (current, previous)) in self.axes.iter().zip(&previous.axes).enumerate() {
+            if current != previous {
+                axes |= 1 << index;
+            }
+        }
+
+        InputDiff {
+            pressed: self.buttons & !previous.buttons,
+            released: previous.buttons & !self.buttons,
+            axes,
+        }
+    }
+}
+
+impl From<InputState> for [u8; ENCODED_LEN] {
+    fn from(state: InputState) -> Self {
+        let mut bytes = [0; ENCODED_LEN];
+        bytes[..4].copy_from_slice(&state.buttons.to_le_bytes());
+        for (byte, &axis) in bytes[4..].iter_mut().zip(&state.axes) {
+            *byte = axis.to_le_bytes()[0];
+        }
+        bytes
+    }
+}
+
+impl From<[u8; ENCODED_LEN]> for InputState {
+    fn from(bytes: [u8; ENCODED_LEN]) -> Self {
+        let buttons = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let mut axes = [0; MAX_AXES];
+        for (axis, &byte) in axes.iter_mut().zip(&bytes[4..]).take(Axis::ALL.len()) {
+            // `-128` is not a quantized value, so it is read as `-127`.
+            *axis = i8::from_le_bytes([byte]).max(-127);
+        }
+        Self {
+            buttons: buttons & BUTTON_MASK,
+            axes,
+        }
+    }
+}
+
+/// The bits of the buttons that exist.
+const BUTTON_MASK: u32 = u32::MAX >> (u32::BITS as usize - Button::COUNT);
+
+/// The changes between two [`InputState`]s, created with [`InputState::diff`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct InputDiff {
+    pressed: u32,
+    released: u32,
+    axes: u8,
+}
+
+impl InputDiff {
+    /// Checks if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.pressed == 0 && self.released == 0 && self.axes == 0
+    }
+
+    /// Checks if a button was pressed.
+    pub fn just_pressed(&self, button: Button) -> bool {
+        self.pressed & (1 << button.index()) != 0
+    }
+
+    /// Checks if a button was released.
+    pub fn just_released(&self, button: Button) -> bool {
+        self.released & (1 << button.index()) != 0
+    }
+
+    /// Checks if the quantized value of an axis changed.
+    pub fn axis_changed(&self, axis: Axis) -> bool {
+        self.axes & (1 << axis_slot(axis)) != 0
+    }
+}
+
+/// Gets the position of an axis in a state, which is its position in [`Input::ALL`].
+fn axis_slot(axis: Axis) -> usize {
+    Axis::ALL
+        .iter()
+        .position(|&other| other == axis)
+        .unwrap_or_default()
+}
+
+fn quantize(value: f32) -> i8 {
+    (value.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
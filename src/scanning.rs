@@ -0,0 +1,131 @@
+//! Single-switch scanning, for players who can only operate one or two buttons.
+//!
+//! A [`Scanner`] highlights one item of a list at a time. The player moves the highlight with a
+//! scan button and activates the highlighted item with a select button, or by holding the scan
+//! button down. The UI shows the highlight by reacting to each [`ScanEvent`].
+
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// A change reported by [`Scanner::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanEvent {
+    /// The item at this index has been highlighted.
+    Highlighted(usize),
+    /// The item at this index has been activated.
+    Activated(usize),
+}
+
+/// Cycles a highlight through a list of items, such as the actions available to the player.
+#[derive(Debug, Clone)]
+pub struct Scanner<A, B> {
+    items: Vec<A>,
+    highlighted: usize,
+    scan: B,
+    select: Option<B>,
+    long_press: Option<Duration>,
+    press_time: Option<Duration>,
+    long_pressed: bool,
+}
+
+impl<A, B> Scanner<A, B>
+where
+    B: Input,
+{
+    /// Creates a scanner over the given items, highlighting the first one.
+    ///
+    /// Pressing `scan` moves the highlight to the next item, wrapping around at the end.
+    pub fn new(items: Vec<A>, scan: B) -> Self {
+        Self {
+            items,
+            highlighted: 0,
+            scan,
+            select: None,
+            long_press: None,
+            press_time: None,
+            long_pressed: false,
+        }
+    }
+
+    /// Activates the highlighted item when `select` is pressed.
+    pub fn with_select(mut self, select: B) -> Self {
+        self.select = Some(select);
+        self
+    }
+
+    /// Activates the highlighted item when the scan button is held for the given duration.
+    ///
+    /// The highlight then moves when the scan button is released early, rather than when it is
+    /// pressed.
+    pub fn with_long_press(mut self, duration: Duration) -> Self {
+        self.long_press = Some(duration);
+        self
+    }
+
+    /// Gets the items.
+    pub fn items(&self) -> &[A] {
+        &self.items
+    }
+
+    /// Gets the index of the highlighted item.
+    pub fn highlighted(&self) -> usize {
+        self.highlighted
+    }
+
+    /// Gets the highlighted item, or `None` if there are no items.
+    pub fn highlighted_item(&self) -> Option<&A> {
+        self.items.get(self.highlighted)
+    }
+
+    /// Advances the scanner from the state of the player's buttons.
+    ///
+    /// Call this once per update, passing the time of the update.
+    pub fn update(&mut self, inputs: &DigitalInput<B>, time: Duration) -> Option<ScanEvent> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        if let Some(select) = self.select {
+            if inputs.just_activated(select) {
+                return Some(ScanEvent::Activated(self.highlighted));
+            }
+        }
+
+        let long_press = match self.long_press {
+            Some(long_press) => long_press,
+            None if inputs.just_activated(self.scan) => return Some(self.advance()),
+            None => return None,
+        };
+
+        if inputs.just_activated(self.scan) {
+            self.press_time = Some(time);
+            self.long_pressed = false;
+        }
+
+        match self.press_time {
+            Some(press_time) if inputs.activated(self.scan) => {
+                if !self.long_pressed && time.saturating_sub(press_time) >= long_press {
+                    self.long_pressed = true;
+                    return Some(ScanEvent::Activated(self.highlighted));
+                }
+                None
+            }
+            Some(_) => {
+                self.press_time = None;
+                if self.long_pressed {
+                    None
+                } else {
+                    Some(self.advance())
+                }
+            }
+            None => None,
+        }
+    }
+
+    fn advance(&mut self) -> ScanEvent {
+        self.highlighted = (self.highlighted + 1) % self.items.len();
+        ScanEvent::Highlighted(self.highlighted)
+    }
+}
@@ -0,0 +1,254 @@
+//! A fixed number of player slots, filled by gamepads as they connect or join.
+//!
+//! [`PlayerSlots`] suits local multiplayer games with numbered players. Slots are filled either
+//! as soon as a gamepad connects, or when a button is pressed on it, depending on the
+//! [`JoinMode`]. When a gamepad disconnects, its slot is held for a while, and the same gamepad
+//! reconnecting in that time takes the slot back, so a loose cable or a controller going to sleep
+//! does not shuffle the players around. Gamepads the context recognizes as reconnecting, see
+//! [`Gamepad::reconnected_from`](crate::Gamepad::reconnected_from), get their own slot back, and
+//! other gamepads the slot of a gamepad of the same name.
+//!
+//! Gamepads with player LEDs, such as Xbox and Switch controllers, show the number of the slot
+//! they are in, see [`GamepadContext::set_player_index`].
+
+use crate::{Button, GamepadContext, GamepadEvent, GamepadId};
+use std::time::Duration;
+
+/// How long a slot is held for a disconnected gamepad, unless set otherwise.
+pub const DEFAULT_RECONNECT_GRACE: Duration = Duration::from_secs(10);
+
+/// How gamepads without a slot take one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinMode {
+    /// Every connected gamepad takes the first free slot.
+    Automatic,
+    /// A gamepad takes the first free slot when the button is pressed on it, as in "press A to
+    /// join".
+    Press(Button),
+}
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Free,
+    Occupied {
+        gamepad: GamepadId,
+        name: String,
+    },
+    /// Held for a gamepad that disconnected at `since`.
+    Reserved {
+        gamepad: GamepadId,
+        name: String,
+        since: Duration,
+    },
+}
+
+/// Assigns gamepads to a fixed number of numbered player slots.
+///
+/// Players are numbered from 0. Call [`update`](Self::update) once per frame, after
+/// [`GamepadContext::update`].
+#[derive(Debug, Clone)]
+pub struct PlayerSlots {
+    slots: Vec<Slot>,
+    join_mode: JoinMode,
+    reconnect_grace: Duration,
+    player_leds: bool,
+}
+
+impl PlayerSlots {
+    /// Creates `count` free slots, filled automatically as gamepads connect.
+    pub fn new(count: usize) -> Self {
+        Self {
+            slots: vec![Slot::Free; count],
+            join_mode: JoinMode::Automatic,
+            reconnect_grace: DEFAULT_RECONNECT_GRACE,
+            player_leds: true,
+        }
+    }
+
+    /// Sets how gamepads take a slot.
+    pub fn set_join_mode(&mut self, join_mode: JoinMode) {
+        self.join_mode = join_mode;
+    }
+
+    /// Sets how long the slot of a disconnected gamepad is held for it to reconnect.
+    ///
+    /// The default is [`DEFAULT_RECONNECT_GRACE`].
+    pub fn set_reconnect_grace(&mut self, grace: Duration) {
+        self.reconnect_grace = grace;
+    }
+
+    /// Sets whether gamepads show the number of their slot on their player LEDs, which is the
+    /// default.
+    ///
+    /// While enabled, the slots own the player indices of all gamepads, and clear them on
+    /// gamepads without a slot.
+    pub fn set_player_leds(&mut self, enabled: bool) {
+        self.player_leds = enabled;
+    }
+
+    /// Gets the number of slots.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Checks if there are no slots at all.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Gets the gamepad in a player's slot.
+    pub fn gamepad(&self, player: usize) -> Option<GamepadId> {
+        match self.slots.get(player) {
+            Some(&Slot::Occupied { gamepad, .. }) => Some(gamepad),
+            _ => None,
+        }
+    }
+
+    /// Gets the player whose slot a gamepad is in.
+    pub fn player(&self, gamepad: GamepadId) -> Option<usize> {
+        self.slots.iter().position(
+            |slot| matches!(*slot, Slot::Occupied { gamepad: other, .. } if other == gamepad),
+        )
+    }
+
+    /// Checks if a player's slot is held for their gamepad to reconnect.
+    pub fn is_reserved(&self, player: usize) -> bool {
+        matches!(self.slots.get(player), Some(Slot::Reserved { .. }))
+    }
+
+    /// Checks if a player's slot is free to be taken.
+    pub fn is_free(&self, player: usize) -> bool {
+        matches!(self.slots.get(player), Some(Slot::Free))
+    }
+
+    /// Puts a gamepad in a player's slot, such as from a lobby screen, moving it out of any other
+    /// slot.
+    ///
+    /// Does nothing if the player or gamepad does not exist.
+    pub fn assign(&mut self, context: &mut GamepadContext, player: usize, gamepad: GamepadId) {
+        let Some(name) = context
+            .gamepad(gamepad)
+            .map(|gamepad| gamepad.name().to_owned())
+        else {
+            return;
+        };
+        if player >= self.slots.len() {
+            return;
+        }
+
+        if let Some(previous) = self.player(gamepad) {
+            self.slots[previous] = Slot::Free;
+        }
+        self.slots[player] = Slot::Occupied { gamepad, name };
+        self.show_player_indices(context);
+    }
+
+    /// Frees a player's slot, whether a gamepad is in it or it is held for one.
+    pub fn release(&mut self, player: usize) {
+        if let Some(slot) = self.slots.get_mut(player) {
+            *slot = Slot::Free;
+        }
+    }
+
+    /// Updates the slots from the gamepads that connected, disconnected or joined during the
+    /// most recent update of the context.
+    pub fn update(&mut self, context: &mut GamepadContext) {
+        let time = context.time();
+        for slot in &mut self.slots {
+            if let Slot::Reserved { since, .. } = *slot {
+                if time.saturating_sub(since) >= self.reconnect_grace {
+                    *slot = Slot::Free;
+                }
+            }
+        }
+
+        for event in context.events() {
+            match event {
+                GamepadEvent::Disconnected(id) => {
+                    if let Some(player) = self.player(id) {
+                        let Slot::Occupied { name, .. } = &mut self.slots[player] else {
+                            continue;
+                        };
+                        let name = std::mem::take(name);
+                        self.slots[player] = Slot::Reserved {
+                            gamepad: id,
+                            name,
+                            since: time,
+                        };
+                    }
+                }
+                GamepadEvent::Connected(id) => {
+                    let Some(gamepad) = context.gamepad(id) else {
+                        continue;
+                    };
+                    // The context recognizes reconnecting gamepads by GUID, which tells apart
+                    // gamepads of the same name, so that match is preferred.
+                    let reserved = gamepad
+                        .reconnected_from()
+                        .and_then(|previous| {
+                            self.slots.iter().position(|slot| {
+                                matches!(slot, Slot::Reserved { gamepad, .. } if *gamepad == previous)
+                            })
+                        })
+                        .or_else(|| {
+                            self.slots.iter().position(|slot| {
+                                matches!(slot, Slot::Reserved { name, .. } if name == gamepad.name())
+                            })
+                        });
+                    if let Some(player) = reserved {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(?id, player, "gamepad reconnected to its slot");
+                        self.slots[player] = Slot::Occupied {
+                            gamepad: id,
+                            name: gamepad.name().to_owned(),
+                        };
+                    }
+                }
+            }
+        }
+
+        for (id, gamepad) in context.gamepads() {
+            if self.player(id).is_some() {
+                continue;
+            }
+            let joins = match self.join_mode {
+                JoinMode::Automatic => true,
+                JoinMode::Press(button) => gamepad.digital_inputs.just_activated(button),
+            };
+            let free = self
+                .slots
+                .iter()
+                .position(|slot| matches!(slot, Slot::Free));
+            if let (true, Some(player)) = (joins, free) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?id, player, "gamepad took a player slot");
+                self.slots[player] = Slot::Occupied {
+                    gamepad: id,
+                    name: gamepad.name().to_owned(),
+                };
+            }
+        }
+
+        self.show_player_indices(context);
+    }
+
+    /// Sets the player index of every gamepad whose slot changed, if player LEDs are enabled.
+    fn show_player_indices(&self, context: &mut GamepadContext) {
+        if !self.player_leds {
+            return;
+        }
+        let changed: Vec<_> = context
+            .gamepads()
+            .filter_map(|(id, gamepad)| {
+                let index = self.player(id).and_then(|player| u8::try_from(player).ok());
+                (gamepad.player_index() != index).then_some((id, index))
+            })
+            .collect();
+        for (id, index) in changed {
+            if let Err(_error) = context.set_player_index(id, index) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?id, error = %_error, "failed to set player index");
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Consistent snapshots of gamepad state.
+
+use crate::analog::AnalogInput;
+use crate::arena::GamepadMap;
+use crate::digital::DigitalInput;
+use crate::{Axis, Button, Gamepad, GamepadId};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, Weak};
+
+/// A handle to an input view, created with [`GamepadContext::create_view`].
+///
+/// Each view tracks its own `just_*` changes, which accumulate until the view is consumed with
+/// [`GamepadContext::consume_view`]. This lets several systems, such as the UI and gameplay,
+/// process the same input without interfering with each other.
+///
+/// [`GamepadContext::create_view`]: crate::GamepadContext::create_view
+/// [`GamepadContext::consume_view`]: crate::GamepadContext::consume_view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewId(pub(crate) usize);
+
+/// The input state of a single gamepad, captured at a point in time.
+#[derive(Debug)]
+pub struct GamepadSnapshot {
+    /// Analog inputs, such as thumbsticks.
+    pub analog_inputs: AnalogInput<Axis>,
+    /// Digital inputs, such as buttons.
+    pub digital_inputs: DigitalInput<Button>,
+}
+
+impl Clone for GamepadSnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            analog_inputs: self.analog_inputs.clone(),
+            digital_inputs: self.digital_inputs.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.analog_inputs.clone_from(&source.analog_inputs);
+        self.digital_inputs.clone_from(&source.digital_inputs);
+    }
+}
+
+impl GamepadSnapshot {
+    fn new(gamepad: &Gamepad) -> Self {
+        Self {
+            analog_inputs: gamepad.analog_inputs.clone(),
+            digital_inputs: gamepad.digital_inputs.clone(),
+        }
+    }
+
+    fn accumulate(&mut self, gamepad: &Gamepad) {
+        self.analog_inputs.accumulate(&gamepad.analog_inputs);
+        self.digital_inputs.accumulate(&gamepad.digital_inputs);
+    }
+
+    fn clear(&mut self) {
+        self.analog_inputs.clear();
+        self.digital_inputs.clear_changes();
+    }
+}
+
+/// The input state of all gamepads, captured at a point in time.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    gamepads: GamepadMap<GamepadSnapshot>,
+}
+
+// Cloning is written out so that `clone_from` reuses the storage of every gamepad, which keeps
+// `GamepadContext::tick` free of allocations.
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        Self {
+            gamepads: self.gamepads.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.gamepads.clone_from(&source.gamepads);
+    }
+}
+
+impl Snapshot {
+    /// Gets a reference to a specific gamepad.
+    ///
+    /// Returns `None` if the gamepad was not connected when the snapshot was taken.
+    pub fn gamepad(&self, id: GamepadId) -> Option<&GamepadSnapshot> {
+        self.gamepads.get(id)
+    }
+
+    /// Gets an iterator over all gamepads, in the order they connected.
+    pub fn gamepads(&self) -> impl Iterator<Item = (GamepadId, &GamepadSnapshot)> {
+        self.gamepads.iter()
+    }
+
+    /// Folds the current state of `gamepads` into the snapshot.
+    ///
+    /// Values are replaced, while the `just_*` changes are merged with those
+    /// that have been recorded since the snapshot was last cleared.
+    pub(crate) fn accumulate(&mut self, gamepads: &GamepadMap<Gamepad>) {
+        self.gamepads.retain(|id, _| gamepads.contains_key(id));
+        for (id, gamepad) in gamepads {
+            match self.gamepads.get_mut(id) {
+                Some(snapshot) => snapshot.accumulate(gamepad),
+                None => {
+                    self.gamepads.insert(id, GamepadSnapshot::new(gamepad));
+                }
+            }
+        }
+    }
+
+    /// Clears all recorded `just_*` changes, keeping the current values.
+    pub(crate) fn clear(&mut self) {
+        for snapshot in self.gamepads.values_mut() {
+            snapshot.clear();
+        }
+    }
+}
+
+/// A handle to the state of all gamepads that can be read from other threads, created with
+/// [`GamepadContext::shared_state`].
+///
+/// The context itself stays on the thread that created it, since the SDL2 backend is bound to
+/// that thread; every update publishes the new state to its shared handles. Clones of a handle
+/// are cheap and read the same state, while each call to `shared_state` creates a separate state
+/// whose `just_*` changes are consumed independently.
+///
+/// [`GamepadContext::shared_state`]: crate::GamepadContext::shared_state
+#[derive(Debug, Clone, Default)]
+pub struct SharedGamepadState {
+    snapshot: Arc<RwLock<Snapshot>>,
+}
+
+impl SharedGamepadState {
+    pub(crate) fn new(gamepads: &GamepadMap<Gamepad>) -> Self {
+        let mut snapshot = Snapshot::default();
+        snapshot.accumulate(gamepads);
+        snapshot.clear();
+        Self {
+            snapshot: Arc::new(RwLock::new(snapshot)),
+        }
+    }
+
+    /// Locks the state for reading. Any number of threads can read at once, while updates of the
+    /// context wait for them to finish.
+    ///
+    /// The `just_*` queries reflect every change since the state was last ticked.
+    pub fn read(&self) -> RwLockReadGuard<'_, Snapshot> {
+        self.snapshot.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Copies the state into `snapshot` and clears the recorded changes, so that each change is
+    /// observed once, the same way as [`GamepadContext::tick`].
+    ///
+    /// Reusing the same `snapshot` every tick avoids allocating.
+    ///
+    /// [`GamepadContext::tick`]: crate::GamepadContext::tick
+    pub fn tick(&self, snapshot: &mut Snapshot) {
+        let mut shared = self
+            .snapshot
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        snapshot.clone_from(&shared);
+        shared.clear();
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<RwLock<Snapshot>> {
+        Arc::downgrade(&self.snapshot)
+    }
+
+    /// Folds the current state of `gamepads` into every shared state that still has a handle,
+    /// forgetting the others.
+    pub(crate) fn publish(
+        shared: &mut Vec<Weak<RwLock<Snapshot>>>,
+        gamepads: &GamepadMap<Gamepad>,
+    ) {
+        shared.retain(|snapshot| match snapshot.upgrade() {
+            Some(snapshot) => {
+                let mut snapshot = snapshot.write().unwrap_or_else(PoisonError::into_inner);
+                snapshot.accumulate(gamepads);
+                true
+            }
+            None => false,
+        });
+    }
+}
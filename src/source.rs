@@ -0,0 +1,111 @@
+//! A common interface over everything that produces gamepad-like input.
+
+use crate::analog::AnalogInput;
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use crate::state::GamepadState;
+
+/// A source of player input.
+///
+/// Physical gamepads, captured snapshots, analog keyboards and the scripted drivers of the
+/// `testing` feature all expose the same analog and digital inputs, so code that reads a player's
+/// input can be written once against this trait and backed by any of them.
+pub trait InputSource {
+    /// The analog inputs of this source, such as thumbstick axes.
+    type Axis: Input;
+    /// The digital inputs of this source, such as buttons.
+    type Button: Input;
+
+    /// Gets the analog inputs.
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis>;
+
+    /// Gets the digital inputs.
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button>;
+
+    /// Gets the analog and digital inputs together, behind a single interface.
+    fn state(&self) -> GamepadState<'_, Self::Axis, Self::Button> {
+        GamepadState::new(self.analog_inputs(), self.digital_inputs())
+    }
+}
+
+impl<S: InputSource + ?Sized> InputSource for &S {
+    type Axis = S::Axis;
+    type Button = S::Button;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        (**self).analog_inputs()
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        (**self).digital_inputs()
+    }
+}
+
+impl<A: Input, B: Input> InputSource for crate::keyboard::AnalogKeyboard<A, B> {
+    type Axis = A;
+    type Button = B;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        &self.analog_inputs
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        &self.digital_inputs
+    }
+}
+
+#[cfg(feature = "std")]
+impl InputSource for crate::Gamepad {
+    type Axis = crate::Axis;
+    type Button = crate::Button;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        &self.analog_inputs
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        &self.digital_inputs
+    }
+}
+
+#[cfg(feature = "std")]
+impl InputSource for crate::composite::CompositeGamepad {
+    type Axis = crate::Axis;
+    type Button = crate::Button;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        &self.analog_inputs
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        &self.digital_inputs
+    }
+}
+
+#[cfg(feature = "std")]
+impl InputSource for crate::snapshot::GamepadSnapshot {
+    type Axis = crate::Axis;
+    type Button = crate::Button;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        &self.analog_inputs
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        &self.digital_inputs
+    }
+}
+
+#[cfg(feature = "testing")]
+impl<A: Input, B: Input> InputSource for crate::testing::Driver<A, B> {
+    type Axis = A;
+    type Button = B;
+
+    fn analog_inputs(&self) -> &AnalogInput<Self::Axis> {
+        &self.analog_inputs
+    }
+
+    fn digital_inputs(&self) -> &DigitalInput<Self::Button> {
+        &self.digital_inputs
+    }
+}
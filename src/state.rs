@@ -0,0 +1,121 @@
+//! A combined view of the analog and digital inputs of a source.
+
+use crate::analog::AnalogInput;
+use crate::digital::DigitalInput;
+use crate::input::Input;
+
+/// Either an analog or a digital input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnyInput<A, B> {
+    /// An analog input, such as a thumbstick axis.
+    Axis(A),
+    /// A digital input, such as a button.
+    Button(B),
+}
+
+/// The state of a gamepad, or any other [`InputSource`](crate::source::InputSource), with
+/// buttons and axes behind one interface.
+///
+/// Axis values have the analog deadzone applied, and an axis counts as pressed while it is
+/// outside the digital deadzone, so an axis can stand in wherever a button is expected. Every
+/// query works the same for [`AnyInput::Axis`] and [`AnyInput::Button`].
+///
+/// Created with [`InputSource::state`](crate::source::InputSource::state).
+#[derive(Debug)]
+pub struct GamepadState<'a, A, B> {
+    analog_inputs: &'a AnalogInput<A>,
+    digital_inputs: &'a DigitalInput<B>,
+}
+
+impl<A, B> Clone for GamepadState<'_, A, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, B> Copy for GamepadState<'_, A, B> {}
+
+impl<'a, A, B> GamepadState<'a, A, B>
+where
+    A: Input,
+    B: Input,
+{
+    pub(crate) fn new(
+        analog_inputs: &'a AnalogInput<A>,
+        digital_inputs: &'a DigitalInput<B>,
+    ) -> Self {
+        Self {
+            analog_inputs,
+            digital_inputs,
+        }
+    }
+
+    /// Gets the value of an input: `0.0` or `1.0` for a button, and the value after the analog
+    /// deadzone for an axis.
+    pub fn value(&self, input: AnyInput<A, B>) -> f32 {
+        match input {
+            AnyInput::Axis(axis) => self.analog_inputs.value(axis),
+            AnyInput::Button(button) => {
+                if self.digital_inputs.activated(button) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Checks if an input is pressed. An axis is pressed while outside the digital deadzone.
+    pub fn pressed(&self, input: AnyInput<A, B>) -> bool {
+        match input {
+            AnyInput::Axis(axis) => self.analog_inputs.digital_value(axis) != 0.0,
+            AnyInput::Button(button) => self.digital_inputs.activated(button),
+        }
+    }
+
+    /// Checks if an input was pressed during the most recent update.
+    pub fn just_pressed(&self, input: AnyInput<A, B>) -> bool {
+        match input {
+            AnyInput::Axis(axis) => self.analog_inputs.just_activated_digital(axis).is_some(),
+            AnyInput::Button(button) => self.digital_inputs.just_activated(button),
+        }
+    }
+
+    /// Checks if an input was released during the most recent update.
+    pub fn just_released(&self, input: AnyInput<A, B>) -> bool {
+        match input {
+            AnyInput::Axis(axis) => self.analog_inputs.just_deactivated_digital(axis),
+            AnyInput::Button(button) => self.digital_inputs.just_deactivated(button),
+        }
+    }
+
+    /// Gets the value of an axis after the analog deadzone.
+    pub fn axis(&self, axis: A) -> f32 {
+        self.value(AnyInput::Axis(axis))
+    }
+
+    /// Checks if a button is pressed.
+    pub fn button(&self, button: B) -> bool {
+        self.pressed(AnyInput::Button(button))
+    }
+
+    /// Iterates over every pressed input, buttons first.
+    pub fn pressed_inputs(&self) -> impl Iterator<Item = AnyInput<A, B>> + 'a {
+        let state = *self;
+        let buttons = B::ALL.iter().map(|&button| AnyInput::Button(button));
+        let axes = A::ALL.iter().map(|&axis| AnyInput::Axis(axis));
+        buttons
+            .chain(axes)
+            .filter(move |&input| state.pressed(input))
+    }
+
+    /// Gets the underlying analog inputs.
+    pub fn analog_inputs(&self) -> &'a AnalogInput<A> {
+        self.analog_inputs
+    }
+
+    /// Gets the underlying digital inputs.
+    pub fn digital_inputs(&self) -> &'a DigitalInput<B> {
+        self.digital_inputs
+    }
+}
@@ -0,0 +1,187 @@
+//! Helpers for property-testing input handling.
+//!
+//! A [`Generator`] produces random but valid sequences of [`Step`]s, and a [`Driver`] feeds them
+//! into the same state tracking a [`GamepadContext`](crate::GamepadContext) uses,
+//! without needing a backend or a physical gamepad.
+
+use crate::analog::{AnalogInput, AnalogInputValue, Sample};
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// A single step of simulated input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step<A, B> {
+    /// Moves an analog input to a value.
+    Axis(A, f32),
+    /// Activates a digital input.
+    Press(B),
+    /// Deactivates a digital input.
+    Release(B),
+    /// Ends the current update.
+    Update,
+}
+
+/// Generator of random but valid input sequences.
+///
+/// Digital inputs are only pressed while released and only released while pressed,
+/// and analog values are biased towards the edge cases around the default deadzones.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    /// Creates a generator from a seed. The same seed always produces the same sequences.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            // Xorshift must not be seeded with zero.
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn value(&mut self) -> f32 {
+        const INTERESTING: [f32; 9] = [0.0, 0.099, 0.1, 0.101, 0.499, 0.5, 0.501, 0.9, 1.0];
+
+        let magnitude = if self.below(2) == 0 {
+            INTERESTING[self.below(INTERESTING.len())]
+        } else {
+            (self.next() >> 40) as f32 / (1u64 << 24) as f32
+        };
+
+        if self.below(2) == 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Generates a sequence of `len` steps.
+    pub fn sequence<A, B>(&mut self, len: usize) -> Vec<Step<A, B>>
+    where
+        A: Input,
+        B: Input,
+    {
+        let mut steps = Vec::with_capacity(len);
+        let mut pressed = Vec::new();
+
+        while steps.len() < len {
+            let step = match self.below(4) {
+                0 if !A::ALL.is_empty() => {
+                    let axis = A::ALL[self.below(A::ALL.len())];
+                    Step::Axis(axis, self.value())
+                }
+                1 if !B::ALL.is_empty() => {
+                    let button = B::ALL[self.below(B::ALL.len())];
+                    if let Some(index) = pressed.iter().position(|&pressed| pressed == button) {
+                        pressed.swap_remove(index);
+                        Step::Release(button)
+                    } else {
+                        pressed.push(button);
+                        Step::Press(button)
+                    }
+                }
+                _ => Step::Update,
+            };
+            steps.push(step);
+        }
+
+        steps
+    }
+
+    /// Generates an analog input passing completely through the deadzone between two updates.
+    pub fn pass_through<A, B>(&mut self, axis: A) -> Vec<Step<A, B>>
+    where
+        A: Copy,
+    {
+        let value = self.value().abs().max(0.9);
+        alloc::vec![
+            Step::Axis(axis, value),
+            Step::Update,
+            Step::Axis(axis, -value),
+            Step::Update,
+        ]
+    }
+
+    /// Generates a digital input being pressed and released within the same update.
+    pub fn tap<A, B>(button: B) -> Vec<Step<A, B>>
+    where
+        B: Copy,
+    {
+        alloc::vec![Step::Press(button), Step::Release(button), Step::Update]
+    }
+}
+
+/// Feeds steps into input state, one update at a time.
+#[derive(Debug, Clone)]
+pub struct Driver<A, B> {
+    /// Analog inputs, such as thumbsticks.
+    pub analog_inputs: AnalogInput<A>,
+    /// Digital inputs, such as buttons.
+    pub digital_inputs: DigitalInput<B>,
+    time: Duration,
+}
+
+impl<A, B> Driver<A, B>
+where
+    A: Input,
+    B: Input,
+{
+    /// Creates a driver with no inputs read yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs a sequence of steps, calling `check` at the end of every update.
+    ///
+    /// The `just_*` queries seen by `check` reflect the steps since the previous update.
+    pub fn run(
+        &mut self,
+        steps: impl IntoIterator<Item = Step<A, B>>,
+        mut check: impl FnMut(&Self),
+    ) {
+        for step in steps {
+            self.time += Duration::from_millis(1);
+            match step {
+                Step::Axis(axis, value) => {
+                    let sample = Sample::new(AnalogInputValue::from(value), self.time);
+                    self.analog_inputs.set(axis, sample);
+                }
+                Step::Press(button) => self.digital_inputs.activate_at(button, self.time),
+                Step::Release(button) => self.digital_inputs.deactivate_at(button, self.time),
+                Step::Update => {
+                    self.analog_inputs.flush();
+                    check(self);
+                    self.analog_inputs.update(self.time);
+                    self.digital_inputs.update(self.time);
+                }
+            }
+        }
+    }
+}
+
+impl<A, B> Default for Driver<A, B>
+where
+    A: Input,
+    B: Input,
+{
+    fn default() -> Self {
+        Self {
+            analog_inputs: Default::default(),
+            digital_inputs: Default::default(),
+            time: Duration::ZERO,
+        }
+    }
+}
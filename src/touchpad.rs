@@ -0,0 +1,95 @@
+//! The touchpads of gamepads such as the DualShock 4 and DualSense, and the trackpads of the
+//! Steam Deck.
+//!
+//! Fingers are read from the backend on every update, and are available through
+//! [`Gamepad::touchpad`](crate::Gamepad::touchpad), or
+//! [`Gamepad::touchpads`](crate::Gamepad::touchpads) for gamepads with several. The physical click
+//! of the first touchpad is the [`Button::Touchpad`] button, and the backend reports the clicks of
+//! the others along with their fingers.
+//!
+//! The Steam Deck reports its left trackpad as the first touchpad and its right trackpad as the
+//! second.
+
+use crate::digital::DigitalInput;
+use crate::Button;
+
+/// A finger touching a touchpad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchFinger {
+    /// The slot the backend tracks the finger in, which stays the same while the finger is down.
+    pub slot: usize,
+    /// The horizontal position, from `0.0` at the left edge to `1.0` at the right edge.
+    pub x: f32,
+    /// The vertical position, from `0.0` at the top edge to `1.0` at the bottom edge.
+    pub y: f32,
+    /// How firmly the finger presses, from `0.0` to `1.0`.
+    pub pressure: f32,
+}
+
+/// The touchpads of a gamepad, and what was last read from them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TouchpadState {
+    pub(crate) pads: Vec<PadState>,
+}
+
+/// A single touchpad of a gamepad.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PadState {
+    // The fingers that are down, by slot.
+    pub(crate) fingers: Vec<TouchFinger>,
+    // Whether the touchpad is clicked down. Unused for the first touchpad, whose click is
+    // `Button::Touchpad`.
+    pub(crate) clicked: bool,
+}
+
+/// The touchpad of a gamepad.
+///
+/// Created with [`Gamepad::touchpad`](crate::Gamepad::touchpad).
+#[derive(Debug, Clone, Copy)]
+pub struct Touchpad<'a> {
+    index: usize,
+    state: &'a PadState,
+    digital_inputs: &'a DigitalInput<Button>,
+}
+
+impl<'a> Touchpad<'a> {
+    pub(crate) fn new(
+        index: usize,
+        state: &'a PadState,
+        digital_inputs: &'a DigitalInput<Button>,
+    ) -> Self {
+        Self {
+            index,
+            state,
+            digital_inputs,
+        }
+    }
+
+    /// Gets the index of the touchpad among those of the gamepad.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the number of fingers touching the touchpad.
+    pub fn finger_count(&self) -> usize {
+        self.state.fingers.len()
+    }
+
+    /// Gets an iterator over the fingers touching the touchpad, by slot.
+    pub fn fingers(&self) -> impl Iterator<Item = TouchFinger> + 'a {
+        self.state.fingers.iter().copied()
+    }
+
+    /// Gets the finger in a slot, if one is touching the touchpad there.
+    pub fn finger(&self, slot: usize) -> Option<TouchFinger> {
+        self.fingers().find(|finger| finger.slot == slot)
+    }
+
+    /// Checks if the touchpad is clicked down.
+    pub fn pressed(&self) -> bool {
+        match self.index {
+            0 => self.digital_inputs.activated(Button::Touchpad),
+            _ => self.state.clicked,
+        }
+    }
+}
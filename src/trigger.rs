@@ -0,0 +1,127 @@
+//! Analog triggers, with values from `0.0` when released to `1.0` when fully pressed.
+//!
+//! Triggers are axes that only move one way. Every backend reports them in `[0.0, ANALOG_MAX]`,
+//! converting from the full range where the native API rests them at `ANALOG_MIN`, so they can
+//! also be read as plain axes. [`TriggerInput`] reads them by [`Trigger`] rather than by axis,
+//! and counts a trigger as pressed while it is outside its digital deadzone.
+
+use crate::analog::AnalogInput;
+use crate::Axis;
+
+/// One of the two analog triggers of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Trigger {
+    /// The left trigger.
+    Left,
+    /// The right trigger.
+    Right,
+}
+
+impl Trigger {
+    /// Both triggers.
+    pub const ALL: [Trigger; 2] = [Trigger::Left, Trigger::Right];
+
+    /// Gets the axis the trigger is reported as.
+    pub fn axis(self) -> Option<Axis> {
+        Some(match self {
+            Trigger::Left => Axis::TriggerLeft,
+            Trigger::Right => Axis::TriggerRight,
+        })
+    }
+}
+
+/// A force feedback effect of an adaptive trigger, such as those of the DualSense.
+///
+/// Positions are fractions of the trigger's travel, from `0.0` when released to `1.0` when
+/// fully pressed, and strengths range from `0.0` to `1.0`. Set with
+/// [`GamepadContext::set_trigger_effect`](crate::GamepadContext::set_trigger_effect).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriggerEffect {
+    /// No effect; the trigger moves freely.
+    #[default]
+    Off,
+    /// Constant resistance from a position to the end of the trigger's travel, such as for
+    /// aiming down sights or drawing a bow.
+    Resistance {
+        /// Where the resistance begins.
+        start: f32,
+        /// How hard the trigger pushes back.
+        strength: f32,
+    },
+    /// Resistance between two positions that gives way with a click past the end, like the
+    /// trigger of a gun.
+    Weapon {
+        /// Where the resistance begins.
+        start: f32,
+        /// Where the trigger breaks through.
+        end: f32,
+        /// How hard the trigger pushes back before breaking through.
+        strength: f32,
+    },
+    /// Vibration of the trigger from a position to the end of its travel, such as for automatic
+    /// fire or a rough road.
+    Vibration {
+        /// Where the vibration begins.
+        start: f32,
+        /// How strongly the trigger vibrates.
+        amplitude: f32,
+        /// How fast the trigger vibrates, in hertz.
+        frequency: u8,
+    },
+}
+
+/// The analog triggers of a gamepad.
+///
+/// Created with [`Gamepad::triggers`](crate::Gamepad::triggers). Triggers use the deadzones of
+/// their axes, which can be set apart from those of the sticks with
+/// [`GamepadContext::set_trigger_deadzones`](crate::GamepadContext::set_trigger_deadzones).
+#[derive(Debug, Clone, Copy)]
+pub struct TriggerInput<'a> {
+    analog_inputs: &'a AnalogInput<Axis>,
+}
+
+impl<'a> TriggerInput<'a> {
+    pub(crate) fn new(analog_inputs: &'a AnalogInput<Axis>) -> Self {
+        Self { analog_inputs }
+    }
+
+    /// Gets how far a trigger is pressed, from `0.0` to `1.0`, after the analog deadzone.
+    pub fn value(&self, trigger: Trigger) -> f32 {
+        trigger
+            .axis()
+            .map_or(0.0, |axis| self.analog_inputs.value(axis).max(0.0))
+    }
+
+    /// Gets how far a trigger is pressed, from `0.0` to `1.0`, before the deadzone is applied.
+    pub fn raw_value(&self, trigger: Trigger) -> f32 {
+        trigger
+            .axis()
+            .map_or(0.0, |axis| self.analog_inputs.raw_value(axis).max(0.0))
+    }
+
+    /// Checks if a trigger is pressed past its digital deadzone.
+    pub fn pressed(&self, trigger: Trigger) -> bool {
+        trigger
+            .axis()
+            .is_some_and(|axis| self.analog_inputs.digital_value(axis) > 0.0)
+    }
+
+    /// Checks if a trigger was pressed past its digital deadzone during the most recent update.
+    pub fn just_pressed(&self, trigger: Trigger) -> bool {
+        trigger.axis().is_some_and(|axis| {
+            self.analog_inputs
+                .just_activated_digital(axis)
+                .is_some_and(|value| value > 0.0)
+        })
+    }
+
+    /// Checks if a trigger was released back into its digital deadzone during the most recent
+    /// update.
+    pub fn just_released(&self, trigger: Trigger) -> bool {
+        trigger
+            .axis()
+            .is_some_and(|axis| self.analog_inputs.just_deactivated_digital(axis))
+    }
+}
@@ -0,0 +1,135 @@
+//! Directions of the sticks and presses of the triggers as digital inputs.
+//!
+//! Every gamepad tracks [`VirtualButton`]s in
+//! [`Gamepad::virtual_buttons`](crate::Gamepad::virtual_buttons), alongside its real buttons, so
+//! that code written for buttons, such as menu navigation, works the same with a stick. A virtual
+//! button is pressed while its axis is pushed its way past the digital deadzone.
+
+use crate::analog::AnalogInput;
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use crate::menu::MenuStick;
+use crate::trigger::Trigger;
+use crate::Axis;
+use std::time::Duration;
+
+/// One of the two thumbsticks of a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Stick {
+    /// The left thumbstick.
+    Left,
+    /// The right thumbstick.
+    Right,
+}
+
+impl Stick {
+    /// Gets the axes the stick is reported as.
+    pub fn axes(self) -> Option<MenuStick> {
+        let (x, y) = match self {
+            Stick::Left => (Axis::LeftX, Axis::LeftY),
+            Stick::Right => (Axis::RightX, Axis::RightY),
+        };
+        Some(MenuStick {
+            x,
+            y,
+            y_points_down: true,
+        })
+    }
+}
+
+/// A direction of a stick or a trigger, read as a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VirtualButton {
+    /// The left stick pushed up.
+    LeftStickUp,
+    /// The left stick pushed down.
+    LeftStickDown,
+    /// The left stick pushed left.
+    LeftStickLeft,
+    /// The left stick pushed right.
+    LeftStickRight,
+    /// The right stick pushed up.
+    RightStickUp,
+    /// The right stick pushed down.
+    RightStickDown,
+    /// The right stick pushed left.
+    RightStickLeft,
+    /// The right stick pushed right.
+    RightStickRight,
+    /// The left trigger pressed.
+    LeftTrigger,
+    /// The right trigger pressed.
+    RightTrigger,
+}
+
+impl VirtualButton {
+    /// Gets the axis behind the button, and whether the button is its positive direction.
+    pub fn axis(self) -> Option<(Axis, bool)> {
+        let stick = |stick: Stick, vertical: bool, up_or_left: bool| {
+            let axes = stick.axes()?;
+            Some(if vertical {
+                (axes.y, up_or_left != axes.y_points_down)
+            } else {
+                (axes.x, !up_or_left)
+            })
+        };
+        match self {
+            VirtualButton::LeftStickUp => stick(Stick::Left, true, true),
+            VirtualButton::LeftStickDown => stick(Stick::Left, true, false),
+            VirtualButton::LeftStickLeft => stick(Stick::Left, false, true),
+            VirtualButton::LeftStickRight => stick(Stick::Left, false, false),
+            VirtualButton::RightStickUp => stick(Stick::Right, true, true),
+            VirtualButton::RightStickDown => stick(Stick::Right, true, false),
+            VirtualButton::RightStickLeft => stick(Stick::Right, false, true),
+            VirtualButton::RightStickRight => stick(Stick::Right, false, false),
+            VirtualButton::LeftTrigger => Trigger::Left.axis().map(|axis| (axis, true)),
+            VirtualButton::RightTrigger => Trigger::Right.axis().map(|axis| (axis, true)),
+        }
+    }
+}
+
+impl Input for VirtualButton {
+    const COUNT: usize = VirtualButton::RightTrigger as usize + 1;
+
+    const ALL: &'static [Self] = &[
+        VirtualButton::LeftStickUp,
+        VirtualButton::LeftStickDown,
+        VirtualButton::LeftStickLeft,
+        VirtualButton::LeftStickRight,
+        VirtualButton::RightStickUp,
+        VirtualButton::RightStickDown,
+        VirtualButton::RightStickLeft,
+        VirtualButton::RightStickRight,
+        VirtualButton::LeftTrigger,
+        VirtualButton::RightTrigger,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Presses and releases the virtual buttons to match the latest update of `analog_inputs`.
+pub(crate) fn update(
+    analog_inputs: &AnalogInput<Axis>,
+    buttons: &mut DigitalInput<VirtualButton>,
+    time: Duration,
+) {
+    for &button in VirtualButton::ALL {
+        let Some((axis, positive)) = button.axis() else {
+            continue;
+        };
+        let value = analog_inputs.digital_value(axis);
+        let pressed = if positive { value > 0.0 } else { value < 0.0 };
+        if pressed == buttons.activated(button) {
+            continue;
+        }
+        if pressed {
+            buttons.activate_at(button, time);
+        } else {
+            buttons.deactivate_at(button, time);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! JavaScript API, for browser-based tools that share input code with the game.
+//!
+//! Gamepads are identified by the integer form of their [`GamepadId`](crate::GamepadId),
+//! and inputs by their [`Input::index`].
+
+use crate::digital::DigitalInput;
+use crate::input::Input;
+use crate::{Axis, Button, GamepadContext};
+use wasm_bindgen::prelude::*;
+
+/// The gamepad system context, exported to JavaScript as `GamepadContext`.
+#[wasm_bindgen(js_name = GamepadContext)]
+pub struct JsGamepadContext(GamepadContext);
+
+#[wasm_bindgen(js_class = GamepadContext)]
+impl JsGamepadContext {
+    /// Initializes the gamepad context.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<JsGamepadContext, JsValue> {
+        GamepadContext::new()
+            .map(Self)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    /// Updates the state of all gamepads.
+    pub fn update(&mut self) -> Result<(), JsValue> {
+        self.0
+            .update()
+            .map_err(|error| JsValue::from_str(&error.to_string()))
+    }
+
+    /// Gets the Ids of all connected gamepads.
+    pub fn gamepads(&self) -> Vec<usize> {
+        self.0.gamepads().map(|(id, _)| id.into()).collect()
+    }
+
+    /// Checks if a button is activated.
+    #[wasm_bindgen(js_name = buttonActivated)]
+    pub fn button_activated(&self, gamepad: usize, button: u32) -> bool {
+        self.button(gamepad, button, |inputs, button| inputs.activated(button))
+    }
+
+    /// Checks if a button has just been activated.
+    #[wasm_bindgen(js_name = buttonJustActivated)]
+    pub fn button_just_activated(&self, gamepad: usize, button: u32) -> bool {
+        self.button(gamepad, button, |inputs, button| {
+            inputs.just_activated(button)
+        })
+    }
+
+    /// Checks if a button has just been deactivated.
+    #[wasm_bindgen(js_name = buttonJustDeactivated)]
+    pub fn button_just_deactivated(&self, gamepad: usize, button: u32) -> bool {
+        self.button(gamepad, button, |inputs, button| {
+            inputs.just_deactivated(button)
+        })
+    }
+
+    /// Gets the value of an axis, with the analog deadzone applied.
+    #[wasm_bindgen(js_name = axisValue)]
+    pub fn axis_value(&self, gamepad: usize, axis: u32) -> f32 {
+        match (
+            self.0.gamepad_by_index(gamepad),
+            Axis::from_index(axis as usize),
+        ) {
+            (Some(gamepad), Some(axis)) => gamepad.analog_inputs.value(axis),
+            _ => 0.0,
+        }
+    }
+
+    /// Sets the analog deadzone for all analog inputs.
+    #[wasm_bindgen(js_name = setDeadzone)]
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.0.set_deadzone(deadzone);
+    }
+}
+
+impl JsGamepadContext {
+    fn button(
+        &self,
+        gamepad: usize,
+        button: u32,
+        f: impl FnOnce(&DigitalInput<Button>, Button) -> bool,
+    ) -> bool {
+        match (
+            self.0.gamepad_by_index(gamepad),
+            Button::from_index(button as usize),
+        ) {
+            (Some(gamepad), Some(button)) => f(&gamepad.digital_inputs, button),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Integration with [winit](https://docs.rs/winit) event loops.
+//!
+//! Event-driven applications that wait for window events would otherwise need a separate timer
+//! to poll for gamepad input. [`GamepadWaker`] instead wakes the event loop at a fixed rate
+//! through an [`EventLoopProxy`], delivering a user event whenever the
+//! [`GamepadContext`](crate::GamepadContext) should be updated.
+//!
+//! The context itself stays on the event loop's thread, since backends such as SDL2 must be
+//! polled from the thread that initialized them.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use winit::event_loop::EventLoopProxy;
+
+/// Background thread that periodically sends a user event to a winit event loop.
+///
+/// The thread stops when the waker is dropped, or when the event loop closes.
+pub struct GamepadWaker {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl GamepadWaker {
+    /// Starts sending `event()` through `proxy` every `interval`.
+    ///
+    /// Handle the event in `ApplicationHandler::user_event` by calling
+    /// [`GamepadContext::update`](crate::GamepadContext::update).
+    pub fn spawn<T>(
+        proxy: EventLoopProxy<T>,
+        interval: Duration,
+        event: impl Fn() -> T + Send + 'static,
+    ) -> Self
+    where
+        T: Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if proxy.send_event(event()).is_err() {
+                        break;
+                    }
+                    thread::sleep(interval);
+                }
+            })
+        };
+
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for GamepadWaker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
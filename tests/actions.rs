@@ -0,0 +1,164 @@
+//! Hold and toggle behavior of actions, driven through the mock backend.
+
+use fishsticks::action::{ActionMap, Binding, HoldMode};
+use fishsticks::source::InputSource;
+use fishsticks::{Button, GamepadContext, GamepadId};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Action {
+    Jump,
+    Aim,
+}
+
+fn setup() -> (GamepadContext, GamepadId, ActionMap<Action>) {
+    let mut context = GamepadContext::new().unwrap();
+    let id = context.mock_backend().connect("Gamepad");
+    context.update().unwrap();
+
+    let mut actions = ActionMap::new();
+    actions.bind(Action::Jump, Binding::button(Button::South));
+    actions.bind(Action::Aim, Binding::button(Button::LeftTrigger));
+    actions.set_hold_actions([Action::Aim]);
+    (context, id, actions)
+}
+
+fn step(context: &mut GamepadContext, id: GamepadId, actions: &mut ActionMap<Action>) {
+    context.update().unwrap();
+    actions.update([context.gamepad(id).unwrap().state()]);
+}
+
+fn tap(
+    context: &mut GamepadContext,
+    id: GamepadId,
+    actions: &mut ActionMap<Action>,
+    button: Button,
+) {
+    context.mock_backend().press(id, button);
+    step(context, id, actions);
+    context.mock_backend().release(id, button);
+    step(context, id, actions);
+}
+
+#[test]
+fn hold_actions_are_held_without_sticky_hold() {
+    let (mut context, id, mut actions) = setup();
+    tap(&mut context, id, &mut actions, Button::LeftTrigger);
+    assert!(!actions.pressed(Action::Aim));
+    assert!(actions.just_released(Action::Aim));
+}
+
+#[test]
+fn sticky_hold_toggles_only_hold_actions() {
+    let (mut context, id, mut actions) = setup();
+    actions.set_sticky_hold(true);
+
+    tap(&mut context, id, &mut actions, Button::LeftTrigger);
+    assert!(actions.pressed(Action::Aim));
+    tap(&mut context, id, &mut actions, Button::South);
+    assert!(actions.pressed(Action::Aim));
+    assert!(!actions.pressed(Action::Jump));
+
+    context.mock_backend().press(id, Button::LeftTrigger);
+    step(&mut context, id, &mut actions);
+    assert!(actions.just_released(Action::Aim));
+    context.mock_backend().release(id, Button::LeftTrigger);
+    step(&mut context, id, &mut actions);
+    assert!(!actions.pressed(Action::Aim));
+}
+
+#[test]
+fn turning_sticky_hold_off_releases_latched_actions() {
+    let (mut context, id, mut actions) = setup();
+    actions.set_sticky_hold(true);
+    tap(&mut context, id, &mut actions, Button::LeftTrigger);
+    assert!(actions.pressed(Action::Aim));
+
+    actions.set_sticky_hold(false);
+    step(&mut context, id, &mut actions);
+    assert!(!actions.pressed(Action::Aim));
+}
+
+#[test]
+fn remapped_hold_actions_stay_hold_actions() {
+    let (mut context, id, mut actions) = setup();
+    actions.set_sticky_hold(true);
+    actions.unbind(Action::Aim);
+    actions.bind(Action::Aim, Binding::button(Button::RightShoulder));
+
+    tap(&mut context, id, &mut actions, Button::RightShoulder);
+    assert!(actions.pressed(Action::Aim));
+}
+
+#[test]
+fn hold_mode_of_an_action_overrides_sticky_hold() {
+    let (mut context, id, mut actions) = setup();
+    actions.set_hold_mode(Action::Jump, Some(HoldMode::Toggle));
+    assert_eq!(actions.hold_mode(Action::Jump), HoldMode::Toggle);
+    assert_eq!(actions.hold_mode(Action::Aim), HoldMode::Hold);
+
+    tap(&mut context, id, &mut actions, Button::South);
+    assert!(actions.pressed(Action::Jump));
+
+    actions.set_sticky_hold(true);
+    actions.set_hold_mode(Action::Aim, Some(HoldMode::Hold));
+    assert_eq!(actions.hold_mode(Action::Aim), HoldMode::Hold);
+    tap(&mut context, id, &mut actions, Button::LeftTrigger);
+    assert!(!actions.pressed(Action::Aim));
+
+    actions.set_hold_mode(Action::Aim, None);
+    assert_eq!(actions.hold_mode(Action::Aim), HoldMode::Toggle);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn hold_modes_are_saved_with_the_bindings() {
+    let (_, _, mut actions) = setup();
+    actions.set_hold_mode(Action::Jump, Some(HoldMode::Toggle));
+
+    let loaded = ActionMap::<Action>::from_toml(&actions.to_toml().unwrap()).unwrap();
+    assert_eq!(loaded.hold_mode(Action::Jump), HoldMode::Toggle);
+    assert_eq!(loaded.hold_mode(Action::Aim), HoldMode::Hold);
+    assert_eq!(
+        loaded.bindings(Action::Jump),
+        actions.bindings(Action::Jump)
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn sticky_hold_is_saved_with_the_bindings() {
+    let (_, _, mut actions) = setup();
+    actions.set_sticky_hold(true);
+
+    let loaded = ActionMap::<Action>::from_toml(&actions.to_toml().unwrap()).unwrap();
+    assert!(loaded.sticky_hold());
+    assert_eq!(loaded.bindings(Action::Aim), actions.bindings(Action::Aim));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn axis_bindings_are_saved_by_name() {
+    use fishsticks::Axis;
+
+    let (_, _, mut actions) = setup();
+    actions.bind(Action::Aim, Binding::axis(Axis::TriggerLeft));
+    actions.bind(
+        Action::Jump,
+        Binding::AxisDirection {
+            axis: Axis::LeftY,
+            positive: false,
+            threshold: 0.5,
+        },
+    );
+
+    let text = actions.to_toml().unwrap();
+    assert!(text.contains("axis = \"TriggerLeft\""), "{}", text);
+    assert!(text.contains("axis = \"LeftY\""), "{}", text);
+    let loaded = ActionMap::<Action>::from_toml(&text).unwrap();
+    assert_eq!(loaded.bindings(Action::Aim), actions.bindings(Action::Aim));
+    assert_eq!(
+        loaded.bindings(Action::Jump),
+        actions.bindings(Action::Jump)
+    );
+}
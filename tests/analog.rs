@@ -0,0 +1,102 @@
+//! Analog input processing, driven through the mock backend.
+
+use fishsticks::{Axis, GamepadContext};
+use std::time::Duration;
+
+#[test]
+fn prediction_ends_once_a_stick_is_held_still_past_the_horizon() {
+    let horizon = Duration::from_millis(50);
+    let mut context = GamepadContext::new().unwrap();
+    context.set_extrapolation(Some(horizon));
+    let id = context.mock_backend().connect("Xbox Controller");
+    context.update().unwrap();
+
+    context.mock_backend().set_axis(id, Axis::LeftX, 0.2);
+    context.update().unwrap();
+    context.mock_backend().advance(Duration::from_millis(10));
+    context.mock_backend().set_axis(id, Axis::LeftX, 0.4);
+    context.update().unwrap();
+
+    context.mock_backend().advance(Duration::from_millis(20));
+    context.update().unwrap();
+    let inputs = &context.gamepad(id).unwrap().analog_inputs;
+    assert!(inputs.predicted_value(Axis::LeftX) > inputs.value(Axis::LeftX));
+
+    context.mock_backend().advance(horizon);
+    context.update().unwrap();
+    let inputs = &context.gamepad(id).unwrap().analog_inputs;
+    assert_eq!(
+        inputs.predicted_value(Axis::LeftX),
+        inputs.value(Axis::LeftX)
+    );
+}
+
+#[test]
+fn calibrations_are_stored_by_guid_and_by_name_without_one() {
+    use fishsticks::analog::AxisCalibration;
+    use fishsticks::calibration::GamepadCalibration;
+    use fishsticks::guid::Guid;
+
+    let calibration = GamepadCalibration {
+        axes: vec![(
+            Axis::LeftX,
+            AxisCalibration {
+                center: 0.1,
+                min: -0.9,
+                max: 1.0,
+            },
+        )],
+    };
+    let (xbox, dualsense) = (
+        Guid::from_ids(0x045e, 0x0b12),
+        Guid::from_ids(0x054c, 0x0ce6),
+    );
+    let mut context = GamepadContext::new().unwrap();
+    let calibrated = context.mock_backend().connect_with_guid("Gamepad", xbox);
+    let unknown = context.mock_backend().connect("Gamepad");
+    context.update().unwrap();
+    context.set_calibration(calibrated, Some(calibration.clone()));
+    context.set_calibration(unknown, Some(calibration.clone()));
+
+    let mut keys: Vec<_> = context.calibrations().keys().cloned().collect();
+    keys.sort();
+    assert_eq!(keys, ["030000005e040000120b000000000000", "Gamepad"]);
+
+    let same_model = context.mock_backend().connect_with_guid("Other name", xbox);
+    let same_name = context
+        .mock_backend()
+        .connect_with_guid("Gamepad", dualsense);
+    context.update().unwrap();
+    let calibration_of = |id| {
+        context
+            .gamepad(id)
+            .unwrap()
+            .analog_inputs
+            .calibration(Axis::LeftX)
+    };
+    assert_eq!(calibration_of(same_model), calibration.axis(Axis::LeftX));
+    assert_eq!(calibration_of(same_name), None);
+}
+
+#[test]
+fn values_outside_the_deadzone_are_rescaled_unless_turned_off() {
+    let mut context = GamepadContext::builder().deadzone(0.2).build().unwrap();
+    let id = context.mock_backend().connect("Xbox Controller");
+    context.update().unwrap();
+    assert!(context.config().deadzone_rescaling);
+
+    context.mock_backend().set_axis(id, Axis::LeftX, 0.6);
+    context.mock_backend().set_axis(id, Axis::LeftY, 0.1);
+    context.update().unwrap();
+    let inputs = &context.gamepad(id).unwrap().analog_inputs;
+    assert!((inputs.value(Axis::LeftX) - 0.5).abs() < 1e-6);
+    assert_eq!(inputs.value(Axis::LeftY), 0.0);
+
+    context.set_deadzone_rescaling(false);
+    context.mock_backend().set_axis(id, Axis::LeftX, -0.6);
+    context.update().unwrap();
+    let inputs = &context.gamepad(id).unwrap().analog_inputs;
+    assert!((inputs.value(Axis::LeftX) + 0.6).abs() < 1e-6);
+    assert_eq!(inputs.value(Axis::LeftY), 0.0);
+    assert!(!context.config().deadzone_rescaling);
+}
@@ -0,0 +1,91 @@
+//! Applying a [`Config`] to a live context, driven through the mock backend.
+
+use fishsticks::config::{Config, Haptics, Setting};
+use fishsticks::digital::KeyRepeat;
+use fishsticks::trigger::{Trigger, TriggerEffect};
+use fishsticks::GamepadContext;
+use std::time::Duration;
+
+fn config() -> Config {
+    Config {
+        repeat: Some(KeyRepeat::default()),
+        debounce: Some(Duration::from_millis(20)),
+        haptics: Haptics {
+            rumble_intensity: 0.5,
+            trigger_effects: false,
+        },
+        ..Config::default()
+    }
+}
+
+#[test]
+fn validate_rejects_out_of_range_settings() {
+    assert!(config().validate().is_ok());
+
+    let mut invalid = config();
+    invalid.repeat = Some(KeyRepeat {
+        interval: Duration::ZERO,
+        ..KeyRepeat::default()
+    });
+    assert!(invalid.validate().is_err());
+
+    for intensity in [-0.1, 1.5, f32::NAN] {
+        let mut invalid = config();
+        invalid.haptics.rumble_intensity = intensity;
+        assert!(invalid.validate().is_err(), "intensity {}", intensity);
+    }
+}
+
+#[test]
+fn diff_lists_the_new_settings() {
+    assert_eq!(
+        Config::default().diff(&config()),
+        [Setting::Repeat, Setting::Debounce, Setting::Haptics]
+    );
+}
+
+#[test]
+fn applied_config_reaches_present_and_later_gamepads() {
+    let mut context = GamepadContext::new().unwrap();
+    let early = context.mock_backend().connect("Early gamepad");
+    context.update().unwrap();
+
+    context.apply_config(&config()).unwrap();
+    assert_eq!(context.config(), config());
+
+    let late = context.mock_backend().connect("Late gamepad");
+    context.update().unwrap();
+    for id in [early, late] {
+        let inputs = &context.gamepad(id).unwrap().digital_inputs;
+        assert_eq!(inputs.repeat(), Some(KeyRepeat::default()));
+        assert_eq!(inputs.debounce(), Some(Duration::from_millis(20)));
+    }
+}
+
+#[test]
+fn haptics_policy_scales_rumble_and_drops_trigger_effects() {
+    let mut context = GamepadContext::new().unwrap();
+    let id = context.mock_backend().connect("Gamepad");
+    context.update().unwrap();
+    context.apply_config(&config()).unwrap();
+
+    context
+        .rumble(id, 1.0, 0.5, Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(
+        context.mock_backend().rumble_intensities(id),
+        Some((0.5, 0.25))
+    );
+
+    let effect = TriggerEffect::Resistance {
+        start: 0.5,
+        strength: 1.0,
+    };
+    context
+        .set_trigger_effect(id, Trigger::Right, effect)
+        .unwrap();
+    assert_eq!(
+        context.mock_backend().trigger_effect(id, Trigger::Right),
+        Some(TriggerEffect::Off)
+    );
+}
@@ -0,0 +1,26 @@
+//! Overriding settings through environment variables.
+//!
+//! The environment is shared by the whole process, so everything is checked in one test.
+
+use fishsticks::GamepadContext;
+
+#[test]
+fn invalid_overrides_are_ignored() {
+    std::env::set_var("FISHSTICKS_BACKEND", "no-such-backend");
+    std::env::set_var("FISHSTICKS_DEADZONE", "garbage");
+    std::env::set_var("FISHSTICKS_DIGITAL_DEADZONE", "5.0");
+    std::env::set_var("FISHSTICKS_MAPPINGS", "/no/such/mappings.txt");
+
+    let context = GamepadContext::builder()
+        .deadzone(0.2)
+        .digital_deadzone(0.4)
+        .build()
+        .unwrap();
+    let config = context.config();
+    assert_eq!(config.deadzone, 0.2);
+    assert_eq!(config.digital_deadzone, 0.4);
+
+    std::env::set_var("FISHSTICKS_DEADZONE", "0.3");
+    let context = GamepadContext::builder().deadzone(0.2).build().unwrap();
+    assert_eq!(context.config().deadzone, 0.3);
+}
@@ -0,0 +1,228 @@
+//! Stress tests of local multiplayer with many gamepads, driven through the mock backend.
+
+use fishsticks::{Axis, Button, GamepadContext, GamepadEvent, GamepadId};
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn connected_ids(context: &GamepadContext) -> Vec<GamepadId> {
+    context.gamepads().map(|(id, _)| id).collect()
+}
+
+fn connect(context: &mut GamepadContext, count: usize) -> Vec<GamepadId> {
+    (0..count)
+        .map(|index| {
+            let name = format!("Gamepad {}", index);
+            context.mock_backend().connect(&name)
+        })
+        .collect()
+}
+
+#[test]
+fn sixteen_gamepads_connect_in_order() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 16);
+    context.update().unwrap();
+
+    assert_eq!(context.len(), 16);
+    assert_eq!(connected_ids(&context), ids);
+    assert_eq!(
+        context.events().collect::<Vec<_>>(),
+        ids.iter()
+            .map(|&id| GamepadEvent::Connected(id))
+            .collect::<Vec<_>>(),
+    );
+
+    let unique: HashSet<_> = ids.iter().copied().collect();
+    assert_eq!(unique.len(), ids.len());
+    let indices: HashSet<usize> = ids.iter().map(|&id| usize::from(id)).collect();
+    assert_eq!(indices.len(), ids.len());
+
+    for (index, &id) in ids.iter().enumerate() {
+        assert_eq!(
+            context.gamepad(id).unwrap().name(),
+            format!("Gamepad {}", index)
+        );
+    }
+
+    context.update().unwrap();
+    assert_eq!(context.events().count(), 0);
+}
+
+#[test]
+fn disconnects_in_mixed_order_keep_the_rest_in_place() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 12);
+    context.update().unwrap();
+
+    let removed = [ids[3], ids[0], ids[11], ids[7], ids[4]];
+    for &id in &removed {
+        context.mock_backend().disconnect(id);
+    }
+    context.update().unwrap();
+
+    assert_eq!(
+        context.events().collect::<Vec<_>>(),
+        removed
+            .iter()
+            .map(|&id| GamepadEvent::Disconnected(id))
+            .collect::<Vec<_>>(),
+    );
+    let remaining: Vec<_> = ids
+        .iter()
+        .copied()
+        .filter(|id| !removed.contains(id))
+        .collect();
+    assert_eq!(connected_ids(&context), remaining);
+    for &id in &removed {
+        assert!(context.gamepad(id).is_none());
+    }
+}
+
+#[test]
+fn interleaved_connects_and_disconnects() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 8);
+    context.update().unwrap();
+
+    let mut expected_events = Vec::new();
+    let mut expected_ids = ids.clone();
+    for (round, &old) in [ids[5], ids[1], ids[6], ids[2]].iter().enumerate() {
+        let new = context
+            .mock_backend()
+            .connect(&format!("Late gamepad {}", round));
+        context.mock_backend().disconnect(old);
+        expected_events.push(GamepadEvent::Connected(new));
+        expected_events.push(GamepadEvent::Disconnected(old));
+        expected_ids.retain(|&id| id != old);
+        expected_ids.push(new);
+    }
+    context.update().unwrap();
+
+    assert_eq!(context.events().collect::<Vec<_>>(), expected_events);
+    assert_eq!(connected_ids(&context), expected_ids);
+    assert_eq!(context.len(), 8);
+
+    let unique: HashSet<_> = expected_ids.iter().copied().collect();
+    assert_eq!(unique.len(), expected_ids.len());
+    for id in &ids[..] {
+        if !expected_ids.contains(id) {
+            assert!(!unique.contains(id));
+        }
+    }
+}
+
+#[test]
+fn gamepad_connected_and_disconnected_in_one_update() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 10);
+    let brief = context.mock_backend().connect("Brief gamepad");
+    context.mock_backend().disconnect(brief);
+    context.update().unwrap();
+
+    let mut expected: Vec<_> = ids.iter().map(|&id| GamepadEvent::Connected(id)).collect();
+    expected.push(GamepadEvent::Connected(brief));
+    expected.push(GamepadEvent::Disconnected(brief));
+    assert_eq!(context.events().collect::<Vec<_>>(), expected);
+    assert_eq!(connected_ids(&context), ids);
+}
+
+#[test]
+fn everyone_leaves_and_a_new_group_joins() {
+    let mut context = GamepadContext::new().unwrap();
+    let first = connect(&mut context, 16);
+    context.update().unwrap();
+
+    for &id in first.iter().rev() {
+        context.mock_backend().disconnect(id);
+    }
+    context.update().unwrap();
+    assert!(context.is_empty());
+    assert_eq!(
+        context.events().collect::<Vec<_>>(),
+        first
+            .iter()
+            .rev()
+            .map(|&id| GamepadEvent::Disconnected(id))
+            .collect::<Vec<_>>(),
+    );
+
+    let second: Vec<_> = (0..16)
+        .map(|index| {
+            context
+                .mock_backend()
+                .connect(&format!("Second gamepad {}", index))
+        })
+        .collect();
+    context.update().unwrap();
+    assert_eq!(connected_ids(&context), second);
+    assert!(second.iter().all(|id| !first.contains(id)));
+}
+
+#[test]
+fn inputs_reach_the_right_gamepad_among_many() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 16);
+    context.update().unwrap();
+
+    for (index, &id) in ids.iter().enumerate() {
+        if index % 2 == 1 {
+            context.mock_backend().press(id, Button::South);
+        }
+        if index % 3 == 0 {
+            context.mock_backend().set_axis(id, Axis::LeftX, 1.0);
+        }
+    }
+    context.mock_backend().disconnect(ids[9]);
+    context.update().unwrap();
+
+    for (index, &id) in ids.iter().enumerate() {
+        let Some(gamepad) = context.gamepad(id) else {
+            assert_eq!(index, 9);
+            continue;
+        };
+        assert_eq!(
+            gamepad.digital_inputs.activated(Button::South),
+            index % 2 == 1,
+            "gamepad {}",
+            index
+        );
+        assert_eq!(
+            gamepad.analog_inputs.value(Axis::LeftX) > 0.5,
+            index % 3 == 0,
+            "gamepad {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn player_rumble_reaches_each_of_their_gamepads() {
+    let mut context = GamepadContext::new().unwrap();
+    let ids = connect(&mut context, 8);
+    context.update().unwrap();
+
+    let first = context.players_mut().create_player();
+    let second = context.players_mut().create_player();
+    for &id in &ids[..3] {
+        context.players_mut().assign(first, id);
+    }
+    context.players_mut().assign(second, ids[3]);
+
+    first
+        .rumble(&mut context, 1.0, 0.5, Duration::from_secs(1))
+        .unwrap();
+    for (index, &id) in ids.iter().enumerate() {
+        let expected = (index < 3).then_some((1.0, 0.5));
+        assert_eq!(
+            context.mock_backend().rumble_intensities(id),
+            expected,
+            "gamepad {}",
+            index
+        );
+    }
+
+    first.stop_rumble(&mut context).unwrap();
+    for &id in &ids[..3] {
+        assert_eq!(context.mock_backend().rumble_intensities(id), None);
+    }
+}
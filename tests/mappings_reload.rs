@@ -0,0 +1,74 @@
+//! Reloading a watched mappings file, driven through the mock backend.
+
+use fishsticks::log::LogEntry;
+use fishsticks::GamepadContext;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const MAPPING: &str = "03000000000000000100000000000000,Test pad,a:b0,b:b1,platform:Linux,\n";
+const FIXED_MAPPING: &str = "03000000000000000100000000000000,Test pad,a:b1,b:b0,platform:Linux,\n";
+
+// The interval the file is checked at, on the clock of the mock backend.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+fn errors(context: &GamepadContext) -> usize {
+    context
+        .event_log()
+        .unwrap()
+        .entries()
+        .filter(|(_, entry)| matches!(entry, LogEntry::Error(_)))
+        .count()
+}
+
+fn reloads(context: &GamepadContext) -> usize {
+    context
+        .event_log()
+        .unwrap()
+        .entries()
+        .filter(|(_, entry)| matches!(entry, LogEntry::Mapping(message) if message.contains("reloaded")))
+        .count()
+}
+
+fn check(context: &mut GamepadContext) {
+    context.mock_backend().advance(CHECK_INTERVAL);
+    context.update().unwrap();
+}
+
+#[test]
+fn malformed_reload_is_logged_and_retried() {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "fishsticks-mappings-reload-{}.txt",
+        std::process::id()
+    ));
+    std::fs::write(&path, MAPPING).unwrap();
+    let mut context = GamepadContext::builder()
+        .env_overrides(false)
+        .mappings(&path)
+        .watch_mappings(true)
+        .event_log(Duration::from_secs(60))
+        .build()
+        .unwrap();
+
+    std::fs::write(&path, "not a mapping\n").unwrap();
+    // The file is only checked once the interval has passed.
+    context.update().unwrap();
+    assert_eq!(errors(&context), 0);
+    check(&mut context);
+    assert_eq!(errors(&context), 1);
+    assert_eq!(reloads(&context), 0);
+
+    // The file has not changed, but failed to load, so it is tried again.
+    check(&mut context);
+    assert_eq!(errors(&context), 2);
+
+    std::fs::write(&path, FIXED_MAPPING).unwrap();
+    check(&mut context);
+    assert_eq!(errors(&context), 2);
+    assert_eq!(reloads(&context), 1);
+
+    // Once loaded, an unchanged file is left alone.
+    check(&mut context);
+    assert_eq!(reloads(&context), 1);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,125 @@
+//! The built-in controls of the Steam Deck, driven through the mock backend.
+
+use fishsticks::motion::MotionSensor;
+use fishsticks::prompt::{prompt_key, DeviceKind};
+use fishsticks::touchpad::TouchFinger;
+use fishsticks::{Button, GamepadContext, GamepadId};
+
+fn connect_deck(context: &mut GamepadContext) -> GamepadId {
+    let id = context.mock_backend().connect("Steam Deck");
+    context.update().unwrap();
+    id
+}
+
+fn finger(slot: usize, x: f32, y: f32) -> TouchFinger {
+    TouchFinger {
+        slot,
+        x,
+        y,
+        pressure: 1.0,
+    }
+}
+
+#[test]
+fn deck_is_detected_with_two_trackpads() {
+    let mut context = GamepadContext::new().unwrap();
+    let deck = connect_deck(&mut context);
+    let other = context.mock_backend().connect("Xbox Controller");
+    context.update().unwrap();
+
+    let gamepad = context.gamepad(deck).unwrap();
+    assert_eq!(gamepad.device_kind(), DeviceKind::SteamDeck);
+    let indices: Vec<_> = gamepad
+        .touchpads()
+        .map(|touchpad| touchpad.index())
+        .collect();
+    assert_eq!(indices, [0, 1]);
+    assert_eq!(context.gamepad(other).unwrap().touchpads().count(), 1);
+}
+
+#[test]
+fn trackpads_report_touches_and_clicks_separately() {
+    let mut context = GamepadContext::new().unwrap();
+    let deck = connect_deck(&mut context);
+
+    context
+        .mock_backend()
+        .touch_at(deck, 0, finger(0, 0.25, 0.5));
+    context
+        .mock_backend()
+        .touch_at(deck, 1, finger(0, 0.75, 0.5));
+    context
+        .mock_backend()
+        .touch_at(deck, 1, finger(1, 0.5, 0.1));
+    context.mock_backend().click_touchpad(deck, 1, true);
+    context.update().unwrap();
+
+    let gamepad = context.gamepad(deck).unwrap();
+    let [left, right]: [_; 2] = gamepad.touchpads().collect::<Vec<_>>().try_into().unwrap();
+    assert_eq!(left.finger_count(), 1);
+    assert_eq!(left.finger(0).unwrap().x, 0.25);
+    assert!(!left.pressed());
+    assert_eq!(right.finger_count(), 2);
+    assert_eq!(right.finger(1).unwrap().y, 0.1);
+    assert!(right.pressed());
+
+    context.mock_backend().lift_at(deck, 1, 0);
+    context.mock_backend().click_touchpad(deck, 1, false);
+    context.mock_backend().press(deck, Button::Touchpad);
+    context.update().unwrap();
+
+    let gamepad = context.gamepad(deck).unwrap();
+    let left = gamepad.touchpad().unwrap();
+    let right = gamepad.touchpads().nth(1).unwrap();
+    assert!(left.pressed());
+    assert_eq!(right.finger_count(), 1);
+    assert!(!right.pressed());
+}
+
+#[test]
+fn gyro_is_read_once_enabled() {
+    let mut context = GamepadContext::new().unwrap();
+    let deck = connect_deck(&mut context);
+    assert!(context
+        .gamepad(deck)
+        .unwrap()
+        .has_sensor(MotionSensor::Gyroscope));
+
+    context
+        .set_sensor_enabled(deck, MotionSensor::Gyroscope, true)
+        .unwrap();
+    context
+        .mock_backend()
+        .set_sensor(deck, MotionSensor::Gyroscope, [0.5, -1.0, 0.0]);
+    context.update().unwrap();
+    assert_eq!(
+        context.gamepad(deck).unwrap().gyro(),
+        Some([0.5, -1.0, 0.0])
+    );
+}
+
+#[test]
+fn deck_prompts_name_the_grips_and_trackpad() {
+    let keys: Vec<_> = [
+        Button::Paddle1,
+        Button::Paddle2,
+        Button::Paddle3,
+        Button::Paddle4,
+        Button::Misc,
+        Button::Touchpad,
+    ]
+    .into_iter()
+    .map(|button| prompt_key(button, DeviceKind::SteamDeck))
+    .collect();
+    assert_eq!(
+        keys,
+        [
+            "deck_r4",
+            "deck_l4",
+            "deck_r5",
+            "deck_l5",
+            "deck_quick_access",
+            "deck_left_trackpad"
+        ]
+    );
+}